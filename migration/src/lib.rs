@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 pub use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DatabaseConnection;
 
 mod m20251019_194941_create_connections_table;
 mod m20251025_205133_create_plans_table;
@@ -6,6 +9,19 @@ mod m20251025_220607_create_changesets_table;
 mod m20251103_185312_create_deployments_table;
 mod m20251105_220916_create_changes_table;
 mod m20251110_164816_create_rollbacks_table;
+mod m20251118_093012_add_rollback_chain_columns;
+mod m20251121_104530_add_changeset_verification_columns;
+mod m20251124_141200_create_approvals_table;
+mod m20251126_083000_create_deployment_locks_table;
+mod m20251128_091500_add_deployment_owner_columns;
+mod m20251201_101500_add_deployment_hook_executions;
+mod m20251203_090000_add_connection_kind;
+mod m20251205_090000_add_changeset_object_index;
+mod m20251207_090000_create_audit_events_table;
+mod m20251209_090000_create_plan_revisions_table;
+mod m20251211_090000_encrypt_connection_passwords;
+mod m20251213_090000_add_deployment_owner_user;
+mod m20251215_090000_add_changesets_deployment_fk;
 
 pub struct Migrator;
 
@@ -19,6 +35,41 @@ impl MigratorTrait for Migrator {
             Box::new(m20251103_185312_create_deployments_table::Migration),
             Box::new(m20251105_220916_create_changes_table::Migration),
             Box::new(m20251110_164816_create_rollbacks_table::Migration),
+            Box::new(m20251118_093012_add_rollback_chain_columns::Migration),
+            Box::new(m20251121_104530_add_changeset_verification_columns::Migration),
+            Box::new(m20251124_141200_create_approvals_table::Migration),
+            Box::new(m20251126_083000_create_deployment_locks_table::Migration),
+            Box::new(m20251128_091500_add_deployment_owner_columns::Migration),
+            Box::new(m20251201_101500_add_deployment_hook_executions::Migration),
+            Box::new(m20251203_090000_add_connection_kind::Migration),
+            Box::new(m20251205_090000_add_changeset_object_index::Migration),
+            Box::new(m20251207_090000_create_audit_events_table::Migration),
+            Box::new(m20251209_090000_create_plan_revisions_table::Migration),
+            Box::new(m20251211_090000_encrypt_connection_passwords::Migration),
+            Box::new(m20251213_090000_add_deployment_owner_user::Migration),
+            Box::new(m20251215_090000_add_changesets_deployment_fk::Migration),
         ]
     }
 }
+
+/// Encrypts connection passwords stored in plain text by earlier versions. The key is part
+/// of the application settings, so the application passes the sealer to [`up_with_sealer`].
+pub trait PasswordSealer: Send + Sync {
+    /// The stored form of each password, `None` for passwords that are stored as is.
+    fn seal_passwords(&self, passwords: &[String]) -> Result<Vec<Option<String>>, String>;
+}
+
+tokio::task_local! {
+    static PASSWORD_SEALER: Arc<dyn PasswordSealer>;
+}
+
+/// Runs the pending migrations with `sealer` encrypting the connection passwords.
+///
+/// Without a sealer, e.g. with [`Migrator::up`], the migration that encrypts them fails
+/// if there are any connections with a password.
+pub async fn up_with_sealer(
+    db: &DatabaseConnection,
+    sealer: Arc<dyn PasswordSealer>,
+) -> Result<(), DbErr> {
+    PASSWORD_SEALER.scope(sealer, Migrator::up(db, None)).await
+}
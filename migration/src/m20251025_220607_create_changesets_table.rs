@@ -1,4 +1,4 @@
-use sea_orm_migration::{prelude::*, schema::*};
+use sea_orm_migration::{prelude::*, schema::*, sea_orm::DbBackend};
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -6,40 +6,42 @@ pub struct Migration;
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        manager
-            .create_table(
-                Table::create()
-                    .table(Changesets::Table)
-                    .if_not_exists()
-                    .col(pk_auto(Changesets::Id))
-                    .col(integer(Changesets::DeploymentId).not_null())
-                    .col(string(Changesets::ObjectType).not_null())
-                    .col(string(Changesets::ObjectName).not_null())
-                    .col(string(Changesets::ObjectOwner).not_null())
-                    .col(timestamp_null(Changesets::SourceDdlTime))
-                    .col(text_null(Changesets::SourceDdl))
-                    .col(timestamp_null(Changesets::TargetDdlTime))
-                    .col(text_null(Changesets::TargetDdl))
-                    .col(string(Changesets::Status).not_null().default("IDLE"))
-                    .col(text_null(Changesets::Errors))
-                    .col(
-                        timestamp(Changesets::CreatedAt)
-                            .not_null()
-                            .default(Expr::current_timestamp()),
-                    )
-                    .col(timestamp_null(Changesets::UpdatedAt))
-                    .col(timestamp_null(Changesets::StartedAt))
-                    .col(timestamp_null(Changesets::EndedAt))
-                    .foreign_key(
-                        ForeignKey::create()
-                            .from(Changesets::Table, Changesets::DeploymentId)
-                            .to(Deployments::Table, Deployments::Id)
-                            .on_delete(ForeignKeyAction::Cascade)
-                            .on_update(ForeignKeyAction::Cascade),
-                    )
-                    .to_owned(),
+        let mut table = Table::create()
+            .table(Changesets::Table)
+            .if_not_exists()
+            .col(pk_auto(Changesets::Id))
+            .col(integer(Changesets::DeploymentId).not_null())
+            .col(string(Changesets::ObjectType).not_null())
+            .col(string(Changesets::ObjectName).not_null())
+            .col(string(Changesets::ObjectOwner).not_null())
+            .col(timestamp_null(Changesets::SourceDdlTime))
+            .col(text_null(Changesets::SourceDdl))
+            .col(timestamp_null(Changesets::TargetDdlTime))
+            .col(text_null(Changesets::TargetDdl))
+            .col(string(Changesets::Status).not_null().default("IDLE"))
+            .col(text_null(Changesets::Errors))
+            .col(
+                timestamp(Changesets::CreatedAt)
+                    .not_null()
+                    .default(Expr::current_timestamp()),
             )
-            .await?;
+            .col(timestamp_null(Changesets::UpdatedAt))
+            .col(timestamp_null(Changesets::StartedAt))
+            .col(timestamp_null(Changesets::EndedAt))
+            .to_owned();
+        // The deployments table is created by a later migration, only SQLite accepts the
+        // reference before it exists. Other backends get the foreign key from
+        // m20251215_090000_add_changesets_deployment_fk.
+        if manager.get_database_backend() == DbBackend::Sqlite {
+            table.foreign_key(
+                ForeignKey::create()
+                    .from(Changesets::Table, Changesets::DeploymentId)
+                    .to(Deployments::Table, Deployments::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade),
+            );
+        }
+        manager.create_table(table).await?;
 
         // Create index on deployment_id for faster queries
         manager
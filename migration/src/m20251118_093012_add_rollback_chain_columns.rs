@@ -0,0 +1,82 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Deployments::Table)
+                    .add_column(string_null(Deployments::Tag))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Tags are unique per plan, concurrent taggers can not both win
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_deployments_plan_tag")
+                    .table(Deployments::Table)
+                    .col(Deployments::PlanId)
+                    .col(Deployments::Tag)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rollbacks::Table)
+                    .add_column(string_null(Rollbacks::OperationId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rollbacks::Table)
+                    .drop_column(Rollbacks::OperationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_deployments_plan_tag")
+                    .table(Deployments::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Deployments::Table)
+                    .drop_column(Deployments::Tag)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Deployments {
+    Table,
+    PlanId,
+    Tag,
+}
+
+#[derive(DeriveIden)]
+enum Rollbacks {
+    Table,
+    OperationId,
+}
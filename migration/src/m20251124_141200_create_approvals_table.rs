@@ -0,0 +1,102 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Approvals::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Approvals::Id))
+                    .col(integer(Approvals::DeploymentId))
+                    .col(string(Approvals::ApprovedBy))
+                    .col(text_null(Approvals::Comment))
+                    .col(string(Approvals::Checksum))
+                    .col(timestamp(Approvals::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Approvals::Table, Approvals::DeploymentId)
+                            .to(Deployments::Table, Deployments::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .add_column(integer(Plans::RequiredApprovals).default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .add_column(integer(Connections::RequiredApprovals).default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .drop_column(Connections::RequiredApprovals)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .drop_column(Plans::RequiredApprovals)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Approvals::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Approvals {
+    Table,
+    Id,
+    DeploymentId,
+    ApprovedBy,
+    Comment,
+    Checksum,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Deployments {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    RequiredApprovals,
+}
+
+#[derive(DeriveIden)]
+enum Connections {
+    Table,
+    RequiredApprovals,
+}
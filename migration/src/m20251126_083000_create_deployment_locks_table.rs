@@ -0,0 +1,46 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeploymentLocks::Table)
+                    .if_not_exists()
+                    .col(pk_auto(DeploymentLocks::Id))
+                    // A target can only be deployed by one process at a time
+                    .col(integer_uniq(DeploymentLocks::TargetConnectionId))
+                    .col(integer_uniq(DeploymentLocks::PlanId))
+                    .col(string(DeploymentLocks::Operation))
+                    .col(integer(DeploymentLocks::OwnerPid))
+                    .col(string(DeploymentLocks::OwnerHost))
+                    .col(timestamp(DeploymentLocks::AcquiredAt))
+                    .col(timestamp(DeploymentLocks::HeartbeatAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DeploymentLocks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeploymentLocks {
+    Table,
+    Id,
+    TargetConnectionId,
+    PlanId,
+    Operation,
+    OwnerPid,
+    OwnerHost,
+    AcquiredAt,
+    HeartbeatAt,
+}
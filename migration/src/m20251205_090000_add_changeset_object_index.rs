@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Object history looks up every changeset of an object across deployments
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_changesets_object")
+                    .table(Changesets::Table)
+                    .col(Changesets::ObjectOwner)
+                    .col(Changesets::ObjectName)
+                    .col(Changesets::ObjectType)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_changesets_object")
+                    .table(Changesets::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Changesets {
+    Table,
+    ObjectOwner,
+    ObjectName,
+    ObjectType,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // No foreign keys, audit events must outlive pruned plans and deployments
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvents::Table)
+                    .if_not_exists()
+                    .col(pk_auto(AuditEvents::Id))
+                    .col(string(AuditEvents::Operation))
+                    .col(integer_null(AuditEvents::PlanId))
+                    .col(integer_null(AuditEvents::DeploymentId))
+                    .col(string(AuditEvents::Outcome).default("STARTED"))
+                    .col(text_null(AuditEvents::Error))
+                    .col(string(AuditEvents::OsUser))
+                    .col(string(AuditEvents::Hostname))
+                    .col(string(AuditEvents::LeafVersion))
+                    .col(text(AuditEvents::Arguments))
+                    .col(json(AuditEvents::Settings))
+                    .col(timestamp(AuditEvents::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(AuditEvents::EndedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_created_at")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_plan_id")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::PlanId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditEvents {
+    Table,
+    Id,
+    Operation,
+    PlanId,
+    DeploymentId,
+    Outcome,
+    Error,
+    OsUser,
+    Hostname,
+    LeafVersion,
+    Arguments,
+    Settings,
+    CreatedAt,
+    EndedAt,
+}
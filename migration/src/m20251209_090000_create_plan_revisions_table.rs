@@ -0,0 +1,138 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlanRevisions::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PlanRevisions::Id))
+                    .col(integer(PlanRevisions::PlanId))
+                    .col(integer(PlanRevisions::Revision))
+                    .col(string(PlanRevisions::Name))
+                    .col(integer(PlanRevisions::SourceConnectionId))
+                    .col(integer(PlanRevisions::TargetConnectionId))
+                    .col(text(PlanRevisions::Schemas))
+                    .col(text_null(PlanRevisions::ExcludeObjectTypes))
+                    .col(text_null(PlanRevisions::ExcludeObjectNames))
+                    .col(text_null(PlanRevisions::DisabledDropTypes))
+                    .col(boolean(PlanRevisions::DisableAllDrops))
+                    .col(boolean(PlanRevisions::FailFast))
+                    .col(boolean(PlanRevisions::DisableHooks))
+                    .col(json_null(PlanRevisions::Hooks))
+                    .col(integer(PlanRevisions::RequiredApprovals).default(0))
+                    .col(text_null(PlanRevisions::ChangedFields))
+                    .col(timestamp(PlanRevisions::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PlanRevisions::Table, PlanRevisions::PlanId)
+                            .to(Plans::Table, Plans::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_plan_revisions_plan_revision")
+                    .table(PlanRevisions::Table)
+                    .col(PlanRevisions::PlanId)
+                    .col(PlanRevisions::Revision)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Deployments keep their revision when they are purged from the plan's history,
+        // so there is no foreign key
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Deployments::Table)
+                    .add_column(integer_null(Deployments::PlanRevisionId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Earlier definitions of plans edited before revisions existed are lost, only the
+        // current one can be recorded. It becomes revision 1 of every plan, with
+        // `changed_fields` left NULL to mark it as reconstructed: older deployments point to
+        // it but may have been prepared with another definition.
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO plan_revisions (plan_id, revision, name, source_connection_id, \
+             target_connection_id, schemas, exclude_object_types, exclude_object_names, \
+             disabled_drop_types, disable_all_drops, fail_fast, disable_hooks, hooks, \
+             required_approvals, created_at) \
+             SELECT id, 1, name, source_connection_id, target_connection_id, schemas, \
+             exclude_object_types, exclude_object_names, disabled_drop_types, \
+             disable_all_drops, fail_fast, disable_hooks, hooks, required_approvals, created_at \
+             FROM plans",
+        )
+        .await?;
+        db.execute_unprepared(
+            "UPDATE deployments SET plan_revision_id = (SELECT r.id FROM plan_revisions r \
+             WHERE r.plan_id = deployments.plan_id AND r.revision = 1)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Deployments::Table)
+                    .drop_column(Deployments::PlanRevisionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PlanRevisions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlanRevisions {
+    Table,
+    Id,
+    PlanId,
+    Revision,
+    Name,
+    SourceConnectionId,
+    TargetConnectionId,
+    Schemas,
+    ExcludeObjectTypes,
+    ExcludeObjectNames,
+    DisabledDropTypes,
+    DisableAllDrops,
+    FailFast,
+    DisableHooks,
+    Hooks,
+    RequiredApprovals,
+    ChangedFields,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Deployments {
+    Table,
+    PlanRevisionId,
+}
@@ -0,0 +1,72 @@
+use sea_orm_migration::{prelude::*, sea_orm::ConnectionTrait};
+
+use crate::PASSWORD_SEALER;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let rows = db
+            .query_all(
+                &Query::select()
+                    .columns([Connections::Id, Connections::Password])
+                    .from(Connections::Table)
+                    .to_owned(),
+            )
+            .await?;
+        let mut ids = Vec::new();
+        let mut passwords = Vec::new();
+        for row in rows {
+            let password: String = row.try_get("", "password")?;
+            if !password.is_empty() {
+                ids.push(row.try_get::<i32>("", "id")?);
+                passwords.push(password);
+            }
+        }
+        if passwords.is_empty() {
+            return Ok(());
+        }
+
+        // Passwords stored in plain text by earlier versions are encrypted with the key of
+        // the application settings, which only the application can provide
+        let sealer = PASSWORD_SEALER.try_with(|sealer| sealer.clone()).map_err(|_| {
+            DbErr::Migration(
+                "Connection passwords must be encrypted with the configured key, run `leaf db migrate up`"
+                    .to_string(),
+            )
+        })?;
+        let sealed = sealer
+            .seal_passwords(&passwords)
+            .map_err(|e| DbErr::Migration(format!("Failed to encrypt connection passwords: {}", e)))?;
+
+        for (id, password) in ids.into_iter().zip(sealed) {
+            let Some(password) = password else {
+                continue;
+            };
+            db.execute(
+                &Query::update()
+                    .table(Connections::Table)
+                    .value(Connections::Password, password)
+                    .and_where(Expr::col(Connections::Id).eq(id))
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Encrypted passwords are read by every version since this one, they stay encrypted
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Connections {
+    Table,
+    Id,
+    Password,
+}
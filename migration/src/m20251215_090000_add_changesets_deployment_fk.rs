@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Foreign key of changesets to their deployment on the backends that could not create it
+/// with the changesets table, SQLite databases already have it.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() == DbBackend::Sqlite {
+            return Ok(());
+        }
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(CHANGESETS_DEPLOYMENT_FK)
+                    .from(Changesets::Table, Changesets::DeploymentId)
+                    .to(Deployments::Table, Deployments::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() == DbBackend::Sqlite {
+            return Ok(());
+        }
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name(CHANGESETS_DEPLOYMENT_FK)
+                    .table(Changesets::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+const CHANGESETS_DEPLOYMENT_FK: &str = "fk_changesets_deployment_id";
+
+#[derive(DeriveIden)]
+enum Changesets {
+    Table,
+    DeploymentId,
+}
+
+#[derive(DeriveIden)]
+enum Deployments {
+    Table,
+    Id,
+}
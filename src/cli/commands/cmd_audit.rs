@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use clap::Subcommand;
+use colored::*;
+use tabled::{
+    Table, Tabled,
+    settings::{
+        Alignment, Modify, Style, Width,
+        object::{Columns, Rows},
+    },
+};
+
+use crate::{
+    cli::{Context, commands::ExitOnErr},
+    types::OutputFormat,
+    utils::parsers::parse_cutoff_date,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum AuditCommands {
+    /// List who prepared, applied, rolled back or reset plans
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf audit list --since 2025.12.01 --plan demo3
+    This will list every prepare, apply, run, rollback and reset of plan `demo3` since
+    December 1st, newest first, with the OS user, hostname, leaf version, outcome and command.
+
+    # leaf audit list --since 2025.12.01 --format json > audit.json
+    This will export the events with their masked command line arguments and settings.
+    "#)]
+    List {
+        /// Only list events on or after this date, format 'YYYY.MM.DD[:HH24.MI.SS]'
+        #[arg(long, value_parser = parse_cutoff_date)]
+        since: Option<NaiveDateTime>,
+
+        /// Only list events of this plan
+        #[arg(long, short)]
+        plan: Option<String>,
+
+        /// Maximum number of events
+        #[arg(long, default_value_t = 50)]
+        limit: u64,
+
+        /// Output format, `json` includes the arguments and settings of every event
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Tabled)]
+struct AuditEventRow {
+    #[tabled(rename = "ID")]
+    id: String,
+
+    #[tabled(rename = "Time")]
+    created_at: String,
+
+    #[tabled(rename = "Operation")]
+    operation: String,
+
+    #[tabled(rename = "Plan")]
+    plan: String,
+
+    #[tabled(rename = "Deployment")]
+    deployment_id: String,
+
+    #[tabled(rename = "Outcome")]
+    outcome: String,
+
+    #[tabled(rename = "User")]
+    user: String,
+
+    #[tabled(rename = "Version")]
+    leaf_version: String,
+
+    #[tabled(rename = "Command")]
+    command: String,
+}
+
+pub async fn execute(action: &AuditCommands, ctx: &Context<'_>) {
+    match action {
+        AuditCommands::List {
+            since,
+            plan,
+            limit,
+            format,
+        } => list(*since, plan.as_deref(), *limit, *format, ctx).await,
+    }
+}
+
+async fn list(
+    since: Option<NaiveDateTime>,
+    plan: Option<&str>,
+    limit: u64,
+    format: OutputFormat,
+    ctx: &Context<'_>,
+) {
+    let plan_id = match plan {
+        Some(name) => Some(
+            ctx.services
+                .plan_service
+                .find_by_name(name)
+                .await
+                .exit_on_err(&format!("❌ Failed to find plan '{}'", name))
+                .unwrap_or_else(|| {
+                    eprintln!("❌ Plan '{}' not found", name);
+                    std::process::exit(1);
+                })
+                .id,
+        ),
+        None => None,
+    };
+
+    let events = ctx
+        .services
+        .audit_service
+        .find(since, plan_id, Some(limit))
+        .await
+        .exit_on_err("❌ Failed to list audit events");
+
+    if format == OutputFormat::Json {
+        let json =
+            serde_json::to_string_pretty(&events).exit_on_err("❌ Failed to serialize events");
+        println!("{}", json);
+        return;
+    }
+
+    if events.is_empty() {
+        println!("✅ No audit events found");
+        return;
+    }
+
+    // Events outlive pruned plans, those are shown by ID
+    let plan_names: HashMap<i32, String> = ctx
+        .services
+        .plan_service
+        .get_all()
+        .await
+        .exit_on_err("❌ Failed to fetch plans")
+        .into_iter()
+        .map(|p| (p.id, p.name))
+        .collect();
+    let dash = || "-".bright_black().to_string();
+
+    let rows: Vec<AuditEventRow> = events
+        .iter()
+        .map(|e| AuditEventRow {
+            id: e.id.to_string(),
+            created_at: e.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            operation: e.operation.to_string(),
+            plan: e
+                .plan_id
+                .map(|id| {
+                    plan_names
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| id.to_string())
+                })
+                .unwrap_or_else(dash),
+            deployment_id: e
+                .deployment_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(dash),
+            outcome: e.outcome.to_colored_string(),
+            user: format!("{}@{}", e.os_user, e.hostname),
+            leaf_version: e.leaf_version.clone(),
+            command: e
+                .arguments
+                .0
+                .iter()
+                .skip(1)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(" "),
+        })
+        .collect();
+
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .with(Modify::new(Columns::one(8)).with(Width::truncate(60).suffix("...")))
+        .to_string();
+    println!("{}", table);
+}
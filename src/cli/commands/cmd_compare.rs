@@ -0,0 +1,218 @@
+use clap::Args;
+use colored::*;
+use tabled::{
+    Table, Tabled,
+    settings::{Alignment, Modify, Style, object::Rows},
+};
+
+use crate::{
+    cli::{
+        Context,
+        commands::{ExitOnErr, ProgressRenderer, shared::colored_diff},
+    },
+    delta::{CompareStatus, summarize},
+    services::CompareOptions,
+    utils::ProgressReporter,
+};
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+EXAMPLES:
+    # leaf compare --source dev --target prod --schemas HR,SALES
+    This will print how many objects of each type exist only in `dev`, only in `prod`, differ or are
+    identical, followed by the objects that are not in sync. Nothing is written to the repository.
+
+    # leaf compare --source dev --target prod --schemas HR --types VIEW,PACKAGE --diff
+    This will compare views and packages only and print a unified diff of the DDL of every object
+    that differs, from the target to the source.
+    "#)]
+pub struct CompareCommand {
+    /// Source connection name
+    #[arg(long, required = true)]
+    source: String,
+
+    /// Target connection name
+    #[arg(long, required = true)]
+    target: String,
+
+    /// Comma-separated list of schemas to compare
+    #[arg(long, required = true, value_delimiter = ',')]
+    schemas: Vec<String>,
+
+    /// Comma-separated list of object types to compare, all types by default
+    #[arg(long, value_delimiter = ',')]
+    types: Vec<String>,
+
+    /// Comma-separated list of object types to exclude
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_types: Vec<String>,
+
+    /// Comma-separated list of object names to exclude
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_names: Vec<String>,
+
+    /// Print the DDL diff of every object that is not in sync
+    #[arg(long, default_value_t = false)]
+    diff: bool,
+}
+
+#[derive(Tabled)]
+struct SummaryRow {
+    #[tabled(rename = "Type")]
+    object_type: String,
+
+    #[tabled(rename = "Only in Source")]
+    only_in_source: String,
+
+    #[tabled(rename = "Only in Target")]
+    only_in_target: String,
+
+    #[tabled(rename = "Different")]
+    different: String,
+
+    #[tabled(rename = "Identical")]
+    identical: String,
+}
+
+#[derive(Tabled)]
+struct ObjectRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "Type")]
+    object_type: String,
+
+    #[tabled(rename = "Object")]
+    object: String,
+
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+fn colored_count(count: usize, color: Color) -> String {
+    if count == 0 {
+        count.to_string().bright_black().to_string()
+    } else {
+        count.to_string().color(color).to_string()
+    }
+}
+
+fn colored_status(status: CompareStatus) -> String {
+    let text = status.to_string();
+    match status {
+        CompareStatus::OnlyInSource => text.green().to_string(),
+        CompareStatus::OnlyInTarget => text.red().to_string(),
+        CompareStatus::Different => text.yellow().to_string(),
+        CompareStatus::Identical => text.bright_black().to_string(),
+    }
+}
+
+pub async fn execute(args: &CompareCommand, ctx: &Context<'_>) {
+    let options = CompareOptions {
+        schemas: args.schemas.clone(),
+        object_types: args.types.clone(),
+        exclude_object_types: ctx
+            .settings
+            .rules
+            .combined_exclude_object_types(Some(args.exclude_object_types.clone())),
+        exclude_object_names: ctx
+            .settings
+            .rules
+            .combined_exclude_object_names(Some(args.exclude_object_names.clone())),
+    };
+
+    let (progress, tx) = ProgressRenderer::new(None);
+    let res = ctx
+        .services
+        .connection_service
+        .compare(
+            &args.source,
+            &args.target,
+            options,
+            &ProgressReporter::new(Some(tx)),
+        )
+        .await;
+    progress.finish().await;
+
+    let comparisons = res.exit_on_err(&format!(
+        "❌ Failed to compare '{}' and '{}'",
+        args.source, args.target
+    ));
+
+    println!(
+        "{}",
+        format!("=== Compare '{}' → '{}' ===", args.source, args.target).blue()
+    );
+
+    if comparisons.is_empty() {
+        println!("✅ No objects found");
+        return;
+    }
+
+    let summary_rows: Vec<SummaryRow> = summarize(&comparisons)
+        .into_iter()
+        .map(|s| SummaryRow {
+            object_type: s.object_type.clone(),
+            only_in_source: colored_count(s.only_in_source, Color::Green),
+            only_in_target: colored_count(s.only_in_target, Color::Red),
+            different: colored_count(s.different, Color::Yellow),
+            identical: s.identical.to_string(),
+        })
+        .collect();
+    let table = Table::new(summary_rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::right()))
+        .to_string();
+    println!("{}", table);
+
+    let out_of_sync: Vec<_> = comparisons
+        .iter()
+        .filter(|c| c.status != CompareStatus::Identical)
+        .collect();
+    if out_of_sync.is_empty() {
+        println!(
+            "✅ '{}' and '{}' are in sync ({} object(s))",
+            args.source,
+            args.target,
+            comparisons.len()
+        );
+        return;
+    }
+
+    let object_rows: Vec<ObjectRow> = out_of_sync
+        .iter()
+        .enumerate()
+        .map(|(i, c)| ObjectRow {
+            index: (i + 1).to_string().bright_black().to_string(),
+            object_type: c.object_type.clone(),
+            object: format!("{}.{}", c.owner, c.object_name),
+            status: colored_status(c.status),
+        })
+        .collect();
+    let table = Table::new(object_rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
+
+    if args.diff {
+        for comparison in &out_of_sync {
+            let object = format!(
+                "{} {}.{}",
+                comparison.object_type, comparison.owner, comparison.object_name
+            );
+            if let Some(diff) = comparison.ddl_diff(
+                &format!("{} ({})", object, args.source),
+                &format!("{} ({})", object, args.target),
+            ) {
+                println!("\n{}", colored_diff(&diff));
+            }
+        }
+    }
+
+    println!(
+        "⚠️ {} of {} object(s) are not in sync",
+        out_of_sync.len(),
+        comparisons.len()
+    );
+}
@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use colored::Colorize;
+use inquire::Confirm;
+
+use crate::{
+    cli::{Context, commands::ExitOnErr},
+    declarative::{ConfigChange, ConfigDiff, ConfigFormat, FieldChange, LeafConfig},
+};
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+EXAMPLES:
+    # leaf apply-config --dry-run
+    This will print the connections and plans `leaf.yaml` creates, updates or deletes
+    without changing the repository.
+
+    # leaf apply-config -f envs/prod.toml --prune --yes
+    This will create and update the connections and plans of `envs/prod.toml` and delete
+    the ones the file does not describe, without asking for confirmation.
+
+CONFIG FILE:
+    connections:
+      - name: dev
+        username: hr
+        password: env:LEAF_DEV_PASSWORD      # or file:/run/secrets/dev
+        connection_string: dev:1521/ORCL
+      - name: ddl
+        path: ./ddl                          # directory connection
+    rules:                                   # combined into every plan
+      exclude_object_types: [JOB]
+      disable_all_drops: true
+    hooks:                                   # for plans without their own
+      post_apply_deployment: ["begin my_user.notify('{{ plan }}'); end;"]
+    plans:
+      - name: release
+        source: dev
+        target: prod
+        schemas: [HR]
+        required_approvals: 1
+    "#)]
+pub struct ApplyConfigCommand {
+    /// Config file, `.yaml`, `.yml` or `.toml`
+    #[arg(long, short, default_value = "leaf.yaml")]
+    pub file: PathBuf,
+
+    /// Only print the changes
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Delete the connections and plans the file does not describe
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Apply without asking for confirmation
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+EXAMPLES:
+    # leaf export-config --output leaf.yaml
+    This will describe the connections and plans of the repository in `leaf.yaml`.
+    Passwords are written as `env:LEAF_<NAME>_PASSWORD` references, never in plain text.
+    Connections whose password is already a reference keep it.
+
+    # leaf export-config
+    This will print the config as YAML.
+    "#)]
+pub struct ExportConfigCommand {
+    /// Config file to write, `.yaml`, `.yml` or `.toml`, printed as YAML by default
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Overwrite the output file if it exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+pub async fn apply(args: &ApplyConfigCommand, ctx: &Context<'_>) {
+    let config = LeafConfig::load(&args.file).exit_on_err("❌ Failed to load config");
+    let base_dir = args.file.parent().unwrap_or(Path::new(""));
+
+    let diff = ctx
+        .services
+        .config_service
+        .diff(&config, base_dir, args.prune)
+        .await
+        .exit_on_err("❌ Failed to compare config with the repository");
+
+    print_diff(&diff);
+    if diff.is_empty() {
+        println!("✅ Repository is up to date with {}", args.file.display());
+        return;
+    }
+    if args.dry_run {
+        println!("✅ Dry run, no changes were applied");
+        return;
+    }
+
+    let proceed = args.yes
+        || Confirm::new(&format!("Apply {} change(s)?", diff.changes.len()))
+            .with_default(false)
+            .prompt()
+            .unwrap_or(false);
+    if !proceed {
+        println!("✅ Aborted");
+        return;
+    }
+
+    ctx.services
+        .config_service
+        .apply(&diff)
+        .await
+        .exit_on_err("❌ Failed to apply config");
+    println!("✅ Applied {} change(s)", diff.changes.len());
+}
+
+pub async fn export(args: &ExportConfigCommand, ctx: &Context<'_>) {
+    let config = ctx
+        .services
+        .config_service
+        .export()
+        .await
+        .exit_on_err("❌ Failed to export config");
+
+    let Some(output) = &args.output else {
+        let content = config
+            .to_string(ConfigFormat::Yaml)
+            .exit_on_err("❌ Failed to serialize config");
+        print!("{}", content);
+        return;
+    };
+
+    if output.exists() && !args.force {
+        eprintln!(
+            "❌ '{}' already exists, use --force to overwrite it",
+            output.display()
+        );
+        std::process::exit(1);
+    }
+    let format = ConfigFormat::from_path(output).exit_on_err("❌ Failed to export config");
+    let content = config
+        .to_string(format)
+        .exit_on_err("❌ Failed to serialize config");
+    std::fs::write(output, content)
+        .exit_on_err(&format!("❌ Failed to write '{}'", output.display()));
+
+    println!(
+        "✅ Exported {} connection(s) and {} plan(s) to {}",
+        config.connections.len(),
+        config.plans.len(),
+        output.display()
+    );
+    let passwords: Vec<&str> = config
+        .connections
+        .iter()
+        .filter_map(|c| c.password.as_deref())
+        .filter_map(|p| p.strip_prefix("env:"))
+        .collect();
+    if !passwords.is_empty() {
+        println!(
+            "Set {} before running `leaf apply-config`",
+            passwords.join(", ").bold()
+        );
+    }
+}
+
+fn print_diff(diff: &ConfigDiff) {
+    for change in &diff.changes {
+        match change {
+            ConfigChange::CreateConnection(c) => {
+                println!("{}", format!("+ connection {}", c.name).green())
+            }
+            ConfigChange::UpdateConnection { connection, fields } => {
+                println!("{}", format!("~ connection {}", connection.name).yellow());
+                print_fields(fields);
+            }
+            ConfigChange::DeleteConnection(name) => {
+                println!("{}", format!("- connection {}", name).red())
+            }
+            ConfigChange::CreatePlan(p) => println!("{}", format!("+ plan {}", p.name).green()),
+            ConfigChange::UpdatePlan { plan, fields } => {
+                println!("{}", format!("~ plan {}", plan.name).yellow());
+                print_fields(fields);
+            }
+            ConfigChange::DeletePlan(name) => println!("{}", format!("- plan {}", name).red()),
+        }
+    }
+
+    if diff.unchanged > 0 {
+        println!(
+            "{}",
+            format!("{} connection(s) and plan(s) unchanged", diff.unchanged).bright_black()
+        );
+    }
+    if !diff.unmanaged.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Not in the file, kept without --prune: {}",
+                diff.unmanaged.join(", ")
+            )
+            .bright_black()
+        );
+    }
+}
+
+fn print_fields(fields: &[FieldChange]) {
+    for field in fields {
+        println!(
+            "    {}: {} → {}",
+            field.field,
+            field.from.red(),
+            field.to.green()
+        );
+    }
+}
@@ -1,4 +1,10 @@
-use crate::cli::{Context, commands::ExitOnErr};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cli::{Context, commands::ExitOnErr},
+    services::ConnectionEdit,
+    types::ConnectionKind,
+};
 use clap::Subcommand;
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
@@ -20,13 +26,88 @@ pub enum ConnectionCommands {
         #[arg(long, required = true)]
         username: String,
 
-        /// Password
+        /// Password, stored encrypted, or a reference resolved when connecting:
+        /// `env:VAR`, `file:PATH` or `cmd:COMMAND`
         #[arg(long, required = true)]
         password: String,
 
         /// Connection string
         #[arg(long, required = true)]
         connection_string: String,
+
+        /// Number of distinct approvals a deployment targeting this connection needs
+        #[arg(long, default_value_t = 0)]
+        required_approvals: i32,
+    },
+
+    /// Create a connection that reads objects from a directory of DDL files
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf connections add-directory --name repo --path ./schema
+    The directory has the layout written by `leaf snapshot`: `<SCHEMA>/<TYPE>/<NAME>.sql` and an
+    optional `manifest.json`. Use the connection as the source of a plan to deploy the files to a
+    database. Objects are selected by cutoff date using the time of the last git commit of each
+    file, or its modification time outside of git and for uncommitted changes.
+    "#)]
+    AddDirectory {
+        /// Connection name (unique)
+        #[arg(long, required = true)]
+        name: String,
+
+        /// Directory of DDL files
+        #[arg(long, required = true, value_name = "DIR")]
+        path: PathBuf,
+    },
+
+    /// Update a connection in place, plans referencing it are kept
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf connections update prod --password new_password
+    This will test the connection with the new password and save it if the test passes.
+
+    # leaf connections update dev --rename dev_eu
+    This will rename the connection, plans using it now show `dev_eu` as their source or target.
+    "#)]
+    Update {
+        #[arg(required = true)]
+        name: String,
+
+        /// New connection name (unique)
+        #[arg(long)]
+        rename: Option<String>,
+
+        /// New username
+        #[arg(long)]
+        username: Option<String>,
+
+        /// New password, or a reference: `env:VAR`, `file:PATH` or `cmd:COMMAND`
+        #[arg(long)]
+        password: Option<String>,
+
+        /// New connection string
+        #[arg(long)]
+        connection_string: Option<String>,
+
+        /// Save new credentials without testing the connection
+        #[arg(long)]
+        skip_test: bool,
+    },
+
+    /// Encrypt the stored passwords with a new key
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf connections rotate-key
+    This will generate a new key, re-encrypt the passwords with it and replace the key file
+    (`leaf.key` or LEAF__SECRETS__KEY_FILE) once every password is re-encrypted.
+
+    # leaf connections rotate-key --key-file /etc/leaf/leaf.key
+    This will write the new key to another file, e.g. when the key is set by LEAF__SECRETS__KEY.
+    Point LEAF__SECRETS__KEY_FILE to it afterwards.
+    "#)]
+    RotateKey {
+        /// File to write the new key to, the configured key file by default
+        #[arg(long, value_name = "FILE")]
+        key_file: Option<PathBuf>,
     },
 
     /// Test a connection
@@ -71,6 +152,9 @@ struct ConnectionRow {
     #[tabled(rename = "Name")]
     name: String,
 
+    #[tabled(rename = "Kind")]
+    kind: String,
+
     #[tabled(rename = "Username")]
     username: String,
 
@@ -85,7 +169,36 @@ pub async fn execute(action: &ConnectionCommands, ctx: &Context<'_>) {
             username,
             password,
             connection_string,
-        } => add(ctx, name, username, password, connection_string).await,
+            required_approvals,
+        } => {
+            add(
+                ctx,
+                name,
+                username,
+                password,
+                connection_string,
+                *required_approvals,
+            )
+            .await
+        }
+        ConnectionCommands::AddDirectory { name, path } => add_directory(name, path, ctx).await,
+        ConnectionCommands::Update {
+            name,
+            rename,
+            username,
+            password,
+            connection_string,
+            skip_test,
+        } => {
+            let edit = ConnectionEdit {
+                rename: rename.clone(),
+                username: username.clone(),
+                password: password.clone(),
+                connection_string: connection_string.clone(),
+            };
+            update(name, &edit, *skip_test, ctx).await
+        }
+        ConnectionCommands::RotateKey { key_file } => rotate_key(key_file.as_deref(), ctx).await,
         ConnectionCommands::Remove { name, .. } => remove(name, ctx).await,
         ConnectionCommands::Prune { yes } => prune(yes, ctx).await,
         ConnectionCommands::List => list(ctx).await,
@@ -104,11 +217,18 @@ pub async fn add(
     username: &str,
     password: &str,
     connection_string: &str,
+    required_approvals: i32,
 ) {
     let result = ctx
         .services
         .connection_service
-        .create(name, username, password, connection_string)
+        .create(
+            name,
+            username,
+            password,
+            connection_string,
+            required_approvals,
+        )
         .await;
     match result {
         Ok(_) => println!("✅ Connection created successfully for '{}'", name),
@@ -116,6 +236,70 @@ pub async fn add(
     }
 }
 
+pub async fn add_directory(name: &str, path: &Path, ctx: &Context<'_>) {
+    let result = ctx
+        .services
+        .connection_service
+        .create_directory(name, path, 0)
+        .await;
+    match result {
+        Ok(connection) => println!(
+            "✅ Directory connection created successfully for '{}' ({})",
+            name, connection.connection_string
+        ),
+        Err(e) => eprintln!("❌ Connection creation failed for '{}': {:?}", name, e),
+    }
+}
+
+pub async fn update(name: &str, edit: &ConnectionEdit, skip_test: bool, ctx: &Context<'_>) {
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
+            .template("{spinner:.cyan} {msg}")
+            .unwrap(),
+    );
+    spinner.set_message(format!("Updating connection '{}'...", name));
+    spinner.enable_steady_tick(std::time::Duration::from_millis(80));
+
+    let result = ctx
+        .services
+        .connection_service
+        .edit(name, edit, !skip_test)
+        .await;
+
+    spinner.finish_and_clear();
+
+    let connection = result.exit_on_err(&format!("❌ Failed to update connection '{}'", name));
+    println!("✅ Connection '{}' updated", connection.name);
+}
+
+pub async fn rotate_key(key_file: Option<&Path>, ctx: &Context<'_>) {
+    let service = &ctx.services.connection_service;
+    let configured = service
+        .get_repo()
+        .secrets()
+        .key_file()
+        .map(Path::to_path_buf);
+
+    let (count, path) = service
+        .rotate_key(key_file)
+        .await
+        .exit_on_err("❌ Failed to rotate the encryption key");
+
+    println!(
+        "✅ Re-encrypted {} password(s) with the new key in '{}'",
+        count,
+        path.display()
+    );
+    let setting = format!("LEAF__SECRETS__KEY_FILE={}", path.display()).bold();
+    match configured {
+        None => println!("Set {} and unset LEAF__SECRETS__KEY", setting),
+        Some(configured) if configured != path => println!("Set {}", setting),
+        Some(_) => {}
+    }
+}
+
 pub async fn remove(name: &str, ctx: &Context<'_>) {
     let result = ctx.services.connection_service.delete_by_name(&name).await;
     match result {
@@ -176,6 +360,10 @@ pub async fn list(ctx: &Context<'_>) {
         .map(|(i, c)| ConnectionRow {
             index: (i + 1).to_string().bright_black().to_string(),
             name: c.name.green().to_string(),
+            kind: match c.kind {
+                ConnectionKind::Oracle => c.kind.to_string(),
+                ConnectionKind::Directory => c.kind.to_string().yellow().to_string(),
+            },
             username: c.username.blue().to_string(),
             connection_string: c.connection_string.bright_cyan().to_string(),
         })
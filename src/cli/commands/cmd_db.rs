@@ -40,7 +40,7 @@ pub async fn up(settings: &Settings) {
         .exit_on_err("Failed to connect to database");
 
     info!("Running migrations");
-    migrations::up(&db)
+    migrations::up_with_secrets(&db, settings)
         .await
         .exit_on_err("Failed to run migrations");
 }
@@ -1,7 +1,9 @@
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 use clap::{Parser, Subcommand};
 use colored::Colorize;
-use std::path::PathBuf;
+use inquire::Confirm;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tabled::{
     Table, Tabled,
     settings::{
@@ -14,12 +16,25 @@ use terminal_size::{Width as TermWidth, terminal_size};
 use crate::{
     cli::{
         Context,
-        commands::{ExitOnErr, new_spinner, shared::get_cut_off_date_or_bail},
+        commands::{
+            ExitOnErr, ProgressRenderer,
+            cmd_plans::bail_if_tag_used,
+            print_delta_document_and_exit,
+            shared::{cancel_plan_on_signal, colored_diff, get_cut_off_date_or_bail},
+        },
+    },
+    delta::DeltaDocument,
+    errors::{
+        ApprovalRequiredError, DeploymentCancelledError, DriftDetectedError, PrepareCancelledError,
+    },
+    report,
+    types::{
+        DeploymentStatus, DriftPolicy, EventsFormat, OutputFormat, ReportFormat, RetentionPolicy,
     },
-    types::DeploymentStatus,
     utils::{
-        DeploymentContext, DeploymentContextOptions, format_duration, parsers::parse_cutoff_date,
-        validate_dir,
+        CancelToken, DeploymentContext, DeploymentContextOptions, format_duration,
+        parsers::{parse_cutoff_date, parse_duration},
+        print_output, validate_dir,
     },
 };
 
@@ -42,6 +57,51 @@ pub enum ShowSubcommand {
         #[arg(long, short, required = true)]
         deployment_id: i32,
     },
+
+    /// List all approvals for a deployment
+    Approvals {
+        #[arg(long, short, required = true)]
+        deployment_id: i32,
+    },
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct DeploymentsPrepareArgs {
+    #[arg(long, required = true)]
+    plan: String,
+
+    /// Cutoff date for the deployment. if not specified, the last successful deployment date will be used.
+    /// This is only useful when you first time run a deployment for the plan. Otherwise you should skip this argument.
+    #[arg(long, required = false, value_parser = parse_cutoff_date)]
+    cutoff_date: Option<NaiveDateTime>,
+
+    /// Dry run mode, will not create repository objects, will just simulate the deployment preparation.
+    #[arg(long, required = false)]
+    dry: bool,
+
+    /// Collect migration scripts and rollback scripts
+    #[arg(long, required = false)]
+    collect_scripts: bool,
+
+    #[arg(long, value_name = "DIR", value_parser = validate_dir)]
+    output_path: Option<PathBuf>,
+
+    /// Disable pre-prepare-deployment and post-prepare-deployment hooks
+    #[arg(long, default_value = None)]
+    disable_hooks: Option<bool>,
+
+    /// Release tag for the deployment, can be used with `plans rollback --to-tag`
+    #[arg(long, value_name = "TAG", conflicts_with = "dry")]
+    tag: Option<String>,
+
+    /// Output format, `json` prints the prepared changes as a single JSON document.
+    /// Exits with 0 when there are no changes, 2 when there are changes and 1 on error.
+    #[arg(long, default_value = "text", conflicts_with = "events")]
+    format: OutputFormat,
+
+    /// Stream progress events to stdout instead of showing a spinner, e.g. `jsonl`
+    #[arg(long, value_name = "FORMAT")]
+    events: Option<EventsFormat>,
 }
 
 #[derive(Parser, Debug)]
@@ -96,36 +156,18 @@ EXAMPLES:
     ╰───┴────┴───────┴─────────────┴─────────────┴─────────────────────┴─────────┴─────────┴─────────┴────────────┴──────────┴────────╯
 "#
     )]
-    Prepare {
-        #[arg(long, required = true)]
-        plan: String,
-
-        /// Cutoff date for the deployment. if not specified, the last successful deployment date will be used.
-        /// This is only useful when you first time run a deployment for the plan. Otherwise you should skip this argument.
-        #[arg(long, required = false, value_parser = parse_cutoff_date)]
-        cutoff_date: Option<NaiveDateTime>,
-
-        /// Dry run mode, will not create repository objects, will just simulate the deployment preparation.
-        #[arg(long, required = false)]
-        dry: bool,
-
-        /// Collect migration scripts and rollback scripts
-        #[arg(long, required = false)]
-        collect_scripts: bool,
-
-        #[arg(long, value_name = "DIR", value_parser = validate_dir)]
-        output_path: Option<PathBuf>,
-
-        /// Disable pre-prepare-deployment and post-prepare-deployment hooks
-        #[arg(long, default_value = None)]
-        disable_hooks: Option<bool>,
-    },
+    Prepare(DeploymentsPrepareArgs),
 
     /// Applies a prepared deployment.
     #[command(
         long_about = r#"
 This will apply the deployment with given id to target database.
 The deployment status should be `IDLE` to apply it. Otherwise the app will exit.
+
+Before applying, the DDL of every object in the deployment is extracted from the target again and
+compared with the DDL recorded during preparation. If any object changed in the meantime, the apply
+is aborted and the drifted objects are listed. Use `--ignore-drift` to apply the prepared scripts
+anyway or `--refresh-drifted` to re-prepare the drifted objects from the current target state.
 "#,
         after_help = r#"
 EXAMPLES:
@@ -136,6 +178,10 @@ EXAMPLES:
 
     Example output:
     🚀 Deployment for plan 'demo3' completed successfully.
+
+    #  leaf deployments apply --deployment-id 1 --refresh-drifted
+    This will re-prepare the changes of the objects that changed on the target since the deployment
+    was prepared and then apply the deployment.
     "#
     )]
     Apply {
@@ -150,6 +196,145 @@ EXAMPLES:
         /// Disable pre-apply-deployment and post-apply-deployment hooks
         #[arg(long, default_value = None)]
         disable_hooks: Option<bool>,
+
+        /// Apply the prepared scripts even if the target changed since preparation
+        #[arg(long, conflicts_with = "refresh_drifted")]
+        ignore_drift: bool,
+
+        /// Re-prepare the objects that changed on the target since preparation
+        #[arg(long)]
+        refresh_drifted: bool,
+
+        /// Verify the deployed objects against the source after a successful apply
+        #[arg(long)]
+        verify: bool,
+
+        /// Stream progress events to stdout instead of showing a spinner, e.g. `jsonl`
+        #[arg(long, value_name = "FORMAT")]
+        events: Option<EventsFormat>,
+    },
+
+    /// Tag a deployment with a release name
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf deployments tag --deployment-id 12 --tag v1.4
+    This will tag the deployment 12 as `v1.4`. Tags are unique per plan and can be
+    used as a rollback point with `leaf plans rollback --plan demo3 --to-tag v1.4`.
+    "#)]
+    Tag {
+        /// Deployment ID to tag
+        #[arg(long, required = true)]
+        deployment_id: i32,
+
+        /// Release tag
+        #[arg(long, required = true)]
+        tag: String,
+    },
+
+    /// Approve a prepared deployment
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf deployments approve --deployment-id 12 --by alice --comment "Reviewed the index changes"
+    This will record alice's approval of the current change scripts of deployment 12.
+    If the scripts change afterwards (e.g. `apply --refresh-drifted`), the approval no longer counts.
+    "#)]
+    Approve {
+        /// Deployment ID to approve
+        #[arg(long, required = true)]
+        deployment_id: i32,
+
+        /// Name of the approver
+        #[arg(long, required = true)]
+        by: String,
+
+        /// Optional comment
+        #[arg(long)]
+        comment: Option<String>,
+    },
+
+    /// Show a report of a deployment
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf deployments report --deployment-id 12
+    This will print the report of deployment 12 to the terminal: the plan settings it ran with,
+    the changed objects with their status and timing, errors, hook executions and rollbacks.
+
+    # leaf deployments report --deployment-id 12 --format html --output-path ./reports
+    This will write a standalone HTML report to `./reports/deployment-12-report.html`.
+    Supported formats are `terminal`, `markdown` (or `md`), `html` and `json`.
+    "#)]
+    Report {
+        /// Deployment ID to report
+        #[arg(long, required = true)]
+        deployment_id: i32,
+
+        /// Report format
+        #[arg(long, default_value = "terminal")]
+        format: ReportFormat,
+
+        /// Report output directory, the report is printed when omitted
+        #[arg(long, value_name = "DIR", value_parser = validate_dir)]
+        output_path: Option<PathBuf>,
+    },
+
+    /// Compare the objects, scripts and status of two deployments of the same plan
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf deployments diff --a 12 --b 15
+    This will list the objects changed by only one of the deployments 12 and 15, the objects
+    whose generated scripts differ and the objects that ended with a different status.
+
+    # leaf deployments diff --a 12 --b 15 --scripts
+    Same, followed by a unified diff of the scripts of every object whose scripts differ.
+    "#)]
+    Diff {
+        /// First deployment ID
+        #[arg(long, required = true)]
+        a: i32,
+
+        /// Second deployment ID
+        #[arg(long, required = true)]
+        b: i32,
+
+        /// Print the script diff of every object whose scripts differ
+        #[arg(long, default_value_t = false)]
+        scripts: bool,
+    },
+
+    /// Delete old deployments with their changesets, changes and rollbacks
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf deployments purge --older-than 90d --keep-last 5
+    This will list the deployments older than 90 days that are not among the 5 newest of their
+    plan and delete them after confirmation. The latest successful deployment of every plan and
+    running deployments are always kept.
+
+    # leaf deployments purge --plan demo3 --keep-last 10 --dry-run
+    This will only list the deployments of plan `demo3` that would be deleted.
+
+    Without --older-than and --keep-last the `LEAF__RETENTION__*` settings are used.
+    Durations are a number followed by s, m, h, d or w.
+    "#)]
+    Purge {
+        /// Delete deployments older than this, e.g. `90d`
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        older_than: Option<Duration>,
+
+        /// Keep this many of the newest deployments of every plan
+        #[arg(long, value_name = "N")]
+        keep_last: Option<usize>,
+
+        /// Only purge deployments of this plan
+        #[arg(long, short)]
+        plan: Option<String>,
+
+        /// Only list the deployments that would be deleted
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+
+        /// Do not ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
     },
 }
 
@@ -217,6 +402,69 @@ struct DeploymentObjectRow {
 
     #[tabled(rename = "Name")]
     object_name: String,
+
+    #[tabled(rename = "Status")]
+    status: String,
+
+    #[tabled(rename = "Verification")]
+    verification: String,
+}
+
+#[derive(Tabled)]
+struct PurgeRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "ID")]
+    id: String,
+
+    #[tabled(rename = "Plan")]
+    plan: String,
+
+    #[tabled(rename = "Status")]
+    status: String,
+
+    #[tabled(rename = "Tag")]
+    tag: String,
+
+    #[tabled(rename = "Created At")]
+    created_at: String,
+}
+
+#[derive(Tabled)]
+struct DeploymentDiffRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "Object")]
+    object: String,
+
+    #[tabled(rename = "A")]
+    a_status: String,
+
+    #[tabled(rename = "B")]
+    b_status: String,
+
+    #[tabled(rename = "Difference")]
+    difference: String,
+}
+
+#[derive(Tabled)]
+struct DeploymentApprovalRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "Approved By")]
+    approved_by: String,
+
+    #[tabled(rename = "Comment")]
+    comment: String,
+
+    #[tabled(rename = "Approved At")]
+    approved_at: String,
+
+    #[tabled(rename = "Valid")]
+    valid: String,
 }
 
 #[derive(Tabled)]
@@ -259,34 +507,77 @@ pub async fn execute(action: &DeploymentCommands, ctx: &Context<'_>) {
                 ShowSubcommand::Changes { deployment_id } => {
                     show_deployment_changes(*deployment_id, ctx).await
                 }
+                ShowSubcommand::Approvals { deployment_id } => {
+                    show_deployment_approvals(*deployment_id, ctx).await
+                }
             },
             None => show_deployment(0, ctx).await, // default behavior when no subcommand is given
         },
-        DeploymentCommands::Prepare {
-            plan,
-            cutoff_date,
-            dry,
-            collect_scripts,
-            output_path,
+        DeploymentCommands::Prepare(args) => prepare_deployment(args, ctx).await,
+
+        DeploymentCommands::Apply {
+            deployment_id,
+            fail_fast,
             disable_hooks,
+            ignore_drift,
+            refresh_drifted,
+            verify,
+            events,
         } => {
-            prepare_deployment(
-                plan,
-                cutoff_date,
-                *dry,
-                *collect_scripts,
-                output_path,
+            let drift_policy = if *ignore_drift {
+                DriftPolicy::Ignore
+            } else if *refresh_drifted {
+                DriftPolicy::Refresh
+            } else {
+                DriftPolicy::Abort
+            };
+            apply_deployment(
+                *deployment_id,
+                *fail_fast,
                 *disable_hooks,
+                drift_policy,
+                *verify,
+                *events,
                 ctx,
             )
             .await
         }
 
-        DeploymentCommands::Apply {
+        DeploymentCommands::Tag { deployment_id, tag } => {
+            tag_deployment(*deployment_id, tag, ctx).await
+        }
+
+        DeploymentCommands::Approve {
             deployment_id,
-            fail_fast,
-            disable_hooks,
-        } => apply_deployment(*deployment_id, *fail_fast, *disable_hooks, ctx).await,
+            by,
+            comment,
+        } => approve_deployment(*deployment_id, by, comment.as_deref(), ctx).await,
+
+        DeploymentCommands::Report {
+            deployment_id,
+            format,
+            output_path,
+        } => report_deployment(*deployment_id, *format, output_path.as_deref(), ctx).await,
+
+        DeploymentCommands::Diff { a, b, scripts } => diff_deployments(*a, *b, *scripts, ctx).await,
+
+        DeploymentCommands::Purge {
+            older_than,
+            keep_last,
+            plan,
+            dry_run,
+            yes,
+        } => {
+            purge_deployments(
+                *older_than,
+                *keep_last,
+                plan.as_deref(),
+                *dry_run,
+                *yes,
+                ctx,
+            )
+            .await
+        }
     }
 }
 
@@ -333,6 +624,29 @@ async fn show_deployment(deployment_id: i32, ctx: &Context<'_>) {
         value: plan.name.clone(),
     });
 
+    let plan_revision = match deployment.plan_revision_id {
+        Some(id) => ctx
+            .services
+            .plan_service
+            .find_revision_by_id(id)
+            .await
+            .exit_on_err(&format!("❌ Failed to find plan revision {}", id))
+            .map(|r| {
+                if r.is_reconstructed() {
+                    format!("{} (reconstructed)", r.revision)
+                } else {
+                    r.revision.to_string()
+                }
+            }),
+        None => None,
+    };
+    index += 1;
+    table_data.push(KeyValueRow {
+        index: index.to_string().bright_black().to_string(),
+        key: "Plan Revision".to_string(),
+        value: plan_revision.unwrap_or_else(|| "-".bright_black().to_string()),
+    });
+
     let source_connection = ctx
         .services
         .connection_service
@@ -443,6 +757,13 @@ async fn show_deployment(deployment_id: i32, ctx: &Context<'_>) {
         value: duration,
     });
 
+    index += 1;
+    table_data.push(KeyValueRow {
+        index: index.to_string().bright_black().to_string(),
+        key: "Tag".to_string(),
+        value: deployment.tag.clone().unwrap_or_else(|| "N/A".to_string()),
+    });
+
     index += 1;
     table_data.push(KeyValueRow {
         index: index.to_string().bright_black().to_string(),
@@ -472,12 +793,20 @@ async fn show_deployment_objects(deployment_id: i32, ctx: &Context<'_>) {
         let object_type = changeset.object_type.clone();
         let object_owner = changeset.object_owner.clone();
         let object_name = changeset.object_name.clone();
+        let status = changeset.status.to_colored_string();
+        let verification = changeset
+            .verification_status
+            .as_ref()
+            .map(|v| v.to_colored_string())
+            .unwrap_or_else(|| "-".bright_black().to_string());
 
         table_data.push(DeploymentObjectRow {
             index: index.to_string().bright_black().to_string(),
             object_type,
             object_owner,
             object_name,
+            status,
+            verification,
         });
         index += 1;
     }
@@ -667,25 +996,25 @@ async fn list_deployments(
     println!("{}", table);
 }
 
-async fn prepare_deployment(
-    plan_name: &str,
-    cutoff_date: &Option<NaiveDateTime>,
-    dry: bool,
-    collect_scripts: bool,
-    output_path: &Option<PathBuf>,
-    disable_hooks: Option<bool>,
-    ctx: &Context<'_>,
-) {
-    let (spinner, tx) = new_spinner();
+async fn prepare_deployment(args: &DeploymentsPrepareArgs, ctx: &Context<'_>) {
+    let plan_name = args.plan.as_str();
+    let tag = args.tag.as_deref();
+    let (dry, format) = (args.dry, args.format);
+    let (progress, tx) = ProgressRenderer::new(args.events);
+    if format == OutputFormat::Json {
+        progress.hide();
+    }
 
+    let cancel_token = CancelToken::new();
     let mut dctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
         dry,
-        collect_scripts,
-        output_path.clone(),
+        args.collect_scripts,
+        args.output_path.clone(),
         None,
         Some(tx),
     )))
-    .exit_on_err("Failed to initialize deployment sink"); // TODO: Add more info
+    .exit_on_err("Failed to initialize deployment sink") // TODO: Add more info
+    .with_cancel_token(cancel_token.clone());
 
     let plan = ctx
         .services
@@ -698,15 +1027,28 @@ async fn prepare_deployment(
             std::process::exit(1);
         });
 
-    let cutoff_date = get_cut_off_date_or_bail(cutoff_date.clone(), plan.id, ctx).await;
+    let cutoff_date = get_cut_off_date_or_bail(args.cutoff_date, plan.id, ctx).await;
+
+    if let Some(tag) = tag {
+        bail_if_tag_used(plan.id, tag, ctx).await;
+    }
 
+    let signals = cancel_plan_on_signal(plan.id, cancel_token, ctx);
     let res = ctx
         .services
         .deployment_service
-        .prepare(plan.id, cutoff_date, disable_hooks, &mut dctx)
+        .prepare(plan.id, cutoff_date, args.disable_hooks, &mut dctx)
         .await;
+    drop(signals);
 
-    spinner.finish_and_clear();
+    progress.finish().await;
+
+    if let Err(e) = &res
+        && let Some(cancelled) = e.downcast_ref::<PrepareCancelledError>()
+    {
+        eprintln!("🛑 {}", cancelled);
+        std::process::exit(130);
+    }
 
     if res.is_err() {
         eprintln!(
@@ -716,35 +1058,54 @@ async fn prepare_deployment(
         );
         std::process::exit(1);
     }
-    match res.unwrap() {
-        Some(deployment_id) => {
-            println!(
-                "✅ Deployment prepared for plan '{}' completed successfully",
-                plan_name
-            );
-            println!("🗝️ Deployment ID: {}", deployment_id);
+    let deployment_id = res.unwrap();
+    if let (Some(deployment_id), Some(tag)) = (deployment_id, tag) {
+        ctx.services
+            .deployment_service
+            .tag_deployment(deployment_id, tag)
+            .await
+            .exit_on_err(&format!("❌ Failed to tag deployment {}", deployment_id));
+    }
+
+    if format == OutputFormat::Json {
+        let document =
+            DeltaDocument::new(plan_name, cutoff_date, dry, deployment_id, dctx.deltas());
+        print_delta_document_and_exit(&document);
+    }
+
+    if let Some(deployment_id) = deployment_id {
+        print_output(format!(
+            "✅ Deployment prepared for plan '{}' completed successfully",
+            plan_name
+        ));
+        print_output(format!("🗝️ Deployment ID: {}", deployment_id));
+        if let Some(tag) = tag {
+            print_output(format!("🏷️ Deployment {} tagged '{}'", deployment_id, tag));
         }
-        None => {}
     }
 
     if dctx.is_collect_scripts() {
         dctx.print_summary("✅ Collected scripts successfully");
     }
 
-    println!(
+    print_output(format!(
         "✅ Deployment preparation for plan '{}' completed successfully{}",
         plan_name,
         if dry { " in dry-run mode 👻." } else { "." }
-    );
+    ));
 }
 
 async fn apply_deployment(
     deployment_id: i32,
     fail_fast: bool,
     disable_hooks: Option<bool>,
+    drift_policy: DriftPolicy,
+    verify: bool,
+    events: Option<EventsFormat>,
     ctx: &Context<'_>,
 ) {
-    let (spinner, tx) = new_spinner();
+    let (progress, tx) = ProgressRenderer::new(events);
+    let cancel_token = CancelToken::new();
 
     let mut dctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
         false,
@@ -753,7 +1114,8 @@ async fn apply_deployment(
         None,
         Some(tx),
     )))
-    .exit_on_err("Failed to initialize deployment sink"); // TODO: Add more info
+    .exit_on_err("Failed to initialize deployment sink") // TODO: Add more info
+    .with_cancel_token(cancel_token.clone());
 
     dctx.progress(format!("Finding deployment by ID '{}'...", deployment_id));
     let deployment = ctx
@@ -767,10 +1129,13 @@ async fn apply_deployment(
         "Checking deployment status '{}'...",
         deployment.plan_id
     ));
-    if deployment.status != DeploymentStatus::Idle {
-        spinner.finish_and_clear();
+    if !matches!(
+        deployment.status,
+        DeploymentStatus::Idle | DeploymentStatus::Interrupted | DeploymentStatus::Cancelled
+    ) {
+        progress.finish().await;
         eprintln!(
-            "❌ Deployment with ID '{}' is not in IDLE status. It can not be applied.",
+            "❌ Deployment with ID '{}' is not in IDLE, INTERRUPTED or CANCELLED status. It can not be applied.",
             deployment_id
         );
         std::process::exit(1);
@@ -783,21 +1148,446 @@ async fn apply_deployment(
         .await
         .exit_on_err(format!("❌ Failed to find plan by id {}", deployment.plan_id).as_str());
 
+    let signals = cancel_plan_on_signal(plan.id, cancel_token, ctx);
     let res = ctx
         .services
         .deployment_service
-        .apply(deployment_id, fail_fast, disable_hooks, &mut dctx)
+        .apply(
+            deployment_id,
+            fail_fast,
+            disable_hooks,
+            drift_policy,
+            &mut dctx,
+        )
         .await;
 
-    spinner.finish_and_clear();
+    drop(signals);
+    progress.finish().await;
 
-    if res.is_err() {
-        eprintln!("❌ Deployment for plan '{}' failed", plan.name);
+    if let Err(e) = &res
+        && let Some(cancelled) = e.downcast_ref::<DeploymentCancelledError>()
+    {
+        eprintln!("🛑 {}", cancelled);
+        eprintln!(
+            "Use `leaf deployments apply --deployment-id {}` to resume it or `leaf plans rollback {}` to revert it.",
+            deployment_id, plan.name
+        );
+        std::process::exit(130);
+    }
+
+    if let Err(e) = &res
+        && let Some(drift) = e.downcast_ref::<DriftDetectedError>()
+    {
+        eprintln!(
+            "❌ Target changed since deployment {} was prepared. Drifted objects:",
+            deployment_id
+        );
+        for object in &drift.objects {
+            eprintln!("   - {}", object);
+        }
+        eprintln!("Use --refresh-drifted to re-prepare them or --ignore-drift to apply anyway.");
         std::process::exit(1);
     }
 
-    println!(
+    if let Err(e) = &res
+        && let Some(approval) = e.downcast_ref::<ApprovalRequiredError>()
+    {
+        eprintln!("❌ {}", approval);
+        eprintln!(
+            "Use `leaf deployments approve --deployment-id {} --by <NAME>` to approve it.",
+            deployment_id
+        );
+        std::process::exit(1);
+    }
+
+    if let Err(e) = res {
+        eprintln!("❌ Deployment for plan '{}' failed: {}", plan.name, e);
+        std::process::exit(1);
+    }
+
+    print_output(format!(
         "🚀 Deployment for plan '{}' completed successfully.",
         plan.name
+    ));
+
+    if verify {
+        verify_deployment_or_bail(deployment_id, events, ctx).await;
+    }
+}
+
+/// Verifies the deployed objects of a deployment and exits if any of them does not match the source.
+pub async fn verify_deployment_or_bail(
+    deployment_id: i32,
+    events: Option<EventsFormat>,
+    ctx: &Context<'_>,
+) {
+    let (progress, tx) = ProgressRenderer::new(events);
+
+    let mut dctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
+        false,
+        false,
+        None,
+        None,
+        Some(tx),
+    )))
+    .exit_on_err("Failed to initialize deployment sink");
+
+    let mismatched = ctx
+        .services
+        .deployment_service
+        .verify(deployment_id, &mut dctx)
+        .await;
+
+    progress.finish().await;
+
+    let mismatched =
+        mismatched.exit_on_err(&format!("❌ Failed to verify deployment {}", deployment_id));
+
+    if !mismatched.is_empty() {
+        eprintln!(
+            "❌ Verification of deployment {} failed, target does not match the source for:",
+            deployment_id
+        );
+        for changeset in &mismatched {
+            eprintln!(
+                "   - {} {}.{}",
+                changeset.object_type, changeset.object_owner, changeset.object_name
+            );
+        }
+        std::process::exit(1);
+    }
+
+    print_output(format!("🔍 Deployment {} verified.", deployment_id));
+}
+
+async fn tag_deployment(deployment_id: i32, tag: &str, ctx: &Context<'_>) {
+    ctx.services
+        .deployment_service
+        .tag_deployment(deployment_id, tag)
+        .await
+        .exit_on_err(&format!("❌ Failed to tag deployment {}", deployment_id));
+
+    println!("🏷️ Deployment {} tagged '{}'", deployment_id, tag);
+}
+
+async fn approve_deployment(
+    deployment_id: i32,
+    by: &str,
+    comment: Option<&str>,
+    ctx: &Context<'_>,
+) {
+    let approval = ctx
+        .services
+        .deployment_service
+        .approve(deployment_id, by, comment)
+        .await
+        .exit_on_err(&format!(
+            "❌ Failed to approve deployment {}",
+            deployment_id
+        ));
+
+    println!(
+        "✅ Deployment {} approved by '{}' (checksum {})",
+        deployment_id,
+        approval.approved_by,
+        &approval.checksum[..12]
+    );
+}
+
+async fn show_deployment_approvals(deployment_id: i32, ctx: &Context<'_>) {
+    let deployment = ctx
+        .services
+        .deployment_service
+        .get_by_id(deployment_id)
+        .await
+        .exit_on_err(format!("❌ Failed to find deployment by id {}", deployment_id).as_str());
+
+    let approvals = ctx
+        .services
+        .deployment_service
+        .find_approvals(deployment_id)
+        .await
+        .exit_on_err("❌ Failed to fetch approvals");
+
+    if approvals.is_empty() {
+        println!("✅ No approvals found");
+        return;
+    }
+
+    let plan = ctx
+        .services
+        .plan_service
+        .get_by_id(deployment.plan_id)
+        .await
+        .exit_on_err(format!("❌ Failed to find plan by id {}", deployment.plan_id).as_str());
+    let required = ctx
+        .services
+        .deployment_service
+        .required_approvals(&plan)
+        .await
+        .exit_on_err("❌ Failed to find required approvals");
+    let checksum = ctx
+        .services
+        .deployment_service
+        .scripts_checksum(deployment_id)
+        .await
+        .exit_on_err("❌ Failed to compute scripts checksum");
+
+    let table_data: Vec<DeploymentApprovalRow> = approvals
+        .into_iter()
+        .enumerate()
+        .map(|(i, approval)| DeploymentApprovalRow {
+            index: (i + 1).to_string().bright_black().to_string(),
+            approved_by: approval.approved_by,
+            comment: approval.comment.unwrap_or_default(),
+            approved_at: approval.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            valid: if approval.checksum == checksum {
+                "YES".green().bold().to_string()
+            } else {
+                "SCRIPTS CHANGED".red().bold().to_string()
+            },
+        })
+        .collect();
+
+    let table = Table::new(table_data)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
+    println!("Required approvals: {}", required);
+}
+
+pub async fn report_deployment(
+    deployment_id: i32,
+    format: ReportFormat,
+    output_path: Option<&Path>,
+    ctx: &Context<'_>,
+) {
+    let deployment_report = ctx
+        .services
+        .deployment_service
+        .report(deployment_id)
+        .await
+        .exit_on_err(&format!(
+            "❌ Failed to build report for deployment {}",
+            deployment_id
+        ));
+
+    // Escape codes only make sense when the report is printed
+    if output_path.is_some() {
+        colored::control::set_override(false);
+    }
+    let content = report::render(&deployment_report, format);
+    if output_path.is_some() {
+        colored::control::unset_override();
+    }
+    let content = content.exit_on_err("❌ Failed to render report");
+
+    match output_path {
+        Some(dir) => {
+            let path = dir.join(format!(
+                "deployment-{}-report.{}",
+                deployment_id,
+                format.extension()
+            ));
+            std::fs::write(&path, content)
+                .exit_on_err(&format!("❌ Failed to write report to {}", path.display()));
+            print_output(format!("📄 Report written to {}", path.display()));
+        }
+        None => print_output(content),
+    }
+}
+
+async fn diff_deployments(a: i32, b: i32, scripts: bool, ctx: &Context<'_>) {
+    let diff = ctx
+        .services
+        .deployment_service
+        .diff(a, b)
+        .await
+        .exit_on_err(&format!("❌ Failed to compare deployments {} and {}", a, b));
+
+    println!(
+        "{}",
+        format!("=== Deployment {} (A) → Deployment {} (B) ===", a, b).blue()
+    );
+
+    if diff.is_empty() {
+        println!(
+            "✅ Deployments {} and {} changed the same {} object(s) the same way",
+            a, b, diff.identical_count
+        );
+        return;
+    }
+
+    let status = |object: &Option<report::DeployedObject>| {
+        object
+            .as_ref()
+            .map(|o| o.status.to_colored_string())
+            .unwrap_or_else(|| "-".bright_black().to_string())
+    };
+    let rows: Vec<DeploymentDiffRow> = diff
+        .objects
+        .iter()
+        .enumerate()
+        .map(|(i, o)| {
+            let difference = match (&o.a, &o.b) {
+                (Some(_), None) => "Only in A".green().to_string(),
+                (None, Some(_)) => "Only in B".red().to_string(),
+                _ => {
+                    let mut differences = Vec::new();
+                    if o.scripts_differ() {
+                        differences.push("Scripts");
+                    }
+                    if o.status_differs() {
+                        differences.push("Status");
+                    }
+                    differences.join(", ").yellow().to_string()
+                }
+            };
+            DeploymentDiffRow {
+                index: (i + 1).to_string().bright_black().to_string(),
+                object: o.qualified_name(),
+                a_status: status(&o.a),
+                b_status: status(&o.b),
+                difference,
+            }
+        })
+        .collect();
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
+
+    if scripts {
+        for object in diff.objects.iter().filter(|o| o.scripts_differ()) {
+            let name = object.qualified_name();
+            if let Some(script_diff) = object.script_diff(
+                &format!("{} (deployment {})", name, a),
+                &format!("{} (deployment {})", name, b),
+            ) {
+                println!("\n{}", colored_diff(&script_diff));
+            }
+        }
+    }
+
+    println!(
+        "⚠️ {} object(s) differ, {} identical",
+        diff.objects.len(),
+        diff.identical_count
+    );
+}
+
+async fn purge_deployments(
+    older_than: Option<Duration>,
+    keep_last: Option<usize>,
+    plan: Option<&str>,
+    dry_run: bool,
+    yes: bool,
+    ctx: &Context<'_>,
+) {
+    let policy = if older_than.is_some() || keep_last.is_some() {
+        RetentionPolicy::new(older_than, keep_last).exit_on_err("❌ Invalid retention policy")
+    } else {
+        ctx.settings
+            .retention
+            .policy()
+            .exit_on_err("❌ Invalid retention settings")
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "❌ Pass --older-than and/or --keep-last, or set LEAF__RETENTION__OLDER_THAN or LEAF__RETENTION__KEEP_LAST"
+                );
+                std::process::exit(1);
+            })
+    };
+
+    let plan_id = match plan {
+        Some(name) => Some(
+            ctx.services
+                .plan_service
+                .find_by_name(name)
+                .await
+                .exit_on_err(&format!("❌ Failed to find plan '{}'", name))
+                .unwrap_or_else(|| {
+                    eprintln!("❌ Plan '{}' not found", name);
+                    std::process::exit(1);
+                })
+                .id,
+        ),
+        None => None,
+    };
+
+    let deployments = ctx
+        .services
+        .deployment_service
+        .find_purgeable(&policy, plan_id)
+        .await
+        .exit_on_err("❌ Failed to find deployments to purge");
+
+    if deployments.is_empty() {
+        println!("✅ No deployments to purge");
+        return;
+    }
+
+    let plan_names: HashMap<i32, String> = ctx
+        .services
+        .plan_service
+        .get_all()
+        .await
+        .exit_on_err("❌ Failed to fetch plans")
+        .into_iter()
+        .map(|p| (p.id, p.name))
+        .collect();
+    let rows: Vec<PurgeRow> = deployments
+        .iter()
+        .enumerate()
+        .map(|(i, d)| PurgeRow {
+            index: (i + 1).to_string().bright_black().to_string(),
+            id: d.id.to_string(),
+            plan: plan_names
+                .get(&d.plan_id)
+                .cloned()
+                .unwrap_or_else(|| d.plan_id.to_string()),
+            status: d.status.to_colored_string(),
+            tag: d
+                .tag
+                .clone()
+                .unwrap_or_else(|| "-".bright_black().to_string()),
+            created_at: d.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        })
+        .collect();
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
+
+    if dry_run {
+        println!("✅ {} deployment(s) would be purged", deployments.len());
+        return;
+    }
+
+    let proceed = yes || Confirm::new(&format!(
+        "This will delete {} deployment(s) with their changesets, changes and rollbacks. Continue?",
+        deployments.len()
+    ))
+    .with_default(false)
+    .prompt()
+    .unwrap_or(false);
+    if !proceed {
+        println!("✅ Aborted");
+        return;
+    }
+
+    let ids: Vec<i32> = deployments.iter().map(|d| d.id).collect();
+    let summary = ctx
+        .services
+        .deployment_service
+        .purge(&ids)
+        .await
+        .exit_on_err("❌ Failed to purge deployments");
+    println!(
+        "✅ Purged {} deployment(s), {} changeset(s), {} change(s) and {} rollback(s)",
+        summary.deployments, summary.changesets, summary.changes, summary.rollbacks
     );
 }
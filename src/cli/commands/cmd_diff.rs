@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::{
+    cli::{
+        Context,
+        commands::{ExitOnErr, ProgressRenderer},
+    },
+    services::DiffOptions,
+    utils::{DeploymentContext, deployment_context::DeploymentContextOptions, validate_dir},
+};
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+EXAMPLES:
+    # leaf diff --from ./release-1.3 --to ./schema --output ./scripts
+    This will compare two snapshot directories, e.g. written by `leaf snapshot` or checked out
+    from git, and write the scripts that turn `./release-1.3` into `./schema`, their rollback
+    scripts and a summary to `./scripts`. No database connection is needed.
+
+    # leaf diff --from ./release-1.3 --to ./schema --schemas HR --disable-all-drops=false
+    This will print the scripts of the `HR` schema including drops of removed objects.
+    If --disable-all-drops is not specified, the value from the `.env` file is used, which is
+    `true` by default.
+    "#)]
+pub struct DiffCommand {
+    /// Snapshot directory with the current state
+    #[arg(long, required = true, value_name = "DIR", value_parser = validate_dir)]
+    from: PathBuf,
+
+    /// Snapshot directory with the desired state
+    #[arg(long, required = true, value_name = "DIR", value_parser = validate_dir)]
+    to: PathBuf,
+
+    /// Scripts output directory, the scripts are printed if not set
+    #[arg(long, value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    /// Comma-separated list of schemas to compare, all schemas of both directories by default
+    #[arg(long, value_delimiter = ',')]
+    schemas: Vec<String>,
+
+    /// Comma-separated list of object types to exclude
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_types: Vec<String>,
+
+    /// Comma-separated list of object names to exclude
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_names: Vec<String>,
+
+    /// Comma-separated list of disabled object types do drop
+    #[arg(long, value_delimiter = ',')]
+    disabled_drop_types: Vec<String>,
+
+    /// Disable all DROP operations
+    #[arg(long, default_value = None)]
+    disable_all_drops: Option<bool>,
+}
+
+pub async fn execute(args: &DiffCommand, ctx: &Context<'_>) {
+    let rules = &ctx.settings.rules;
+    let options = DiffOptions {
+        schemas: args.schemas.clone(),
+        exclude_object_types: rules
+            .combined_exclude_object_types(Some(args.exclude_object_types.clone())),
+        exclude_object_names: rules
+            .combined_exclude_object_names(Some(args.exclude_object_names.clone())),
+        disabled_drop_types: rules
+            .combined_disabled_drop_types(Some(args.disabled_drop_types.clone())),
+        disable_all_drops: args.disable_all_drops.unwrap_or(rules.disable_all_drops),
+    };
+
+    let (progress, tx) = ProgressRenderer::new(None);
+    let mut dctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
+        true,
+        true,
+        args.output.clone(),
+        None,
+        Some(tx),
+    )))
+    .exit_on_err("❌ Failed to initialize script output");
+
+    let res = ctx
+        .services
+        .snapshot_service
+        .diff(&args.from, &args.to, options, &mut dctx);
+    progress.finish().await;
+
+    let count = res.exit_on_err(&format!(
+        "❌ Failed to diff '{}' and '{}'",
+        args.from.display(),
+        args.to.display()
+    ));
+
+    if count == 0 {
+        println!(
+            "✅ No changes between '{}' and '{}'",
+            args.from.display(),
+            args.to.display()
+        );
+        return;
+    }
+    dctx.print_summary(&format!("✅ Found {} change(s)", count));
+    println!("✅ Found {} change(s)", count);
+}
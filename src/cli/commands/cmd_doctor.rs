@@ -0,0 +1,123 @@
+use clap::Args;
+use colored::Colorize;
+use tabled::{
+    Table, Tabled,
+    settings::{Alignment, Modify, Style, object::Rows},
+};
+
+use crate::{
+    cli::{Context, commands::ExitOnErr},
+    services::InterruptedRecords,
+};
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+EXAMPLES:
+    # leaf doctor
+    This will list the plans and deployments left RUNNING by a process that stopped.
+
+    # leaf doctor --fix
+    This will mark them as INTERRUPTED. An interrupted deployment can be resumed with
+    `leaf deployments apply` or reverted with `leaf plans rollback`.
+    "#)]
+pub struct DoctorCommand {
+    /// Mark the stale RUNNING plans and deployments as INTERRUPTED
+    #[arg(long)]
+    pub fix: bool,
+}
+
+#[derive(Tabled)]
+struct InterruptedRow {
+    #[tabled(rename = "Kind")]
+    kind: String,
+
+    #[tabled(rename = "ID")]
+    id: String,
+
+    #[tabled(rename = "Plan")]
+    plan: String,
+
+    #[tabled(rename = "Owner")]
+    owner: String,
+
+    #[tabled(rename = "Last Seen")]
+    last_seen: String,
+}
+
+pub async fn execute(action: &DoctorCommand, ctx: &Context<'_>) {
+    let records = if action.fix {
+        ctx.services
+            .deployment_service
+            .recover_interrupted()
+            .await
+            .exit_on_err("❌ Failed to recover interrupted deployments")
+    } else {
+        ctx.services
+            .deployment_service
+            .find_interrupted()
+            .await
+            .exit_on_err("❌ Failed to find interrupted deployments")
+    };
+
+    println!("{}", "=== Stale RUNNING records ===".blue());
+
+    if records.is_empty() {
+        println!("✅ No stale RUNNING plans or deployments found");
+        return;
+    }
+
+    print_records(&records, ctx).await;
+
+    if action.fix {
+        println!(
+            "🩹 Marked {} deployment(s) and {} plan(s) as INTERRUPTED",
+            records.deployments.len(),
+            records.plans.len()
+        );
+    } else {
+        println!("⚠️ Run `leaf doctor --fix` to mark them as INTERRUPTED");
+    }
+}
+
+async fn print_records(records: &InterruptedRecords, ctx: &Context<'_>) {
+    let mut table_data = Vec::new();
+    for deployment in &records.deployments {
+        let plan = ctx
+            .services
+            .plan_service
+            .get_by_id(deployment.plan_id)
+            .await
+            .map(|p| p.name)
+            .unwrap_or_else(|_| deployment.plan_id.to_string());
+        let owner = match (&deployment.owner_host, deployment.owner_pid) {
+            (Some(host), Some(pid)) => format!("{} (pid {})", host, pid),
+            _ => "-".to_string(),
+        };
+        table_data.push(InterruptedRow {
+            kind: "Deployment".to_string(),
+            id: deployment.id.to_string(),
+            plan,
+            owner,
+            last_seen: deployment
+                .heartbeat_at
+                .or(deployment.updated_at)
+                .map(|d| d.format("%Y-%m-%dT%H:%M:%S").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        });
+    }
+    for plan in &records.plans {
+        table_data.push(InterruptedRow {
+            kind: "Plan".to_string(),
+            id: plan.id.to_string(),
+            plan: plan.name.clone(),
+            owner: "-".to_string(),
+            last_seen: "-".to_string(),
+        });
+    }
+
+    let table = Table::new(table_data)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
+}
@@ -0,0 +1,132 @@
+use chrono::Local;
+use clap::Subcommand;
+use colored::*;
+use tabled::{
+    Table, Tabled,
+    settings::{Alignment, Modify, Style, object::Rows},
+};
+
+use crate::{
+    cli::{
+        Context,
+        commands::{ExitOnErr, ProgressRenderer, shared::EXIT_CHANGES_PRESENT},
+    },
+    types::{DriftDocument, DriftKind, OutputFormat},
+    utils::ProgressReporter,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum DriftCommands {
+    /// Check the target of a plan for changes made outside of leaf
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf drift check --plan demo3
+    This will compare every object leaf deployed with plan `demo3` to its current DDL on the
+    target and list the objects that were altered, dropped or created outside of leaf.
+
+    # leaf drift check --plan demo3 --format json > drift.json
+    Same, as a single JSON document for cron jobs and CI pipelines.
+
+    Exits with 0 when there is no drift, 2 when drift was found and 1 on error.
+    "#)]
+    Check {
+        /// Name of the plan
+        #[arg(long, short, required = true)]
+        plan: String,
+
+        /// Output format, `json` prints the drifted objects as a single JSON document
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Tabled)]
+struct DriftRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "Object")]
+    object: String,
+
+    #[tabled(rename = "Drift")]
+    kind: String,
+
+    #[tabled(rename = "Deployment")]
+    deployment_id: String,
+}
+
+pub async fn execute(action: &DriftCommands, ctx: &Context<'_>) {
+    match action {
+        DriftCommands::Check { plan, format } => check(plan, *format, ctx).await,
+    }
+}
+
+fn colored_kind(kind: DriftKind) -> String {
+    let text = kind.to_string().to_uppercase();
+    match kind {
+        DriftKind::Altered => text.yellow().bold().to_string(),
+        DriftKind::Dropped => text.red().bold().to_string(),
+        DriftKind::Created => text.magenta().bold().to_string(),
+    }
+}
+
+pub async fn check(name: &str, format: OutputFormat, ctx: &Context<'_>) {
+    let plan = ctx
+        .services
+        .plan_service
+        .find_by_name(name)
+        .await
+        .exit_on_err(&format!("❌ Failed to find plan '{}'", name))
+        .unwrap_or_else(|| {
+            eprintln!("❌ Plan '{}' not found", name);
+            std::process::exit(1);
+        });
+
+    let (progress, tx) = ProgressRenderer::new(None);
+    if format == OutputFormat::Json {
+        progress.hide();
+    }
+    let res = ctx
+        .services
+        .deployment_service
+        .find_plan_drift(plan.id, &ProgressReporter::new(Some(tx)))
+        .await;
+    progress.finish().await;
+
+    let drifted = res.exit_on_err(&format!("❌ Failed to check drift of plan '{}'", name));
+    let has_drift = !drifted.is_empty();
+
+    if format == OutputFormat::Json {
+        let document = DriftDocument::new(&plan.name, Local::now().naive_local(), drifted);
+        let json =
+            serde_json::to_string_pretty(&document).exit_on_err("❌ Failed to serialize drift");
+        println!("{}", json);
+    } else if !has_drift {
+        println!("✅ No drift found for plan '{}'", plan.name);
+    } else {
+        let rows: Vec<DriftRow> = drifted
+            .iter()
+            .enumerate()
+            .map(|(i, o)| DriftRow {
+                index: (i + 1).to_string().bright_black().to_string(),
+                object: o.qualified_name(),
+                kind: colored_kind(o.kind),
+                deployment_id: o.deployment_id.to_string(),
+            })
+            .collect();
+        let table = Table::new(rows)
+            .with(Style::rounded())
+            .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+            .to_string();
+        println!("{}", table);
+        println!(
+            "⚠️ {} object(s) of plan '{}' changed outside of leaf",
+            drifted.len(),
+            plan.name
+        );
+    }
+
+    if has_drift {
+        std::process::exit(EXIT_CHANGES_PRESENT);
+    }
+}
@@ -71,7 +71,7 @@ async fn init_database(ctx: &Context<'_>) {
         .exit_on_err("Failed to connect to database");
 
     info!("Running migrations");
-    migrations::up(&db)
+    migrations::up_with_secrets(&db, ctx.settings)
         .await
         .exit_on_err("Failed to run migrations");
 }
@@ -0,0 +1,144 @@
+use clap::Subcommand;
+use colored::Colorize;
+use tabled::{
+    Table, Tabled,
+    settings::{Alignment, Modify, Style, object::Rows},
+};
+
+use crate::cli::{Context, commands::ExitOnErr};
+
+#[derive(Subcommand, Debug)]
+pub enum LockCommands {
+    /// List deployment locks
+    List,
+
+    /// Release a deployment lock
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf locks release 3
+    This will release the lock 3 if its owner stopped sending heartbeats.
+
+    # leaf locks release 3 --force
+    This will release the lock 3 even if its owner is still alive. Only use this if you are sure
+    the owner process is gone, otherwise two processes may deploy to the same target.
+    "#)]
+    Release {
+        /// Lock ID
+        #[arg(required = true)]
+        id: i32,
+
+        /// Release the lock even if its heartbeat is live
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Tabled)]
+struct LockRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "ID")]
+    id: String,
+
+    #[tabled(rename = "Plan")]
+    plan: String,
+
+    #[tabled(rename = "Target")]
+    target: String,
+
+    #[tabled(rename = "Operation")]
+    operation: String,
+
+    #[tabled(rename = "Owner")]
+    owner: String,
+
+    #[tabled(rename = "Acquired At")]
+    acquired_at: String,
+
+    #[tabled(rename = "Heartbeat At")]
+    heartbeat_at: String,
+
+    #[tabled(rename = "State")]
+    state: String,
+}
+
+pub async fn execute(action: &LockCommands, ctx: &Context<'_>) {
+    match action {
+        LockCommands::List => list(ctx).await,
+        LockCommands::Release { id, force } => release(*id, *force, ctx).await,
+    }
+}
+
+async fn list(ctx: &Context<'_>) {
+    let locks = ctx
+        .services
+        .lock_service
+        .get_all()
+        .await
+        .exit_on_err("❌ Failed to list locks");
+
+    println!("{}", "=== Locks ===".blue());
+
+    if locks.is_empty() {
+        println!("✅ No locks found");
+        return;
+    }
+
+    let mut table_data = Vec::new();
+    for (i, lock) in locks.into_iter().enumerate() {
+        let plan = ctx
+            .services
+            .plan_service
+            .get_by_id(lock.plan_id)
+            .await
+            .map(|p| p.name)
+            .unwrap_or_else(|_| lock.plan_id.to_string());
+        let target = ctx
+            .services
+            .connection_service
+            .find_by_id(lock.target_connection_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|c| c.name)
+            .unwrap_or_else(|| lock.target_connection_id.to_string());
+        let state = if ctx.services.lock_service.is_stale(&lock) {
+            "STALE".yellow().bold().to_string()
+        } else {
+            "LIVE".green().bold().to_string()
+        };
+
+        table_data.push(LockRow {
+            index: (i + 1).to_string().bright_black().to_string(),
+            id: lock.id.to_string(),
+            plan,
+            target,
+            operation: lock.operation,
+            owner: format!("{} (pid {})", lock.owner_host, lock.owner_pid),
+            acquired_at: lock.acquired_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            heartbeat_at: lock.heartbeat_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            state,
+        });
+    }
+
+    let table = Table::new(table_data)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
+}
+
+async fn release(id: i32, force: bool, ctx: &Context<'_>) {
+    let lock = ctx
+        .services
+        .lock_service
+        .release(id, force)
+        .await
+        .exit_on_err(&format!("❌ Failed to release lock {}", id));
+
+    println!(
+        "🔓 Lock {} of plan {} held by {} (pid {}) released",
+        lock.id, lock.plan_id, lock.owner_host, lock.owner_pid
+    );
+}
@@ -0,0 +1,265 @@
+use clap::Subcommand;
+use colored::*;
+use tabled::{
+    Table, Tabled,
+    settings::{Alignment, Modify, Style, object::Rows},
+};
+
+use crate::{
+    cli::{Context, commands::ExitOnErr},
+    services::ObjectVersion,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum ObjectCommands {
+    /// List every changeset that touched an object across all plans and deployments
+    #[command(after_help = r#"
+EXAMPLES:
+    # leaf objects history HR.EMPLOYEES
+    This will list every version of `HR.EMPLOYEES` leaf deployed, oldest first, with the plan,
+    deployment, status, timestamps, scripts and the OS user and host that ran it.
+
+    # leaf objects history 'HR."V.2024"'
+    Names with dots are quoted like in SQL.
+
+    # leaf objects history HR.PKG_PAYROLL --type "PACKAGE BODY" --show-ddl 3
+    This will print the DDL that version 3 of the package body deployed.
+
+    # leaf objects history HR.PKG_PAYROLL --type "PACKAGE BODY" --show-ddl 3 --before
+    This will print the DDL the package body had on the target before version 3 was deployed.
+    "#)]
+    History {
+        /// Object as OWNER.NAME, names are case sensitive like in the database and can be
+        /// double-quoted to contain dots
+        #[arg(required = true, value_parser = parse_qualified_name)]
+        object: (String, String),
+
+        /// Object type, all types with the name by default
+        #[arg(long = "type", short = 't')]
+        object_type: Option<String>,
+
+        /// Print the DDL of the given version instead of the history
+        #[arg(long, value_name = "N")]
+        show_ddl: Option<usize>,
+
+        /// With --show-ddl, print the DDL the target had before the version was deployed
+        #[arg(long, requires = "show_ddl")]
+        before: bool,
+    },
+}
+
+/// Splits `OWNER.NAME` at the dot between the parts. A part in double quotes may contain
+/// dots, `""` inside the quotes is a literal quote.
+fn parse_qualified_name(value: &str) -> Result<(String, String), String> {
+    let invalid = || format!("expected OWNER.NAME, got '{}'", value);
+    let mut parts = vec![String::new()];
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().unwrap();
+        match c {
+            '"' if part.is_empty() => loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        part.push('"');
+                    }
+                    Some('"') => {
+                        if !matches!(chars.peek(), None | Some('.')) {
+                            return Err(invalid());
+                        }
+                        break;
+                    }
+                    Some(c) => part.push(c),
+                    None => return Err(invalid()),
+                }
+            },
+            '"' => return Err(invalid()),
+            '.' => parts.push(String::new()),
+            c => part.push(c),
+        }
+    }
+    match <[String; 2]>::try_from(parts) {
+        Ok([owner, name]) if !owner.is_empty() && !name.is_empty() => Ok((owner, name)),
+        _ => Err(invalid()),
+    }
+}
+
+#[derive(Tabled)]
+struct ObjectVersionRow {
+    #[tabled(rename = "Version")]
+    version: String,
+
+    #[tabled(rename = "Type")]
+    object_type: String,
+
+    #[tabled(rename = "Plan")]
+    plan: String,
+
+    #[tabled(rename = "Deployment")]
+    deployment_id: String,
+
+    #[tabled(rename = "Status")]
+    status: String,
+
+    #[tabled(rename = "Started At")]
+    started_at: String,
+
+    #[tabled(rename = "Ended At")]
+    ended_at: String,
+
+    #[tabled(rename = "Scripts")]
+    scripts: String,
+
+    #[tabled(rename = "Run By")]
+    run_by: String,
+}
+
+pub async fn execute(action: &ObjectCommands, ctx: &Context<'_>) {
+    match action {
+        ObjectCommands::History {
+            object,
+            object_type,
+            show_ddl,
+            before,
+        } => {
+            history(
+                &object.0,
+                &object.1,
+                object_type.as_deref(),
+                *show_ddl,
+                *before,
+                ctx,
+            )
+            .await
+        }
+    }
+}
+
+async fn history(
+    owner: &str,
+    name: &str,
+    object_type: Option<&str>,
+    show_ddl: Option<usize>,
+    before: bool,
+    ctx: &Context<'_>,
+) {
+    let versions = ctx
+        .services
+        .deployment_service
+        .find_object_history(owner, name, object_type)
+        .await
+        .exit_on_err(&format!("❌ Failed to fetch history of {}.{}", owner, name));
+
+    if versions.is_empty() {
+        println!("✅ No changesets found for {}.{}", owner, name);
+        return;
+    }
+
+    match show_ddl {
+        Some(version) => print_ddl(&versions, version, before),
+        None => print_history(&versions),
+    }
+}
+
+fn print_history(versions: &[ObjectVersion]) {
+    let format_time = |time: Option<chrono::NaiveDateTime>| {
+        time.map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".bright_black().to_string())
+    };
+
+    let rows: Vec<ObjectVersionRow> = versions
+        .iter()
+        .map(|v| {
+            let first_line = v
+                .changes
+                .first()
+                .and_then(|c| c.script.trim().lines().next())
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let scripts = match v.changes.len() {
+                0 => "-".bright_black().to_string(),
+                1 => first_line,
+                n => format!("{} (+{} more)", first_line, n - 1),
+            };
+            let run_by = match (&v.deployment.owner_user, &v.deployment.owner_host) {
+                (Some(user), Some(host)) => format!("{}@{}", user, host),
+                (Some(user), None) => user.clone(),
+                _ => "-".bright_black().to_string(),
+            };
+            ObjectVersionRow {
+                version: v.version.to_string().bright_black().to_string(),
+                object_type: v.changeset.object_type.clone(),
+                plan: v.plan_name.clone(),
+                deployment_id: v.deployment.id.to_string(),
+                status: v.changeset.status.to_colored_string(),
+                started_at: format_time(v.changeset.started_at),
+                ended_at: format_time(v.changeset.ended_at),
+                scripts,
+                run_by,
+            }
+        })
+        .collect();
+
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
+}
+
+fn print_ddl(versions: &[ObjectVersion], version: usize, before: bool) {
+    let Some(v) = versions.iter().find(|v| v.version == version) else {
+        eprintln!(
+            "❌ Version {} not found, the object has {} version(s)",
+            version,
+            versions.len()
+        );
+        std::process::exit(1);
+    };
+
+    let ddl = if before {
+        &v.changeset.target_ddl
+    } else {
+        &v.changeset.source_ddl
+    };
+    match ddl {
+        Some(ddl) => println!("{}", ddl),
+        None if before => println!(
+            "✅ {} {}.{} did not exist before version {}",
+            v.changeset.object_type, v.changeset.object_owner, v.changeset.object_name, version
+        ),
+        None => println!(
+            "✅ Version {} dropped {} {}.{}",
+            version, v.changeset.object_type, v.changeset.object_owner, v.changeset.object_name
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_qualified_name() {
+        let parsed = |v: &str| parse_qualified_name(v).ok();
+        let name = |o: &str, n: &str| Some((o.to_string(), n.to_string()));
+
+        assert_eq!(parsed("HR.EMP"), name("HR", "EMP"));
+        assert_eq!(parsed(r#"HR."V.2024""#), name("HR", "V.2024"));
+        assert_eq!(parsed(r#""My.App"."Emp""#), name("My.App", "Emp"));
+        assert_eq!(parsed(r#"HR."Say ""hi""""#), name("HR", r#"Say "hi""#));
+
+        for invalid in [
+            "HR",
+            "HR.",
+            ".EMP",
+            "HR.EMP.X",
+            r#"HR."EMP"#,
+            r#"HR."A"B"#,
+            r#"HR.A"B"#,
+        ] {
+            assert_eq!(parsed(invalid), None, "{}", invalid);
+        }
+    }
+}
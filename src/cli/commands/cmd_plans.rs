@@ -3,21 +3,27 @@ use std::path::PathBuf;
 use chrono::NaiveDateTime;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
 use inquire::Confirm;
 use tabled::{Table, Tabled, builder::Builder};
-use tokio::sync::mpsc;
 use tracing::error;
 
 use crate::{
     cli::{
         Context,
-        commands::{ExitOnErr, get_cut_off_date_or_bail, new_spinner},
+        commands::{
+            ExitOnErr, ProgressRenderer,
+            cmd_deployments::{report_deployment, verify_deployment_or_bail},
+            get_cut_off_date_or_bail, print_delta_document_and_exit,
+            shared::cancel_plan_on_signal,
+        },
     },
-    types::{Hooks, PlanStatus},
+    delta::DeltaDocument,
+    errors::{DeployError, DeploymentCancelledError, PrepareCancelledError, RollbackChainError},
+    services::{ListEdit, PlanEdit, PlanSpec},
+    types::{EventsFormat, Hooks, OutputFormat, PlanStatus, ReportFormat, RollbackTarget},
     utils::{
-        DeploymentContext, ProgressReporter, deployment_context::DeploymentContextOptions,
-        parsers::parse_cutoff_date, validate_dir,
+        CancelToken, DeploymentContext, deployment_context::DeploymentContextOptions,
+        parsers::parse_cutoff_date, print_output, validate_dir,
     },
 };
 use tabled::{settings::Alignment, settings::Modify, settings::Style, settings::object::Rows};
@@ -28,6 +34,10 @@ EXAMPLES:
     # leaf plans run demo3 --dry --collect-scripts --output-path ./.uncommitted/output --cutoff-date 2021.01.01
     This will run the plan `demo3` with dry-run mode (no changes will be applied), collect scripts, and output scripts to `./.uncommitted/output`.
     The deployment will start from the last successful deployment date (2021.01.01) or the app will exit.
+
+    # leaf plans run demo3 --dry --format json > changes.json
+    This will print every change of the plan as a JSON document with its operation, risk, scripts and
+    rollback scripts. The exit code is 0 when there are no changes, 2 when there are changes and 1 on error.
     "#)]
 pub struct PlansRunArgs {
     /// Plan name, case insensitive
@@ -61,13 +71,29 @@ pub struct PlansRunArgs {
     #[arg(long, value_name = "DIR", value_parser = validate_dir)]
     output_path: Option<PathBuf>,
 
-    /// Show report after running the plan (!not fully implemented yet)
+    /// Show the deployment report after running the plan
     #[arg(long, default_value_t = false)]
     show_report: bool,
 
     /// Disable hooks, no hooks will be executed when this flag is provided
     #[arg( long, default_value = None)]
     disable_hooks: Option<bool>,
+
+    /// Release tag for the deployment, can be used with `plans rollback --to-tag`
+    #[arg(long, value_name = "TAG")]
+    tag: Option<String>,
+
+    /// Verify the deployed objects against the source after a successful run
+    #[arg(long)]
+    verify: bool,
+
+    /// Output format, `json` prints the changes of a dry run as a single JSON document
+    #[arg(long, default_value = "text", conflicts_with = "events")]
+    format: OutputFormat,
+
+    /// Stream progress events to stdout instead of showing a spinner, e.g. `jsonl`
+    #[arg(long, value_name = "FORMAT")]
+    events: Option<EventsFormat>,
 }
 
 impl PlansRunArgs {
@@ -75,10 +101,159 @@ impl PlansRunArgs {
         if !self.collect_scripts && self.output_path.is_some() {
             return Err("--collect-scripts is required when --scripts-dir is specified".into());
         }
+        if self.dry && self.tag.is_some() {
+            return Err("--tag can not be used in dry-run mode".into());
+        }
+        if self.dry && self.verify {
+            return Err("--verify can not be used in dry-run mode".into());
+        }
+        if !self.dry && self.format == OutputFormat::Json {
+            return Err("--format json can only be used in dry-run mode".into());
+        }
         Ok(())
     }
 }
 
+#[derive(Parser, Debug, Clone)]
+#[clap(after_help = r#"
+EXAMPLES:
+    # leaf plans update demo3 --add-schemas SALES --remove-exclude-object-names TMP_LOAD
+    This will add schema `SALES` to the plan `demo3` and deploy `TMP_LOAD` again.
+    The plan keeps its deployment history, the change is recorded as a new plan revision.
+
+    # leaf plans update demo3 --target prod2 --rename demo3-prod2
+    This will point the plan to the target connection `prod2` and rename it.
+
+    # leaf plans update demo3 --clear-hooks post_apply_deployment \
+        --add-hook "post_apply_deployment=begin my_user.notify('{{ plan }}'); end;"
+    This will replace the post apply hooks of the plan.
+    "#)]
+pub struct PlansUpdateArgs {
+    /// Plan name, case insensitive
+    #[arg(required = true)]
+    name: String,
+
+    /// New name of the plan
+    #[arg(long)]
+    rename: Option<String>,
+
+    /// Source connection name
+    #[arg(long)]
+    source: Option<String>,
+
+    /// Target connection name
+    #[arg(long)]
+    target: Option<String>,
+
+    /// Comma-separated list of schemas to add
+    #[arg(long, value_delimiter = ',')]
+    add_schemas: Vec<String>,
+
+    /// Comma-separated list of schemas to remove
+    #[arg(long, value_delimiter = ',')]
+    remove_schemas: Vec<String>,
+
+    /// Comma-separated list of object types to exclude
+    #[arg(long, value_delimiter = ',')]
+    add_exclude_object_types: Vec<String>,
+
+    /// Comma-separated list of object types to deploy again
+    #[arg(long, value_delimiter = ',')]
+    remove_exclude_object_types: Vec<String>,
+
+    /// Comma-separated list of object names to exclude
+    #[arg(long, value_delimiter = ',')]
+    add_exclude_object_names: Vec<String>,
+
+    /// Comma-separated list of object names to deploy again
+    #[arg(long, value_delimiter = ',')]
+    remove_exclude_object_names: Vec<String>,
+
+    /// Comma-separated list of object types not to drop
+    #[arg(long, value_delimiter = ',')]
+    add_disabled_drop_types: Vec<String>,
+
+    /// Comma-separated list of object types to drop again
+    #[arg(long, value_delimiter = ',')]
+    remove_disabled_drop_types: Vec<String>,
+
+    /// Disable all DROP operations
+    #[arg(long)]
+    disable_all_drops: Option<bool>,
+
+    /// Fail fast mode
+    #[arg(long)]
+    fail_fast: Option<bool>,
+
+    /// Disable hooks
+    #[arg(long)]
+    disable_hooks: Option<bool>,
+
+    /// Append a script to a hook stage, e.g. `pre_apply_deployment=SCRIPT`
+    #[arg(long, value_name = "STAGE=SCRIPT", value_parser = parse_hook)]
+    add_hook: Vec<(String, String)>,
+
+    /// Comma-separated list of hook stages whose scripts are removed
+    #[arg(long, value_name = "STAGE", value_delimiter = ',')]
+    clear_hooks: Vec<String>,
+}
+
+impl PlansUpdateArgs {
+    fn to_edit(&self) -> PlanEdit {
+        let list = |add: &Vec<String>, remove: &Vec<String>| ListEdit {
+            add: add.clone(),
+            remove: remove.clone(),
+        };
+        PlanEdit {
+            rename: self.rename.clone(),
+            source: self.source.clone(),
+            target: self.target.clone(),
+            schemas: list(&self.add_schemas, &self.remove_schemas),
+            exclude_object_types: list(
+                &self.add_exclude_object_types,
+                &self.remove_exclude_object_types,
+            ),
+            exclude_object_names: list(
+                &self.add_exclude_object_names,
+                &self.remove_exclude_object_names,
+            ),
+            disabled_drop_types: list(
+                &self.add_disabled_drop_types,
+                &self.remove_disabled_drop_types,
+            ),
+            disable_all_drops: self.disable_all_drops,
+            fail_fast: self.fail_fast,
+            disable_hooks: self.disable_hooks,
+            add_hooks: self.add_hook.clone(),
+            clear_hooks: self.clear_hooks.clone(),
+        }
+    }
+}
+
+fn parse_hook(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((stage, script)) if !stage.is_empty() && !script.trim().is_empty() => {
+            Ok((stage.trim().to_string(), script.to_string()))
+        }
+        _ => Err(format!("expected STAGE=SCRIPT, got '{}'", value)),
+    }
+}
+
+#[derive(Tabled)]
+struct PlanRevisionRow {
+    #[tabled(rename = "Revision")]
+    revision: String,
+
+    #[tabled(rename = "Name")]
+    name: String,
+
+    #[tabled(rename = "Created At")]
+    created_at: String,
+
+    #[tabled(rename = "Changed")]
+    changed_fields: String,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ListSubcommand {
     /// List all plans (default)
@@ -109,6 +284,104 @@ pub enum ListSubcommand {
     },
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct PlansAddArgs {
+    /// Name of the plan
+    #[arg(long, required = true)]
+    name: String,
+
+    /// Source connection name
+    #[arg(long, required = true)]
+    source: String,
+
+    /// Target connection name
+    #[arg(long, required = true)]
+    target: String,
+
+    /// Comma-separated list of schemas to include in the plan
+    #[arg(long, required = true, value_delimiter = ',')]
+    schemas: Vec<String>,
+
+    /// Comma-separated list of object types to exclude from the plan
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_types: Vec<String>,
+
+    /// Comma-separated list of object names to exclude from the plan
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_names: Vec<String>,
+
+    /// Comma-separated list of disabled object types do drop
+    /// (e.g., TABLE, VIEW, PROCEDURE, FUNCTION, TRIGGER, etc.)
+    #[arg(long, value_delimiter = ',')]
+    disabled_drop_types: Vec<String>,
+
+    /// Disable all DROP operations
+    #[arg(long, default_value = None)]
+    disable_all_drops: Option<bool>,
+
+    /// Fail fast mode
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Disable hooks
+    #[arg(long, default_value_t = false)]
+    disable_hooks: bool,
+
+    /// Number of distinct approvals a deployment needs before it can be applied
+    #[arg(long, default_value_t = 0)]
+    required_approvals: i32,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct PlansRollbackArgs {
+    /// Plan name, case insensitive
+    #[arg(required_unless_present = "plan_name")]
+    plan: Option<String>,
+
+    /// Plan name, case insensitive (same as the positional argument)
+    #[arg(long = "plan", value_name = "PLAN", conflicts_with = "plan")]
+    plan_name: Option<String>,
+
+    /// Roll back every successful deployment after this deployment ID
+    #[arg(long, value_name = "ID", conflicts_with = "to_tag")]
+    to_deployment: Option<i32>,
+
+    /// Roll back every successful deployment after the deployment with this tag
+    #[arg(long, value_name = "TAG")]
+    to_tag: Option<String>,
+
+    /// Disable hooks during the rollback process
+    #[arg(long, short, required = false)]
+    disable_hooks: Option<bool>,
+
+    /// Dry run mode, this will not roll back anything
+    #[arg(long)]
+    dry: bool,
+
+    /// Collect rollback scripts and a summary of the affected objects
+    #[arg(long)]
+    collect_scripts: bool,
+
+    /// Scripts output directory
+    /// This is only valid when --collect-scripts is set
+    #[arg(long, value_name = "DIR", value_parser = validate_dir, requires = "collect_scripts")]
+    output_path: Option<PathBuf>,
+
+    /// Stream progress events to stdout instead of showing a spinner, e.g. `jsonl`
+    #[arg(long, value_name = "FORMAT")]
+    events: Option<EventsFormat>,
+}
+
+impl PlansRollbackArgs {
+    fn target(&self) -> Option<RollbackTarget> {
+        match (self.to_deployment, &self.to_tag) {
+            (Some(id), _) => Some(RollbackTarget::Deployment(id)),
+            (None, Some(tag)) => Some(RollbackTarget::Tag(tag.clone())),
+            (None, None) => None,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 pub struct ListCommand {
     #[command(subcommand)]
@@ -140,48 +413,7 @@ EXAMPLES:
     If --disable-all-drops is not specified, it will use the value from the `.env` file which is `true` by default.
     To enable all DROP operations, set the value to `false` like this --disable-all-drops=false
     "#)]
-    Add {
-        /// Name of the plan
-        #[arg(long, required = true)]
-        name: String,
-
-        /// Source connection name
-        #[arg(long, required = true)]
-        source: String,
-
-        /// Target connection name
-        #[arg(long, required = true)]
-        target: String,
-
-        /// Comma-separated list of schemas to include in the plan
-        #[arg(long, required = true, value_delimiter = ',')]
-        schemas: Vec<String>,
-
-        /// Comma-separated list of object types to exclude from the plan
-        #[arg(long, value_delimiter = ',')]
-        exclude_object_types: Vec<String>,
-
-        /// Comma-separated list of object names to exclude from the plan
-        #[arg(long, value_delimiter = ',')]
-        exclude_object_names: Vec<String>,
-
-        /// Comma-separated list of disabled object types do drop
-        /// (e.g., TABLE, VIEW, PROCEDURE, FUNCTION, TRIGGER, etc.)
-        #[arg(long, value_delimiter = ',')]
-        disabled_drop_types: Vec<String>,
-
-        /// Disable all DROP operations
-        #[arg(long, default_value = None)]
-        disable_all_drops: Option<bool>,
-
-        /// Fail fast mode
-        #[arg(long)]
-        fail_fast: bool,
-
-        /// Disable hooks
-        #[arg(long, default_value_t = false)]
-        disable_hooks: bool,
-    },
+    Add(PlansAddArgs),
     /// List plans, schemas, excluded object types
     #[clap(after_help = r#"
 EXAMPLES:
@@ -199,6 +431,15 @@ EXAMPLES:
     "#)]
     List(ListCommand),
 
+    /// Change a plan in place, keeping its deployment history
+    Update(PlansUpdateArgs),
+
+    /// List the revisions of a plan
+    Revisions {
+        #[arg(required = true)]
+        plan: String,
+    },
+
     /// Remove a plan
     Remove {
         #[arg(required = true)]
@@ -224,15 +465,23 @@ EXAMPLES:
     Run(PlansRunArgs),
 
     /// Rollback a plan
-    Rollback {
-        /// Plan name, case insensitive
-        #[arg(required = true)]
-        plan: String,
+    #[clap(after_help = r#"
+EXAMPLES:
+    # leaf plans rollback demo3
+    This will roll back the last successful deployment of the plan `demo3`.
 
-        /// Disable hooks during the rollback process
-        #[arg(long, short, required = false)]
-        disable_hooks: Option<bool>,
-    },
+    # leaf plans rollback --plan demo3 --to-deployment 12
+    This will roll back every successful deployment of the plan `demo3` created after
+    deployment 12, newest first. Deployment 12 itself is kept.
+
+    # leaf plans rollback --plan demo3 --to-tag v1.4
+    Same as above, using the deployment tagged `v1.4` as the rollback point.
+
+    # leaf plans rollback --plan demo3 --to-tag v1.4 --dry --collect-scripts --output-path ./.uncommitted/rollback
+    This will write the rollback scripts in execution order and a summary of the affected objects
+    to `./.uncommitted/rollback` without touching the target database or the repository.
+    "#)]
+    Rollback(PlansRollbackArgs),
 }
 
 #[derive(Tabled)]
@@ -264,33 +513,7 @@ struct PlanRow {
 
 pub async fn execute(action: &PlanCommands, ctx: &Context<'_>) {
     match action {
-        PlanCommands::Add {
-            name,
-            source,
-            target,
-            schemas,
-            exclude_object_types,
-            exclude_object_names,
-            disabled_drop_types,
-            disable_all_drops,
-            fail_fast,
-            disable_hooks,
-        } => {
-            add(
-                name,
-                source,
-                target,
-                schemas,
-                exclude_object_types,
-                exclude_object_names,
-                disabled_drop_types,
-                *disable_all_drops,
-                *fail_fast,
-                *disable_hooks,
-                ctx,
-            )
-            .await
-        }
+        PlanCommands::Add(args) => add(args, ctx).await,
         PlanCommands::List(list_cmd) => match &list_cmd.subcommand {
             Some(sub) => match sub {
                 ListSubcommand::All => list_plans(ctx).await,
@@ -307,78 +530,46 @@ pub async fn execute(action: &PlanCommands, ctx: &Context<'_>) {
             },
             None => list_plans(ctx).await, // default behavior when no subcommand is given
         },
+        PlanCommands::Update(args) => update(args, ctx).await,
+        PlanCommands::Revisions { plan } => list_revisions(plan, ctx).await,
         PlanCommands::Remove { name } => remove(name, ctx).await,
         PlanCommands::Prune { yes } => prune(yes, ctx).await,
         PlanCommands::Run(args) => {
             args.validate().exit_on_err("Failed to validate arguments");
-            run(
-                &args.name,
-                &args.dry,
-                args.cutoff_date,
-                args.fail_fast,
-                args.disable_hooks,
-                args.show_report,
-                args.collect_scripts,
-                args.output_path.clone(),
-                ctx,
-            )
-            .await
+            run(args, ctx).await
         }
-        PlanCommands::Rollback {
-            plan,
-            disable_hooks,
-        } => rollback(&plan, *disable_hooks, ctx).await,
+        PlanCommands::Rollback(args) => rollback(args, ctx).await,
         PlanCommands::Reset { plan, yes } => reset(&plan, *yes, ctx).await,
     }
 }
 
-pub async fn add(
-    name: &str,
-    source: &str,
-    target: &str,
-    schemas: &Vec<String>,
-    exclude_object_types: &Vec<String>,
-    exclude_object_names: &Vec<String>,
-    excluded_drop_types: &Vec<String>,
-    disable_all_drops: Option<bool>,
-    fail_fast: bool,
-    disable_hooks: bool,
-    ctx: &Context<'_>,
-) {
-    let combined_exclude_object_types = ctx
-        .settings
-        .rules
-        .combined_exclude_object_types(Some(exclude_object_types.to_vec()));
-
-    let combined_exclude_object_names = ctx
-        .settings
-        .rules
-        .combined_exclude_object_names(Some(exclude_object_names.to_vec()));
-
-    let combined_disabled_drop_types = ctx
-        .settings
-        .rules
-        .combined_disabled_drop_types(Some(excluded_drop_types.to_vec()));
+pub async fn add(args: &PlansAddArgs, ctx: &Context<'_>) {
+    let rules = &ctx.settings.rules;
+    let spec = PlanSpec {
+        name: args.name.clone(),
+        source: args.source.clone(),
+        target: args.target.clone(),
+        schemas: args.schemas.clone(),
+        exclude_object_types: rules
+            .combined_exclude_object_types(Some(args.exclude_object_types.clone())),
+        exclude_object_names: rules
+            .combined_exclude_object_names(Some(args.exclude_object_names.clone())),
+        disabled_drop_types: rules
+            .combined_disabled_drop_types(Some(args.disabled_drop_types.clone())),
+        disable_all_drops: args.disable_all_drops.unwrap_or(rules.disable_all_drops),
+        fail_fast: args.fail_fast,
+        disable_hooks: args.disable_hooks,
+        hooks: Some(Hooks::from_config(ctx.settings.hooks.clone())),
+        required_approvals: args.required_approvals,
+    };
 
     ctx.services
         .plan_service
-        .create(
-            name,
-            source,
-            target,
-            schemas,
-            combined_exclude_object_types,
-            combined_exclude_object_names,
-            combined_disabled_drop_types,
-            disable_all_drops.unwrap_or(ctx.settings.rules.disable_all_drops),
-            fail_fast,
-            disable_hooks,
-            Some(Hooks::from_config(ctx.settings.hooks.clone())),
-        )
+        .create(&spec)
         .await
-        .exit_on_err(&format!("❌ Plan creation failed for '{}'", name));
+        .exit_on_err(&format!("❌ Plan creation failed for '{}'", args.name));
 
-    println!("✅ Plan created successfully for '{}'", name);
+    println!("✅ Plan created successfully for '{}'", args.name);
 }
 
 pub async fn list_plans(ctx: &Context<'_>) {
@@ -527,18 +718,14 @@ pub async fn prune(yes: &bool, ctx: &Context<'_>) {
     println!("✅ Deleted all {} plans", count);
 }
 
-pub async fn run(
-    name: &str,
-    dry: &bool,
-    cutoff_date: Option<NaiveDateTime>,
-    fail_fast: Option<bool>,
-    disable_hooks: Option<bool>,
-    show_report: bool,
-    collect_scripts: bool,
-    output_path: Option<PathBuf>,
-    ctx: &Context<'_>,
-) {
-    let (spinner, tx) = new_spinner();
+pub async fn run(args: &PlansRunArgs, ctx: &Context<'_>) {
+    let name = args.name.as_str();
+    let tag = args.tag.as_deref();
+    let (format, events, show_report) = (args.format, args.events, args.show_report);
+    let (progress, tx) = ProgressRenderer::new(events);
+    if format == OutputFormat::Json {
+        progress.hide();
+    }
 
     let plan = ctx
         .services
@@ -551,49 +738,113 @@ pub async fn run(
             std::process::exit(1);
         });
 
-    let cutoff_date = get_cut_off_date_or_bail(cutoff_date, plan.id, ctx).await;
+    let cutoff_date = get_cut_off_date_or_bail(args.cutoff_date, plan.id, ctx).await;
+
+    if let Some(tag) = tag {
+        bail_if_tag_used(plan.id, tag, ctx).await;
+    }
+
+    let cancel_token = CancelToken::new();
+    let signals = cancel_plan_on_signal(plan.id, cancel_token.clone(), ctx);
 
     let mut dctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
-        *dry,
-        collect_scripts,
-        output_path,
+        args.dry,
+        args.collect_scripts,
+        args.output_path.clone(),
         None,
         Some(tx),
     )))
-    .exit_on_err("Failed to initialize deployment context"); // TODO: Add more info
+    .exit_on_err("Failed to initialize deployment context") // TODO: Add more info
+    .with_cancel_token(cancel_token);
 
     let res = ctx
         .services
         .deployment_service
         .run(
             plan.id,
-            fail_fast.unwrap_or(false),
+            args.fail_fast.unwrap_or(false),
             cutoff_date,
-            disable_hooks,
+            args.disable_hooks,
             &mut dctx,
         )
         .await;
+    drop(signals);
+
+    if let Err(e) = &res
+        && let Some(cancelled) = e.downcast_ref::<DeploymentCancelledError>()
+    {
+        progress.finish().await;
+        eprintln!("🛑 {}", cancelled);
+        eprintln!(
+            "Use `leaf deployments apply --deployment-id {}` to resume it or `leaf plans rollback {}` to revert it.",
+            cancelled.deployment_id, name
+        );
+        std::process::exit(130);
+    }
 
-    if res.is_err() {
-        error!("Failed to run plan: {:?}", res.as_ref().err());
+    if let Err(e) = &res
+        && let Some(cancelled) = e.downcast_ref::<PrepareCancelledError>()
+    {
+        progress.finish().await;
+        eprintln!("🛑 {}", cancelled);
+        std::process::exit(130);
+    }
+
+    if let Err(e) = &res {
+        progress.finish().await;
+        error!("Failed to run plan: {:?}", e);
+        // The report of the failed deployment shows which changes failed and why
+        if show_report
+            && let Some(DeployError::Errors { deployment_id, .. }) = e.downcast_ref::<DeployError>()
+        {
+            report_deployment(*deployment_id, ReportFormat::Terminal, None, ctx).await;
+        }
         std::process::exit(1);
     }
 
+    if format == OutputFormat::Json {
+        progress.finish().await;
+        let document = DeltaDocument::new(&plan.name, cutoff_date, true, None, dctx.deltas());
+        print_delta_document_and_exit(&document);
+    }
+
     if dctx.is_dry_run() {
         dctx.print_summary("✅ Dry run completed successfully");
     }
 
-    spinner.finish_and_clear();
+    progress.finish().await;
+
+    if let (Some(tag), Ok(Some(deployment_id))) = (tag, res.as_ref()) {
+        ctx.services
+            .deployment_service
+            .tag_deployment(*deployment_id, tag)
+            .await
+            .exit_on_err(&format!("❌ Failed to tag deployment {}", deployment_id));
+        print_output(format!("🏷️ Deployment {} tagged '{}'", deployment_id, tag));
+    }
+
+    if let (true, Ok(Some(deployment_id))) = (args.verify, res.as_ref()) {
+        verify_deployment_or_bail(*deployment_id, events, ctx).await;
+    }
+
+    print_output(format!(
+        "✅ Deployment for plan '{}' completed successfully",
+        name
+    ));
 
     if show_report {
-        let _ = res.unwrap();
-        todo!();
-    } else {
-        println!("✅ Deployment for plan '{}' completed successfully", name);
+        match res.as_ref() {
+            Ok(Some(deployment_id)) => {
+                report_deployment(*deployment_id, ReportFormat::Terminal, None, ctx).await
+            }
+            _ => print_output("ℹ️ No deployment was recorded, there is nothing to report"),
+        }
     }
 }
 
-async fn rollback(plan_name: &str, disable_hooks: Option<bool>, ctx: &Context<'_>) {
+async fn rollback(args: &PlansRollbackArgs, ctx: &Context<'_>) {
+    let plan_name = args.plan.as_ref().or(args.plan_name.as_ref()).unwrap();
+    let (dry, disable_hooks) = (args.dry, args.disable_hooks);
     let plan = ctx
         .services
         .plan_service
@@ -607,46 +858,206 @@ async fn rollback(plan_name: &str, disable_hooks: Option<bool>, ctx: &Context<'_
     }
     let plan = plan.unwrap();
 
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
-            .template("{spinner:.cyan} [{elapsed_precise}] {msg}")
-            .unwrap(),
-    );
-    spinner.enable_steady_tick(std::time::Duration::from_millis(80));
-    spinner.set_message(format!(
+    let (progress, tx) = ProgressRenderer::new(args.events);
+    progress.set_message(format!(
         "Rolling back deployment for plan '{}'...",
         plan_name
     ));
 
-    let (tx, mut rx) = mpsc::unbounded_channel();
-    let progress = ProgressReporter::new(Some(tx));
+    let mut dctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
+        dry,
+        args.collect_scripts,
+        args.output_path.clone(),
+        None,
+        Some(tx),
+    )))
+    .exit_on_err("Failed to initialize deployment context");
 
-    let spinner_clone = spinner.clone();
-    tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            spinner_clone.set_message(msg);
+    let Some(target) = args.target() else {
+        let res = ctx
+            .services
+            .deployment_service
+            .rollback(plan.id, disable_hooks, &mut dctx)
+            .await;
+
+        progress.finish().await;
+        if res.is_err() {
+            error!("Failed to rollback deployment: {:?}", res.as_ref().err());
+            std::process::exit(1);
+        }
+
+        if dry {
+            dctx.print_summary("✅ Rollback dry run completed successfully");
+            print_output(format!(
+                "✅ Rollback for plan '{}' completed successfully in dry-run mode 👻.",
+                plan_name
+            ));
+            return;
+        }
+        if dctx.is_collect_scripts() {
+            dctx.print_summary("✅ Collected scripts successfully");
         }
-    });
+
+        print_output(format!(
+            "✅ Deployment for plan '{}' rolled back successfully",
+            plan_name
+        ));
+        return;
+    };
 
     let res = ctx
         .services
         .deployment_service
-        .rollback(plan.id, disable_hooks, progress)
+        .rollback_to(plan.id, target.clone(), disable_hooks, &mut dctx)
         .await;
 
-    if res.is_err() {
-        spinner.finish_and_clear();
-        error!("Failed to rollback deployment: {:?}", res.as_ref().err());
+    progress.finish().await;
+
+    match res {
+        Ok(reverted) if reverted.is_empty() => {
+            print_output(format!(
+                "✅ No successful deployments after {} for plan '{}'",
+                target, plan_name
+            ));
+        }
+        Ok(reverted) if dry => {
+            dctx.print_summary("✅ Rollback dry run completed successfully");
+            print_output(format!(
+                "👻 {} deployment(s) of plan '{}' would be rolled back to {}: {}",
+                reverted.len(),
+                plan_name,
+                target,
+                join_ids(&reverted)
+            ));
+        }
+        Ok(reverted) => {
+            if dctx.is_collect_scripts() {
+                dctx.print_summary("✅ Collected scripts successfully");
+            }
+            print_output(format!(
+                "✅ Rolled back {} deployment(s) of plan '{}' to {}: {}",
+                reverted.len(),
+                plan_name,
+                target,
+                join_ids(&reverted)
+            ));
+        }
+        Err(e) => {
+            error!("Failed to rollback plan to {}: {:?}", target, e);
+            match e.downcast_ref::<RollbackChainError>() {
+                Some(chain) => {
+                    eprintln!(
+                        "❌ Rollback of deployment {} failed: {}",
+                        chain.failed, chain.message
+                    );
+                    if chain.reverted.is_empty() {
+                        eprintln!("   No deployments were reverted");
+                    } else {
+                        eprintln!("   Reverted deployments: {}", join_ids(&chain.reverted));
+                    }
+                    if !chain.skipped.is_empty() {
+                        eprintln!("   Not attempted: {}", join_ids(&chain.skipped));
+                    }
+                }
+                None => eprintln!("❌ Failed to rollback plan '{}': {}", plan_name, e),
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Exits when the tag is already used by another deployment of the plan.
+pub async fn bail_if_tag_used(plan_id: i32, tag: &str, ctx: &Context<'_>) {
+    let existing = ctx
+        .services
+        .deployment_service
+        .find_by_tag(plan_id, tag)
+        .await
+        .exit_on_err(&format!("❌ Failed to check tag '{}'", tag));
+
+    if let Some(deployment) = existing {
+        eprintln!(
+            "❌ Tag '{}' is already used by deployment {}",
+            tag, deployment.id
+        );
         std::process::exit(1);
     }
-    spinner.finish_and_clear();
+}
 
-    println!(
-        "✅ Deployment for plan '{}' rolled back successfully",
-        plan_name
-    );
+fn join_ids(ids: &[i32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn update(args: &PlansUpdateArgs, ctx: &Context<'_>) {
+    let (plan, changed) = ctx
+        .services
+        .plan_service
+        .edit(&args.name, &args.to_edit())
+        .await
+        .exit_on_err(&format!("❌ Failed to update plan '{}'", args.name));
+
+    if !changed {
+        println!("✅ Plan '{}' is unchanged", plan.name);
+        return;
+    }
+
+    let revisions = ctx
+        .services
+        .plan_service
+        .find_revisions(&plan.name)
+        .await
+        .exit_on_err(&format!(
+            "❌ Failed to fetch revisions of plan '{}'",
+            plan.name
+        ));
+    match revisions.last() {
+        Some(revision) => println!(
+            "✅ Plan '{}' updated to revision {} ({})",
+            plan.name,
+            revision.revision,
+            revision
+                .changed_fields
+                .as_ref()
+                .map(|f| f.0.join(", "))
+                .unwrap_or_default()
+        ),
+        None => println!("✅ Plan '{}' updated", plan.name),
+    }
+}
+
+async fn list_revisions(plan_name: &str, ctx: &Context<'_>) {
+    let revisions = ctx
+        .services
+        .plan_service
+        .find_revisions(plan_name)
+        .await
+        .exit_on_err(&format!(
+            "❌ Failed to fetch revisions of plan '{}'",
+            plan_name
+        ));
+
+    let rows: Vec<PlanRevisionRow> = revisions
+        .iter()
+        .map(|r| PlanRevisionRow {
+            revision: r.revision.to_string(),
+            name: r.name.clone(),
+            created_at: r.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            changed_fields: match &r.changed_fields {
+                None => "reconstructed".bright_black().to_string(),
+                Some(f) if f.is_empty() => "created".bright_black().to_string(),
+                Some(f) => f.0.join(", "),
+            },
+        })
+        .collect();
+
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string();
+    println!("{}", table);
 }
 
 async fn reset(plan_name: &str, yes: bool, ctx: &Context<'_>) {
@@ -663,8 +1074,8 @@ async fn reset(plan_name: &str, yes: bool, ctx: &Context<'_>) {
     }
     let plan = plan.unwrap();
 
-    if plan.status != PlanStatus::Running {
-        eprintln!("⚠️ Only running plans can be reset.");
+    if !matches!(plan.status, PlanStatus::Running | PlanStatus::Interrupted) {
+        eprintln!("⚠️ Only running or interrupted plans can be reset.");
         std::process::exit(1);
     }
 
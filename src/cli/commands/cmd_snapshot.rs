@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::{
+    cli::{
+        Context,
+        commands::{ExitOnErr, ProgressRenderer},
+    },
+    utils::ProgressReporter,
+};
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+EXAMPLES:
+    # leaf snapshot --connection prod --schemas HR,SALES --dir ./schema
+    This will write the DDL of every object of the schemas `HR` and `SALES` to
+    `./schema/<SCHEMA>/<TYPE>/<NAME>.sql` and an index of the files with their last DDL time
+    and SHA-256 to `./schema/manifest.json`. Commit the directory to review schema changes as diffs.
+
+    Running the command again updates the files in place and removes the files of dropped objects.
+    The exclude rules of the configuration apply the same way as for plans.
+    "#)]
+pub struct SnapshotCommand {
+    /// Connection name
+    #[arg(long, required = true)]
+    connection: String,
+
+    /// Comma-separated list of schemas to export
+    #[arg(long, required = true, value_delimiter = ',')]
+    schemas: Vec<String>,
+
+    /// Output directory, created if it does not exist
+    #[arg(long, required = true, value_name = "DIR")]
+    dir: PathBuf,
+
+    /// Comma-separated list of object types to exclude
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_types: Vec<String>,
+
+    /// Comma-separated list of object names to exclude
+    #[arg(long, value_delimiter = ',')]
+    exclude_object_names: Vec<String>,
+}
+
+pub async fn execute(args: &SnapshotCommand, ctx: &Context<'_>) {
+    let exclude_object_types = ctx
+        .settings
+        .rules
+        .combined_exclude_object_types(Some(args.exclude_object_types.clone()));
+    let exclude_object_names = ctx
+        .settings
+        .rules
+        .combined_exclude_object_names(Some(args.exclude_object_names.clone()));
+
+    std::fs::create_dir_all(&args.dir).exit_on_err(&format!(
+        "❌ Failed to create directory {}",
+        args.dir.display()
+    ));
+
+    let (progress, tx) = ProgressRenderer::new(None);
+    let res = ctx
+        .services
+        .snapshot_service
+        .export(
+            &args.connection,
+            &args.schemas,
+            exclude_object_types,
+            exclude_object_names,
+            &args.dir,
+            &ProgressReporter::new(Some(tx)),
+        )
+        .await;
+    progress.finish().await;
+
+    let manifest = res.exit_on_err(&format!(
+        "❌ Failed to export snapshot of '{}'",
+        args.connection
+    ));
+
+    println!(
+        "📸 Snapshot of {} object(s) from '{}' written to '{}'",
+        manifest.objects.len(),
+        args.connection,
+        args.dir.display()
+    );
+}
@@ -1,20 +1,40 @@
+pub mod cmd_audit;
+pub mod cmd_compare;
+pub mod cmd_config;
 pub mod cmd_connections;
 pub mod cmd_db;
 pub mod cmd_deployments;
+pub mod cmd_diff;
+pub mod cmd_doctor;
+pub mod cmd_drift;
 pub mod cmd_init;
+pub mod cmd_locks;
+pub mod cmd_objects;
 pub mod cmd_plans;
+pub mod cmd_snapshot;
 pub mod cmd_version;
+pub mod progress;
 pub mod shared;
 
 use clap::Subcommand;
-pub use shared::{get_cut_off_date_or_bail, new_spinner};
+pub use progress::ProgressRenderer;
+pub use shared::{get_cut_off_date_or_bail, print_delta_document_and_exit};
 
 use crate::cli::commands::{
+    cmd_audit::AuditCommands,
+    cmd_compare::CompareCommand,
+    cmd_config::{ApplyConfigCommand, ExportConfigCommand},
     cmd_connections::ConnectionCommands,
     cmd_db::DbCommands,
     cmd_deployments::DeploymentCommands,
+    cmd_diff::DiffCommand,
+    cmd_doctor::DoctorCommand,
+    cmd_drift::DriftCommands,
     cmd_init::InitCommands,
+    cmd_locks::LockCommands,
+    cmd_objects::ObjectCommands,
     cmd_plans::{PlanCommands, PlansRunArgs},
+    cmd_snapshot::SnapshotCommand,
     cmd_version::VersionCommand,
 };
 
@@ -44,9 +64,51 @@ pub enum Commands {
         action: DeploymentCommands,
     },
 
+    /// Manage deployment locks
+    Locks {
+        #[command(subcommand)]
+        action: LockCommands,
+    },
+
+    /// Inspect the deployment history of database objects
+    Objects {
+        #[command(subcommand)]
+        action: ObjectCommands,
+    },
+
+    /// Show who prepared, applied, rolled back or reset plans
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommands,
+    },
+
+    /// Find and recover plans and deployments left RUNNING by a stopped process
+    Doctor(DoctorCommand),
+
     /// Deploy a plan, alias for `plans run`
     Deploy(PlansRunArgs),
 
+    /// Export the DDL of the objects of a connection to a directory
+    Snapshot(SnapshotCommand),
+
+    /// Compare two snapshot directories and write the migration scripts, offline
+    Diff(DiffCommand),
+
+    /// Compare the objects of two connections without creating a plan
+    Compare(CompareCommand),
+
+    /// Create, update or delete connections and plans to match a leaf.yaml or leaf.toml file
+    ApplyConfig(ApplyConfigCommand),
+
+    /// Write the connections and plans of the repository to a leaf.yaml or leaf.toml file
+    ExportConfig(ExportConfigCommand),
+
+    /// Detect changes made to deployed objects outside of leaf
+    Drift {
+        #[command(subcommand)]
+        action: DriftCommands,
+    },
+
     /// Initialize application
     Init {
         #[command(subcommand)]
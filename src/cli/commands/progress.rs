@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde::Serialize;
+use tokio::{
+    sync::{Mutex, mpsc},
+    task::JoinHandle,
+};
+
+use crate::{
+    types::EventsFormat,
+    utils::{ProgressEvent, reserve_stdout},
+};
+
+/// Renders the progress events of a deployment, either as a spinner that turns into a
+/// progress bar for counted events, or as a JSON lines stream on stdout.
+pub struct ProgressRenderer {
+    bar: ProgressBar,
+    events: Option<EventsFormat>,
+    rx: Arc<Mutex<mpsc::UnboundedReceiver<ProgressEvent>>>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Serialize)]
+struct EventLine<'a> {
+    ts: String,
+    #[serde(flatten)]
+    event: &'a ProgressEvent,
+}
+
+fn spinner_style() -> ProgressStyle {
+    ProgressStyle::default_spinner()
+        .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
+        .template("{spinner:.cyan} [{elapsed_precise}] {msg}")
+        .unwrap()
+}
+
+fn bar_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template("{spinner:.cyan} [{elapsed_precise}] [{bar:30.cyan/blue}] {pos}/{len} {msg}")
+        .unwrap()
+        .progress_chars("=> ")
+}
+
+fn render(bar: &ProgressBar, events: Option<EventsFormat>, event: ProgressEvent) {
+    if let Some(EventsFormat::Jsonl) = events {
+        let line = EventLine {
+            ts: Utc::now().format("%Y-%m-%dT%H:%M:%S%.3f").to_string(),
+            event: &event,
+        };
+        if let Ok(json) = serde_json::to_string(&line) {
+            println!("{}", json);
+        }
+        return;
+    }
+
+    match &event {
+        ProgressEvent::PhaseStarted { .. } => {
+            bar.set_style(spinner_style());
+            bar.unset_length();
+            bar.set_position(0);
+        }
+        ProgressEvent::ChangeFailed { .. } | ProgressEvent::HookFinished { error: Some(_), .. } => {
+            bar.println(event.to_string().red().to_string())
+        }
+        _ => {}
+    }
+
+    if let Some((index, total)) = event.position() {
+        if bar.length() != Some(total as u64) {
+            bar.set_style(bar_style());
+            bar.set_length(total as u64);
+        }
+        bar.set_position(index as u64);
+    }
+    bar.set_message(event.to_string());
+}
+
+impl ProgressRenderer {
+    /// Starts rendering, the returned sender is passed to the `DeploymentContext`.
+    /// Streaming events reserves stdout, the command output goes to stderr.
+    pub fn new(events: Option<EventsFormat>) -> (Self, mpsc::UnboundedSender<ProgressEvent>) {
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(spinner_style());
+        if events.is_some() {
+            reserve_stdout();
+            bar.set_draw_target(ProgressDrawTarget::hidden());
+        } else {
+            bar.enable_steady_tick(std::time::Duration::from_millis(80));
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let rx = Arc::new(Mutex::new(rx));
+
+        let task_bar = bar.clone();
+        let task_rx = rx.clone();
+        let task = tokio::spawn(async move {
+            let mut rx = task_rx.lock().await;
+            while let Some(event) = rx.recv().await {
+                render(&task_bar, events, event);
+            }
+        });
+
+        (
+            Self {
+                bar,
+                events,
+                rx,
+                task,
+            },
+            tx,
+        )
+    }
+
+    /// Hides the spinner, used when the command prints machine-readable output.
+    pub fn hide(&self) {
+        self.bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.bar.set_message(message.into());
+    }
+
+    /// Renders the events that are still queued and clears the spinner.
+    pub async fn finish(&self) {
+        self.task.abort();
+        let mut rx = self.rx.lock().await;
+        while let Ok(event) = rx.try_recv() {
+            render(&self.bar, self.events, event);
+        }
+        self.bar.finish_and_clear();
+    }
+}
@@ -1,8 +1,11 @@
 use chrono::NaiveDateTime;
+use colored::Colorize;
 
-use crate::cli::{Context, commands::ExitOnErr};
-use indicatif::{ProgressBar, ProgressStyle};
-use tokio::sync::mpsc;
+use crate::{
+    cli::{Context, commands::ExitOnErr},
+    delta::DeltaDocument,
+    utils::{CancelToken, SignalGuard, cancel_on_signal},
+};
 
 pub async fn get_cut_off_date_or_bail(
     cutoff_date: Option<NaiveDateTime>,
@@ -19,7 +22,7 @@ pub async fn get_cut_off_date_or_bail(
     if let Some(date) = cutoff_date {
         Some(date)
     } else {
-        println!("⚠️ No cutoff date provided, using the last deployment start date");
+        eprintln!("⚠️ No cutoff date provided, using the last deployment start date");
         ctx.services
             .plan_service
             .get_last_cutoff_date(plan.id)
@@ -35,24 +38,48 @@ pub async fn get_cut_off_date_or_bail(
     })
 }
 
-pub fn new_spinner() -> (ProgressBar, mpsc::UnboundedSender<String>) {
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
-            .template("{spinner:.cyan} [{elapsed_precise}] {msg}")
-            .unwrap(),
-    );
-    spinner.enable_steady_tick(std::time::Duration::from_millis(80));
+/// Exit code of `--format json` runs that found changes and of drift checks that found drift,
+/// `0` means none.
+/// Cancels `token` on Ctrl-C while the guard is alive. A second Ctrl-C marks the operation
+/// on the plan INTERRUPTED and releases its lock before exiting.
+pub fn cancel_plan_on_signal(plan_id: i32, token: CancelToken, ctx: &Context<'_>) -> SignalGuard {
+    let deployment_service = ctx.services.deployment_service.clone();
+    cancel_on_signal(token, async move {
+        if let Err(e) = deployment_service.abort(plan_id).await {
+            eprintln!("❌ Failed to mark plan {} INTERRUPTED: {:?}", plan_id, e);
+        }
+    })
+}
 
-    let (tx, mut rx) = mpsc::unbounded_channel();
-    let pb_clone = spinner.clone();
+pub const EXIT_CHANGES_PRESENT: i32 = 2;
 
-    tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            pb_clone.set_message(msg);
-        }
+/// Prints the document to stdout and exits with [`EXIT_CHANGES_PRESENT`] if it has changes.
+pub fn print_delta_document_and_exit(document: &DeltaDocument) -> ! {
+    let json = serde_json::to_string_pretty(document).exit_on_err("❌ Failed to serialize changes");
+    println!("{}", json);
+    std::process::exit(if document.has_changes {
+        EXIT_CHANGES_PRESENT
+    } else {
+        0
     });
+}
 
-    (spinner, tx)
+/// Colors the headers, hunks and added and removed lines of a unified diff.
+pub fn colored_diff(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            if line.starts_with("+++") || line.starts_with("---") {
+                line.bold().to_string()
+            } else if line.starts_with('+') {
+                line.green().to_string()
+            } else if line.starts_with('-') {
+                line.red().to_string()
+            } else if line.starts_with("@@") {
+                line.cyan().to_string()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
@@ -4,7 +4,9 @@ use clap::{CommandFactory, Parser};
 use colored::Colorize;
 
 use crate::cli::commands::{
-    Commands, cmd_connections, cmd_db, cmd_deployments, cmd_init, cmd_plans, cmd_version,
+    Commands, cmd_audit, cmd_compare, cmd_config, cmd_connections, cmd_db, cmd_deployments,
+    cmd_diff, cmd_doctor, cmd_drift, cmd_init, cmd_locks, cmd_objects, cmd_plans, cmd_snapshot,
+    cmd_version,
 };
 use crate::config::Settings;
 use crate::services::AppServices;
@@ -45,9 +47,19 @@ impl Cli {
             Some(Commands::Connections { action }) => cmd_connections::execute(action, ctx).await,
             Some(Commands::Plans { action }) => cmd_plans::execute(action, ctx).await,
             Some(Commands::Deployments { action }) => cmd_deployments::execute(action, ctx).await,
+            Some(Commands::Locks { action }) => cmd_locks::execute(action, ctx).await,
+            Some(Commands::Objects { action }) => cmd_objects::execute(action, ctx).await,
+            Some(Commands::Audit { action }) => cmd_audit::execute(action, ctx).await,
+            Some(Commands::Doctor(action)) => cmd_doctor::execute(action, ctx).await,
             Some(Commands::Deploy(args)) => {
                 cmd_plans::execute(&cmd_plans::PlanCommands::Run(args.clone()), ctx).await
             }
+            Some(Commands::Snapshot(args)) => cmd_snapshot::execute(args, ctx).await,
+            Some(Commands::Diff(args)) => cmd_diff::execute(args, ctx).await,
+            Some(Commands::Compare(args)) => cmd_compare::execute(args, ctx).await,
+            Some(Commands::ApplyConfig(args)) => cmd_config::apply(args, ctx).await,
+            Some(Commands::ExportConfig(args)) => cmd_config::export(args, ctx).await,
+            Some(Commands::Drift { action }) => cmd_drift::execute(action, ctx).await,
             Some(Commands::Init { action }) => cmd_init::execute(action.as_ref(), ctx).await,
             Some(Commands::Version(action)) => cmd_version::execute(action, ctx.settings).await,
             None => {
@@ -60,4 +72,47 @@ impl Cli {
     pub fn should_run_main(&self) -> bool {
         self.command.is_none()
     }
+
+    /// Commands that may run before the repository is initialized, and `doctor` which
+    /// recovers on its own, skip the startup recovery.
+    pub fn should_recover_on_startup(&self) -> bool {
+        !matches!(
+            self.command,
+            None | Some(Commands::Db { .. })
+                | Some(Commands::Init { .. })
+                | Some(Commands::Version(_))
+                | Some(Commands::Doctor(_))
+        )
+    }
+
+    /// The automatic purge of the retention policy runs before the same commands as the
+    /// startup recovery, except `deployments apply`, which applies or resumes a deployment
+    /// that must not be purged before it runs.
+    pub fn should_purge_on_startup(&self) -> bool {
+        self.should_recover_on_startup()
+            && !matches!(
+                self.command,
+                Some(Commands::Deployments {
+                    action: cmd_deployments::DeploymentCommands::Apply { .. }
+                })
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_purge_on_startup() {
+        let parse = |args: &[&str]| Cli::try_parse_from(args).unwrap();
+
+        assert!(parse(&["leaf", "deployments", "list"]).should_purge_on_startup());
+        assert!(parse(&["leaf", "plans", "list"]).should_purge_on_startup());
+        assert!(
+            !parse(&["leaf", "deployments", "apply", "--deployment-id", "1"])
+                .should_purge_on_startup()
+        );
+        assert!(!parse(&["leaf", "version"]).should_purge_on_startup());
+    }
 }
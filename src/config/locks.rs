@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Settings of the deployment locks held in the repository database.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LocksConfig {
+    /// How often a running process refreshes the heartbeat of its lock
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+
+    /// A lock without a heartbeat for this long is considered abandoned and can be taken over
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+}
+
+impl Default for LocksConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval_secs: default_heartbeat_interval_secs(),
+            stale_after_secs: default_stale_after_secs(),
+        }
+    }
+}
+
+fn default_heartbeat_interval_secs() -> u64 {
+    15
+}
+
+fn default_stale_after_secs() -> u64 {
+    120
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_locks_config() {
+        let config = LocksConfig::default();
+
+        assert_eq!(config.heartbeat_interval_secs, 15);
+        assert_eq!(config.stale_after_secs, 120);
+    }
+
+    #[test]
+    fn test_deserialize_partial_locks_config() {
+        let json = r#"{ "stale_after_secs": 300 }"#;
+
+        let config: LocksConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.heartbeat_interval_secs, 15);
+        assert_eq!(config.stale_after_secs, 300);
+    }
+}
@@ -1,11 +1,19 @@
 pub mod database;
 pub mod hooks;
+pub mod locks;
 pub mod log;
+pub mod recovery;
+pub mod retention;
 pub mod rules;
+pub mod secrets;
 pub mod settings;
 
 pub use database::DatabaseConfig;
 pub use hooks::HooksConfig;
+pub use locks::LocksConfig;
 pub use log::LogConfig;
+pub use recovery::RecoveryConfig;
+pub use retention::RetentionConfig;
 pub use rules::RulesConfig;
+pub use secrets::SecretsConfig;
 pub use settings::Settings;
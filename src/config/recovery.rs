@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Settings of the recovery of deployments left RUNNING by a process that stopped.
+///
+/// Heartbeats expire after `locks.stale_after_secs`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RecoveryConfig {
+    /// Mark expired RUNNING plans and deployments as INTERRUPTED when leaf starts
+    #[serde(default = "default_on_startup")]
+    pub on_startup: bool,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            on_startup: default_on_startup(),
+        }
+    }
+}
+
+fn default_on_startup() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_recovery_config() {
+        assert!(RecoveryConfig::default().on_startup);
+    }
+
+    #[test]
+    fn test_deserialize_recovery_config() {
+        let config: RecoveryConfig = serde_json::from_str(r#"{ "on_startup": false }"#).unwrap();
+
+        assert!(!config.on_startup);
+    }
+}
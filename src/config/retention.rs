@@ -0,0 +1,57 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{types::RetentionPolicy, utils::parsers::parse_duration};
+
+/// Settings of the repository retention, see [`RetentionPolicy`].
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RetentionConfig {
+    /// Purge deployments older than this, e.g. `90d`
+    #[serde(default)]
+    pub older_than: Option<String>,
+
+    /// Keep this many of the newest deployments of every plan
+    #[serde(default)]
+    pub keep_last: Option<usize>,
+
+    /// Purge deployments outside of the policy when leaf starts
+    #[serde(default)]
+    pub auto_purge: bool,
+}
+
+impl RetentionConfig {
+    /// The configured policy, `None` if neither limit is set.
+    pub fn policy(&self) -> Result<Option<RetentionPolicy>> {
+        if self.older_than.is_none() && self.keep_last.is_none() {
+            return Ok(None);
+        }
+        let older_than = self
+            .older_than
+            .as_deref()
+            .map(parse_duration)
+            .transpose()
+            .map_err(|e| anyhow!("LEAF__RETENTION__OLDER_THAN: {}", e))?;
+        RetentionPolicy::new(older_than, self.keep_last).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_retention_policy() {
+        assert!(RetentionConfig::default().policy().unwrap().is_none());
+
+        let config: RetentionConfig =
+            serde_json::from_str(r#"{ "older_than": "90d", "keep_last": 5 }"#).unwrap();
+        let policy = config.policy().unwrap().unwrap();
+        assert_eq!(policy.older_than, Some(Duration::days(90)));
+        assert_eq!(policy.keep_last, Some(5));
+        assert!(!config.auto_purge);
+
+        let config: RetentionConfig = serde_json::from_str(r#"{ "older_than": "90" }"#).unwrap();
+        assert!(config.policy().is_err());
+    }
+}
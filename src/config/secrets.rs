@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::MASK;
+
+/// Settings of the key that encrypts connection passwords in the repository database.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SecretsConfig {
+    /// Base64 encoded 32 byte key, takes precedence over the key file
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// File holding the base64 encoded key, created on first use if missing
+    #[serde(default = "default_key_file")]
+    pub key_file: PathBuf,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            key: None,
+            key_file: default_key_file(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SecretsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsConfig")
+            .field("key", &self.key.as_ref().map(|_| MASK))
+            .field("key_file", &self.key_file)
+            .finish()
+    }
+}
+
+fn default_key_file() -> PathBuf {
+    PathBuf::from("leaf.key")
+}
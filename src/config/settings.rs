@@ -1,7 +1,15 @@
+use std::path::{Path, PathBuf};
+
 use config::{Config, ConfigError, Environment};
 use serde::{Deserialize, Serialize};
 
-use crate::config::{DatabaseConfig, HooksConfig, LogConfig, RulesConfig};
+use crate::{
+    config::{
+        DatabaseConfig, HooksConfig, LocksConfig, LogConfig, RecoveryConfig, RetentionConfig,
+        RulesConfig, SecretsConfig,
+    },
+    utils::{MASK, mask_url},
+};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Settings {
@@ -16,6 +24,18 @@ pub struct Settings {
 
     #[serde(default)]
     pub hooks: HooksConfig,
+
+    #[serde(default)]
+    pub locks: LocksConfig,
+
+    #[serde(default)]
+    pub recovery: RecoveryConfig,
+
+    #[serde(default)]
+    pub retention: RetentionConfig,
+
+    #[serde(default)]
+    pub secrets: SecretsConfig,
 }
 
 fn get_env_file_name() -> String {
@@ -33,6 +53,18 @@ fn get_env_file_name() -> String {
     return ".env".to_string();
 }
 
+/// Path of the database file of a SQLite URL, `None` for other and in-memory databases.
+fn sqlite_file(url: &str) -> Option<PathBuf> {
+    let path = url
+        .strip_prefix("sqlite://")
+        .or_else(|| url.strip_prefix("sqlite:"))?;
+    let path = path.split('?').next().unwrap_or_default();
+    if path.is_empty() || path == ":memory:" {
+        return None;
+    }
+    Some(Path::new(path).to_path_buf())
+}
+
 impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
         let env_file_name = get_env_file_name();
@@ -55,6 +87,32 @@ impl Settings {
         Ok(settings)
     }
 
+    /// The secrets settings with a relative key file resolved against the data directory:
+    /// the directory of the SQLite repository database, or of the env file for other
+    /// databases. The key stays next to the database it belongs to, whatever directory
+    /// leaf runs from.
+    pub fn secrets(&self) -> SecretsConfig {
+        let mut secrets = self.secrets.clone();
+        if secrets.key_file.is_relative() {
+            let file = sqlite_file(&self.database.url)
+                .unwrap_or_else(|| PathBuf::from(get_env_file_name()));
+            if let Some(dir) = file.parent() {
+                secrets.key_file = dir.join(&secrets.key_file);
+            }
+        }
+        secrets
+    }
+
+    /// Copy of the settings with the repository database password and the encryption key masked
+    pub fn masked(&self) -> Self {
+        let mut settings = self.clone();
+        settings.database.url = mask_url(&settings.database.url);
+        if settings.secrets.key.is_some() {
+            settings.secrets.key = Some(MASK.to_string());
+        }
+        settings
+    }
+
     pub fn print_config(&self) {
         match serde_json::to_string_pretty(self) {
             Ok(json) => println!("{}", json),
@@ -358,4 +416,37 @@ begin execute immediate 'ALTER SESSION SET NLS_DATE_FORMAT = ''YYYY-MM-DD'''; en
         assert!(hooks[0].contains("dbms_output.put_line"));
         assert!(hooks[1].contains("ALTER SESSION"));
     }
+
+    #[test]
+    #[serial]
+    fn test_key_file_is_resolved_against_the_data_directory() {
+        let mut settings: Settings = serde_json::from_str("{}").unwrap();
+
+        settings.database.url = "sqlite:///var/lib/leaf/leaf.db?mode=rwc".to_string();
+        assert_eq!(
+            settings.secrets().key_file,
+            PathBuf::from("/var/lib/leaf/leaf.key")
+        );
+
+        settings.database.url = "sqlite://leaf.db?mode=rwc".to_string();
+        assert_eq!(settings.secrets().key_file, PathBuf::from("leaf.key"));
+
+        settings.database.url = "postgres://leaf@db/leaf".to_string();
+        unsafe {
+            env::set_var("LEAF_ENV_FILE", "/etc/leaf/.env");
+        }
+        assert_eq!(
+            settings.secrets().key_file,
+            PathBuf::from("/etc/leaf/leaf.key")
+        );
+        unsafe {
+            env::remove_var("LEAF_ENV_FILE");
+        }
+
+        settings.secrets.key_file = PathBuf::from("/run/secrets/leaf.key");
+        assert_eq!(
+            settings.secrets().key_file,
+            PathBuf::from("/run/secrets/leaf.key")
+        );
+    }
 }
@@ -1,7 +1,11 @@
+use std::sync::Arc;
+
 use migration::MigratorTrait;
 use sea_orm::DatabaseConnection;
 use tracing::{error, info};
 
+use crate::{config::Settings, utils::SecretStore};
+
 pub async fn up(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
     info!("Running migrations");
     match migration::Migrator::up(db, None).await {
@@ -29,3 +33,23 @@ pub async fn down(db: &DatabaseConnection, steps: u32) -> Result<(), sea_orm::Db
         }
     }
 }
+
+/// Runs the pending migrations with the key of the settings, which encrypts the connection
+/// passwords stored in plain text by earlier versions.
+pub async fn up_with_secrets(
+    db: &DatabaseConnection,
+    settings: &Settings,
+) -> Result<(), sea_orm::DbErr> {
+    info!("Running migrations");
+    let secrets = Arc::new(SecretStore::new(&settings.secrets()));
+    match migration::up_with_sealer(db, secrets).await {
+        Ok(_) => {
+            info!("✅ Migrations completed successfully");
+            Ok(())
+        }
+        Err(e) => {
+            error!("Failed to run migrations: {}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,566 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail, ensure};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::{ConnectionModel, PlanModel},
+    types::{ConnectionKind, Hooks},
+    utils::{MASK, is_secret_reference, password_env_name},
+};
+
+/// Format of a config file, chosen by its extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            Some("toml") => Ok(Self::Toml),
+            _ => bail!(
+                "Unsupported config file '{}', expected a .yaml, .yml or .toml file",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Contents of a `leaf.yaml` or `leaf.toml` file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LeafConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<ConnectionSpec>,
+
+    /// Rules combined into every plan of the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<RulesSpec>,
+
+    /// Hooks of the plans of the file without their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<Hooks>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plans: Vec<PlanSpec>,
+}
+
+/// A connection, Oracle with `username`, `password` and `connection_string` or a
+/// directory with `path`. Passwords are secret references, never plain text.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionSpec {
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// `env:NAME` or `file:PATH`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_string: Option<String>,
+
+    /// Directory of DDL files, relative to the config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub required_approvals: i32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_object_types: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_object_names: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_drop_types: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_all_drops: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanSpec {
+    pub name: String,
+
+    pub source: String,
+
+    pub target: String,
+
+    pub schemas: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_object_types: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_object_names: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_drop_types: Option<Vec<String>>,
+
+    /// Defaults to the rules of the file, then to `true`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_all_drops: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub fail_fast: bool,
+
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub disable_hooks: bool,
+
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub required_approvals: i32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<Hooks>,
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// A connection of the config file shaped like the repository's connections. The password
+/// is the secret reference of the file, it is stored as is and resolved when connecting.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DesiredConnection {
+    pub name: String,
+    pub kind: ConnectionKind,
+    pub username: String,
+    pub password: String,
+    /// Oracle connection string, or the absolute path of directory connections
+    pub connection_string: String,
+    pub required_approvals: i32,
+}
+
+impl fmt::Debug for DesiredConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DesiredConnection")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("username", &self.username)
+            .field("password", &MASK)
+            .field("connection_string", &self.connection_string)
+            .field("required_approvals", &self.required_approvals)
+            .finish()
+    }
+}
+
+/// A plan of the config file with the rules and hooks of the file applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DesiredPlan {
+    pub name: String,
+    pub source: String,
+    pub target: String,
+    pub schemas: Vec<String>,
+    pub exclude_object_types: Option<Vec<String>>,
+    pub exclude_object_names: Option<Vec<String>>,
+    pub disabled_drop_types: Option<Vec<String>>,
+    pub disable_all_drops: bool,
+    pub fail_fast: bool,
+    pub disable_hooks: bool,
+    pub hooks: Option<Hooks>,
+    pub required_approvals: i32,
+}
+
+/// What the repository should contain according to a config file.
+#[derive(Clone, Debug, Default)]
+pub struct DesiredState {
+    pub connections: Vec<DesiredConnection>,
+    pub plans: Vec<DesiredPlan>,
+}
+
+impl LeafConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let format = ConfigFormat::from_path(path)?;
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file '{}'", path.display()))?;
+        Self::parse(&content, format)
+            .context(format!("Failed to parse config file '{}'", path.display()))
+    }
+
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self> {
+        Ok(match format {
+            ConfigFormat::Yaml => serde_yaml::from_str(content)?,
+            ConfigFormat::Toml => toml::from_str(content)?,
+        })
+    }
+
+    pub fn to_string(&self, format: ConfigFormat) -> Result<String> {
+        Ok(match format {
+            ConfigFormat::Yaml => serde_yaml::to_string(self)?,
+            ConfigFormat::Toml => toml::to_string(self)?,
+        })
+    }
+
+    /// Describes the connections and plans of the repository. Passwords stored as references
+    /// are written as is, encrypted passwords have no reference to write and are exported
+    /// as `env:LEAF_<NAME>_PASSWORD` to fill in. Directory paths are written as stored.
+    pub fn export(connections: &[ConnectionModel], plans: &[PlanModel]) -> Result<Self> {
+        let connections_by_id = |id: i32| {
+            connections
+                .iter()
+                .find(|c| c.id == id)
+                .map(|c| c.name.clone())
+                .context(format!("Connection with ID {} not found", id))
+        };
+
+        let connection_specs = connections
+            .iter()
+            .sorted_by_key(|c| c.name.to_lowercase())
+            .map(|c| match c.kind {
+                ConnectionKind::Oracle => ConnectionSpec {
+                    name: c.name.clone(),
+                    username: Some(c.username.clone()),
+                    password: Some(if is_secret_reference(&c.password) {
+                        c.password.clone()
+                    } else {
+                        format!("env:{}", password_env_name(&c.name))
+                    }),
+                    connection_string: Some(c.connection_string.clone()),
+                    path: None,
+                    required_approvals: c.required_approvals,
+                },
+                ConnectionKind::Directory => ConnectionSpec {
+                    name: c.name.clone(),
+                    path: Some(PathBuf::from(&c.connection_string)),
+                    required_approvals: c.required_approvals,
+                    ..Default::default()
+                },
+            })
+            .collect();
+
+        let plan_specs = plans
+            .iter()
+            .sorted_by_key(|p| p.name.to_lowercase())
+            .map(|p| {
+                Ok(PlanSpec {
+                    name: p.name.clone(),
+                    source: connections_by_id(p.source_connection_id)?,
+                    target: connections_by_id(p.target_connection_id)?,
+                    schemas: p.get_schemas(),
+                    exclude_object_types: non_empty(p.get_exclude_object_types()),
+                    exclude_object_names: non_empty(p.get_exclude_object_names()),
+                    disabled_drop_types: non_empty(
+                        p.disabled_drop_types.as_ref().map(|d| d.0.clone()),
+                    ),
+                    disable_all_drops: Some(p.disable_all_drops),
+                    fail_fast: p.fail_fast,
+                    disable_hooks: p.disable_hooks,
+                    required_approvals: p.required_approvals,
+                    hooks: non_default(p.get_hooks()?),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            connections: connection_specs,
+            rules: None,
+            hooks: None,
+            plans: plan_specs,
+        })
+    }
+
+    /// Resolves secret references and directory paths, relative to `base_dir`, and
+    /// applies the rules and hooks of the file to its plans.
+    pub fn resolve(&self, base_dir: &Path) -> Result<DesiredState> {
+        ensure_unique(
+            self.connections.iter().map(|c| c.name.as_str()),
+            "connection",
+        )?;
+        ensure_unique(self.plans.iter().map(|p| p.name.as_str()), "plan")?;
+
+        let connections = self
+            .connections
+            .iter()
+            .map(|c| c.resolve(base_dir))
+            .collect::<Result<Vec<_>>>()?;
+
+        let rules = self.rules.clone().unwrap_or_default();
+        let plans = self
+            .plans
+            .iter()
+            .map(|p| {
+                ensure!(
+                    p.required_approvals >= 0,
+                    "Plan '{}': required approvals can not be negative",
+                    p.name
+                );
+                Ok(DesiredPlan {
+                    name: p.name.clone(),
+                    source: p.source.clone(),
+                    target: p.target.clone(),
+                    schemas: p.schemas.clone(),
+                    exclude_object_types: combine(
+                        &p.exclude_object_types,
+                        &rules.exclude_object_types,
+                    ),
+                    exclude_object_names: combine(
+                        &p.exclude_object_names,
+                        &rules.exclude_object_names,
+                    ),
+                    disabled_drop_types: combine(
+                        &p.disabled_drop_types,
+                        &rules.disabled_drop_types,
+                    ),
+                    disable_all_drops: p
+                        .disable_all_drops
+                        .or(rules.disable_all_drops)
+                        .unwrap_or(true),
+                    fail_fast: p.fail_fast,
+                    disable_hooks: p.disable_hooks,
+                    hooks: non_default(p.hooks.clone().or_else(|| self.hooks.clone())),
+                    required_approvals: p.required_approvals,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DesiredState { connections, plans })
+    }
+}
+
+impl ConnectionSpec {
+    fn resolve(&self, base_dir: &Path) -> Result<DesiredConnection> {
+        ensure!(
+            self.required_approvals >= 0,
+            "Connection '{}': required approvals can not be negative",
+            self.name
+        );
+
+        match (
+            &self.path,
+            &self.username,
+            &self.password,
+            &self.connection_string,
+        ) {
+            (Some(path), None, None, None) => {
+                let path = base_dir.join(path);
+                ensure!(
+                    path.is_dir(),
+                    "Connection '{}': directory '{}' does not exist",
+                    self.name,
+                    path.display()
+                );
+                let path = path
+                    .canonicalize()
+                    .context(format!("Failed to resolve directory '{}'", path.display()))?;
+                Ok(DesiredConnection {
+                    name: self.name.clone(),
+                    kind: ConnectionKind::Directory,
+                    username: String::new(),
+                    password: String::new(),
+                    connection_string: path.to_string_lossy().to_string(),
+                    required_approvals: self.required_approvals,
+                })
+            }
+            (None, Some(username), Some(password), Some(connection_string)) => {
+                ensure!(
+                    is_secret_reference(password),
+                    "Connection '{}': invalid password, expected a secret reference like 'env:NAME', 'file:PATH' or 'cmd:COMMAND', plain text is not allowed",
+                    self.name
+                );
+                Ok(DesiredConnection {
+                    name: self.name.clone(),
+                    kind: ConnectionKind::Oracle,
+                    username: username.clone(),
+                    password: password.clone(),
+                    connection_string: connection_string.clone(),
+                    required_approvals: self.required_approvals,
+                })
+            }
+            _ => bail!(
+                "Connection '{}' needs either a path, or a username, password and connection_string",
+                self.name
+            ),
+        }
+    }
+}
+
+fn ensure_unique<'a>(names: impl Iterator<Item = &'a str>, what: &str) -> Result<()> {
+    let mut seen = HashSet::new();
+    for name in names {
+        ensure!(
+            seen.insert(name.to_lowercase()),
+            "The {} '{}' is defined more than once, names are case-insensitive",
+            what,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// The values of `list` followed by the values of `defaults` it does not have
+fn combine(list: &Option<Vec<String>>, defaults: &Option<Vec<String>>) -> Option<Vec<String>> {
+    non_empty(Some(
+        list.iter()
+            .chain(defaults.iter())
+            .flatten()
+            .unique()
+            .cloned()
+            .collect(),
+    ))
+}
+
+pub(super) fn non_empty(list: Option<Vec<String>>) -> Option<Vec<String>> {
+    list.filter(|l| !l.is_empty())
+}
+
+pub(super) fn non_default(hooks: Option<Hooks>) -> Option<Hooks> {
+    hooks.filter(|h| *h != Hooks::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StringList;
+
+    const YAML: &str = r#"
+connections:
+  - name: dev
+    username: hr
+    password: file:/dev/null
+    connection_string: dev:1521/ORCL
+  - name: prod
+    username: hr
+    password: file:/dev/null
+    connection_string: prod:1521/ORCL
+    required_approvals: 2
+rules:
+  exclude_object_types: [JOB]
+  disable_all_drops: false
+hooks:
+  post_apply_deployment: ["echo done"]
+plans:
+  - name: release
+    source: dev
+    target: prod
+    schemas: [HR]
+    exclude_object_types: [DB_LINK, JOB]
+    fail_fast: true
+"#;
+
+    #[test]
+    fn test_resolve() {
+        let config = LeafConfig::parse(YAML, ConfigFormat::Yaml).unwrap();
+        let state = config.resolve(Path::new(".")).unwrap();
+
+        assert_eq!(state.connections.len(), 2);
+        assert_eq!(state.connections[1].kind, ConnectionKind::Oracle);
+        assert_eq!(state.connections[1].password, "file:/dev/null");
+        assert_eq!(state.connections[1].required_approvals, 2);
+
+        let plan = &state.plans[0];
+        assert_eq!(
+            plan.exclude_object_types,
+            Some(vec!["DB_LINK".to_string(), "JOB".to_string()])
+        );
+        assert!(!plan.disable_all_drops);
+        assert!(plan.fail_fast);
+        assert_eq!(
+            plan.hooks.as_ref().unwrap().post_apply_deployment,
+            Some(vec!["echo done".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_connections() {
+        let plain_password = r#"
+connections:
+  - name: prod
+    username: hr
+    password: s3cret
+    connection_string: prod:1521/ORCL
+"#;
+        let config = LeafConfig::parse(plain_password, ConfigFormat::Yaml).unwrap();
+        assert!(config.resolve(Path::new(".")).is_err());
+
+        let duplicate = r#"
+connections:
+  - name: docs
+    path: .
+  - name: DOCS
+    path: .
+"#;
+        let config = LeafConfig::parse(duplicate, ConfigFormat::Yaml).unwrap();
+        assert!(config.resolve(Path::new(".")).is_err());
+
+        let unknown_field = "connections:\n  - name: prod\n    passwd: env:X\n";
+        assert!(LeafConfig::parse(unknown_field, ConfigFormat::Yaml).is_err());
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let connections = vec![
+            ConnectionModel {
+                id: 1,
+                name: "dev".to_string(),
+                username: "hr".to_string(),
+                password: "cmd:pass show dev".to_string(),
+                connection_string: "dev:1521/ORCL".to_string(),
+                ..Default::default()
+            },
+            ConnectionModel {
+                id: 2,
+                name: "prod".to_string(),
+                username: "hr".to_string(),
+                password: "s3cret".to_string(),
+                connection_string: "prod:1521/ORCL".to_string(),
+                required_approvals: 1,
+                ..Default::default()
+            },
+        ];
+        let plans = vec![PlanModel {
+            id: 1,
+            name: "release".to_string(),
+            source_connection_id: 1,
+            target_connection_id: 2,
+            schemas: StringList(vec!["HR".to_string()]),
+            exclude_object_types: Some(StringList(vec!["JOB".to_string()])),
+            disable_all_drops: true,
+            hooks: Some(
+                serde_json::to_value(Hooks {
+                    pre_apply_deployment: Some(vec!["echo start".to_string()]),
+                    ..Default::default()
+                })
+                .unwrap(),
+            ),
+            ..Default::default()
+        }];
+
+        let config = LeafConfig::export(&connections, &plans).unwrap();
+        assert_eq!(
+            config.connections[0].password.as_deref(),
+            Some("cmd:pass show dev")
+        );
+        assert_eq!(
+            config.connections[1].password.as_deref(),
+            Some("env:LEAF_PROD_PASSWORD")
+        );
+        assert_eq!(config.plans[0].source, "dev");
+
+        for format in [ConfigFormat::Yaml, ConfigFormat::Toml] {
+            let content = config.to_string(format).unwrap();
+            assert!(!content.contains("s3cret"));
+            assert_eq!(LeafConfig::parse(&content, format).unwrap(), config);
+        }
+    }
+}
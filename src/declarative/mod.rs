@@ -0,0 +1,11 @@
+//! Connections, plans, rules and hooks described in a `leaf.yaml` or `leaf.toml` file,
+//! reconciled into the repository with `leaf apply-config`.
+
+mod file;
+mod reconcile;
+
+pub use file::{
+    ConfigFormat, ConnectionSpec, DesiredConnection, DesiredPlan, DesiredState, LeafConfig,
+    PlanSpec, RulesSpec,
+};
+pub use reconcile::{ConfigChange, ConfigDiff, FieldChange};
@@ -0,0 +1,440 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, bail, ensure};
+
+use crate::{
+    entities::{ConnectionModel, PlanModel},
+    types::{Hooks, PlanStatus},
+    utils::MASK,
+};
+
+use super::file::{DesiredConnection, DesiredPlan, DesiredState, non_default, non_empty};
+
+/// A field whose value changes, with printable old and new values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Clone, Debug)]
+pub enum ConfigChange {
+    CreateConnection(DesiredConnection),
+    UpdateConnection {
+        connection: DesiredConnection,
+        fields: Vec<FieldChange>,
+    },
+    DeleteConnection(String),
+    CreatePlan(DesiredPlan),
+    UpdatePlan {
+        plan: DesiredPlan,
+        fields: Vec<FieldChange>,
+    },
+    DeletePlan(String),
+}
+
+impl ConfigChange {
+    /// Whether the change touches more than the required approvals
+    pub fn changes_definition(&self) -> bool {
+        match self {
+            Self::UpdateConnection { fields, .. } | Self::UpdatePlan { fields, .. } => {
+                fields.iter().any(|f| f.field != "required_approvals")
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Changes that bring the repository to the state of a config file, in the order they
+/// are applied: connections are created and updated before the plans using them, plans
+/// are deleted before the connections they used.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigDiff {
+    pub changes: Vec<ConfigChange>,
+    /// Connections and plans of the file the repository already has as described
+    pub unchanged: usize,
+    /// Connections and plans of the repository missing from the file, kept without prune
+    pub unmanaged: Vec<String>,
+}
+
+impl ConfigDiff {
+    pub fn build(
+        connections: &[ConnectionModel],
+        plans: &[PlanModel],
+        desired: &DesiredState,
+        prune: bool,
+    ) -> Result<Self> {
+        let mut diff = Self::default();
+        let connection_names: HashMap<i32, &str> = connections
+            .iter()
+            .map(|c| (c.id, c.name.as_str()))
+            .collect();
+        let desired_connections: HashSet<String> = desired
+            .connections
+            .iter()
+            .map(|c| c.name.to_lowercase())
+            .collect();
+        let desired_plans: HashSet<String> = desired
+            .plans
+            .iter()
+            .map(|p| p.name.to_lowercase())
+            .collect();
+
+        for connection in &desired.connections {
+            match connections
+                .iter()
+                .find(|c| same_name(&c.name, &connection.name))
+            {
+                None => diff
+                    .changes
+                    .push(ConfigChange::CreateConnection(connection.clone())),
+                Some(current) => {
+                    if current.kind != connection.kind {
+                        bail!(
+                            "Connection '{}' is a {} connection in the repository and a {} connection in the file, remove it first",
+                            connection.name,
+                            current.kind,
+                            connection.kind
+                        );
+                    }
+                    let fields = connection_fields(current, connection);
+                    if fields.is_empty() {
+                        diff.unchanged += 1;
+                    } else {
+                        diff.changes.push(ConfigChange::UpdateConnection {
+                            connection: connection.clone(),
+                            fields,
+                        });
+                    }
+                }
+            }
+        }
+
+        for plan in &desired.plans {
+            for connection in [&plan.source, &plan.target] {
+                let in_file = desired_connections.contains(&connection.to_lowercase());
+                let in_repo = connections.iter().any(|c| same_name(&c.name, connection));
+                ensure!(
+                    in_file || (in_repo && !prune),
+                    "Plan '{}' uses connection '{}' which is not defined in the file",
+                    plan.name,
+                    connection
+                );
+            }
+            match plans.iter().find(|p| same_name(&p.name, &plan.name)) {
+                None => diff.changes.push(ConfigChange::CreatePlan(plan.clone())),
+                Some(current) => {
+                    let fields = plan_fields(current, plan, &connection_names)?;
+                    if fields.is_empty() {
+                        diff.unchanged += 1;
+                        continue;
+                    }
+                    ensure!(
+                        current.status != PlanStatus::Running,
+                        "Plan '{}' is running and can not be updated",
+                        current.name
+                    );
+                    diff.changes.push(ConfigChange::UpdatePlan {
+                        plan: plan.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+
+        for plan in plans {
+            if desired_plans.contains(&plan.name.to_lowercase()) {
+                continue;
+            }
+            if !prune {
+                diff.unmanaged.push(format!("plan {}", plan.name));
+                continue;
+            }
+            ensure!(
+                plan.status != PlanStatus::Running,
+                "Plan '{}' is running and can not be deleted",
+                plan.name
+            );
+            diff.changes
+                .push(ConfigChange::DeletePlan(plan.name.clone()));
+        }
+
+        for connection in connections {
+            if desired_connections.contains(&connection.name.to_lowercase()) {
+                continue;
+            }
+            if prune {
+                diff.changes
+                    .push(ConfigChange::DeleteConnection(connection.name.clone()));
+            } else {
+                diff.unmanaged
+                    .push(format!("connection {}", connection.name));
+            }
+        }
+
+        Ok(diff)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+fn connection_fields(current: &ConnectionModel, desired: &DesiredConnection) -> Vec<FieldChange> {
+    let mut fields = Vec::new();
+    push_field(
+        &mut fields,
+        "username",
+        &current.username,
+        &desired.username,
+    );
+    if current.password != desired.password {
+        fields.push(FieldChange {
+            field: "password",
+            from: MASK.to_string(),
+            to: MASK.to_string(),
+        });
+    }
+    push_field(
+        &mut fields,
+        "connection_string",
+        &current.connection_string,
+        &desired.connection_string,
+    );
+    push_field(
+        &mut fields,
+        "required_approvals",
+        &current.required_approvals.to_string(),
+        &desired.required_approvals.to_string(),
+    );
+    fields
+}
+
+fn plan_fields(
+    current: &PlanModel,
+    desired: &DesiredPlan,
+    connection_names: &HashMap<i32, &str>,
+) -> Result<Vec<FieldChange>> {
+    let connection_name = |id: i32| connection_names.get(&id).copied().unwrap_or_default();
+    let connection_differs = |id: i32, name: &str| !same_name(connection_name(id), name);
+
+    let mut fields = Vec::new();
+    if connection_differs(current.source_connection_id, &desired.source) {
+        push_field(
+            &mut fields,
+            "source",
+            connection_name(current.source_connection_id),
+            &desired.source,
+        );
+    }
+    if connection_differs(current.target_connection_id, &desired.target) {
+        push_field(
+            &mut fields,
+            "target",
+            connection_name(current.target_connection_id),
+            &desired.target,
+        );
+    }
+    push_field(
+        &mut fields,
+        "schemas",
+        &format_list(&Some(current.get_schemas())),
+        &format_list(&Some(desired.schemas.clone())),
+    );
+    push_field(
+        &mut fields,
+        "exclude_object_types",
+        &format_list(&non_empty(current.get_exclude_object_types())),
+        &format_list(&desired.exclude_object_types),
+    );
+    push_field(
+        &mut fields,
+        "exclude_object_names",
+        &format_list(&non_empty(current.get_exclude_object_names())),
+        &format_list(&desired.exclude_object_names),
+    );
+    push_field(
+        &mut fields,
+        "disabled_drop_types",
+        &format_list(&non_empty(
+            current.disabled_drop_types.as_ref().map(|d| d.0.clone()),
+        )),
+        &format_list(&desired.disabled_drop_types),
+    );
+    push_field(
+        &mut fields,
+        "disable_all_drops",
+        &current.disable_all_drops.to_string(),
+        &desired.disable_all_drops.to_string(),
+    );
+    push_field(
+        &mut fields,
+        "fail_fast",
+        &current.fail_fast.to_string(),
+        &desired.fail_fast.to_string(),
+    );
+    push_field(
+        &mut fields,
+        "disable_hooks",
+        &current.disable_hooks.to_string(),
+        &desired.disable_hooks.to_string(),
+    );
+    push_field(
+        &mut fields,
+        "hooks",
+        &format_hooks(&non_default(current.get_hooks()?))?,
+        &format_hooks(&desired.hooks)?,
+    );
+    push_field(
+        &mut fields,
+        "required_approvals",
+        &current.required_approvals.to_string(),
+        &desired.required_approvals.to_string(),
+    );
+    Ok(fields)
+}
+
+fn push_field(fields: &mut Vec<FieldChange>, field: &'static str, from: &str, to: &str) {
+    if from != to {
+        fields.push(FieldChange {
+            field,
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+}
+
+fn format_list(list: &Option<Vec<String>>) -> String {
+    match list {
+        Some(list) => format!("[{}]", list.join(", ")),
+        None => "-".to_string(),
+    }
+}
+
+fn format_hooks(hooks: &Option<Hooks>) -> Result<String> {
+    Ok(match hooks {
+        Some(hooks) => serde_json::to_string(hooks)?,
+        None => "-".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ConnectionKind, StringList};
+
+    fn connection(id: i32, name: &str, password: &str) -> ConnectionModel {
+        ConnectionModel {
+            id,
+            name: name.to_string(),
+            username: "hr".to_string(),
+            password: password.to_string(),
+            connection_string: format!("{}:1521/ORCL", name),
+            ..Default::default()
+        }
+    }
+
+    fn desired_connection(name: &str, password: &str) -> DesiredConnection {
+        DesiredConnection {
+            name: name.to_string(),
+            kind: ConnectionKind::Oracle,
+            username: "hr".to_string(),
+            password: password.to_string(),
+            connection_string: format!("{}:1521/ORCL", name),
+            required_approvals: 0,
+        }
+    }
+
+    fn plan(name: &str, schemas: &[&str]) -> PlanModel {
+        PlanModel {
+            id: 1,
+            name: name.to_string(),
+            source_connection_id: 1,
+            target_connection_id: 2,
+            schemas: StringList(schemas.iter().map(|s| s.to_string()).collect()),
+            disable_all_drops: true,
+            ..Default::default()
+        }
+    }
+
+    fn desired_plan(name: &str, schemas: &[&str]) -> DesiredPlan {
+        DesiredPlan {
+            name: name.to_string(),
+            source: "dev".to_string(),
+            target: "prod".to_string(),
+            schemas: schemas.iter().map(|s| s.to_string()).collect(),
+            exclude_object_types: None,
+            exclude_object_names: None,
+            disabled_drop_types: None,
+            disable_all_drops: true,
+            fail_fast: false,
+            disable_hooks: false,
+            hooks: None,
+            required_approvals: 0,
+        }
+    }
+
+    #[test]
+    fn test_config_diff() {
+        let connections = vec![
+            connection(1, "dev", "a"),
+            connection(2, "prod", "a"),
+            connection(3, "old", "a"),
+        ];
+        let plans = vec![plan("Release", &["HR"]), plan("legacy", &["HR"])];
+        let desired = DesiredState {
+            connections: vec![
+                desired_connection("dev", "a"),
+                desired_connection("prod", "b"),
+                desired_connection("test", "a"),
+            ],
+            plans: vec![
+                desired_plan("release", &["HR", "SALES"]),
+                desired_plan("nightly", &["HR"]),
+            ],
+        };
+
+        let diff = ConfigDiff::build(&connections, &plans, &desired, false).unwrap();
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.unmanaged, vec!["plan legacy", "connection old"]);
+        assert_eq!(diff.changes.len(), 4);
+        assert!(matches!(
+            &diff.changes[0],
+            ConfigChange::UpdateConnection { fields, .. }
+                if fields[0].field == "password" && fields[0].to == MASK
+        ));
+        assert!(matches!(&diff.changes[1], ConfigChange::CreateConnection(c) if c.name == "test"));
+        assert!(matches!(
+            &diff.changes[2],
+            ConfigChange::UpdatePlan { fields, .. }
+                if fields == &vec![FieldChange {
+                    field: "schemas",
+                    from: "[HR]".to_string(),
+                    to: "[HR, SALES]".to_string(),
+                }]
+        ));
+        assert!(matches!(&diff.changes[3], ConfigChange::CreatePlan(p) if p.name == "nightly"));
+
+        let diff = ConfigDiff::build(&connections, &plans, &desired, true).unwrap();
+        assert!(diff.unmanaged.is_empty());
+        assert!(matches!(&diff.changes[4], ConfigChange::DeletePlan(p) if p == "legacy"));
+        assert!(matches!(&diff.changes[5], ConfigChange::DeleteConnection(c) if c == "old"));
+    }
+
+    #[test]
+    fn test_config_diff_requires_connections() {
+        let connections = vec![connection(1, "dev", "a"), connection(2, "prod", "a")];
+        let desired = DesiredState {
+            connections: vec![desired_connection("dev", "a")],
+            plans: vec![desired_plan("release", &["HR"])],
+        };
+
+        assert!(ConfigDiff::build(&connections, &[], &desired, false).is_ok());
+        assert!(ConfigDiff::build(&connections, &[], &desired, true).is_err());
+    }
+}
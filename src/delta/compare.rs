@@ -0,0 +1,221 @@
+use std::collections::{BTreeMap, HashMap};
+
+use similar::TextDiff;
+use strum_macros::Display;
+
+use crate::{delta::ddl_matches, types::Object};
+
+/// How an object of one side relates to the same object on the other side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display)]
+#[strum(serialize_all = "title_case")]
+pub enum CompareStatus {
+    OnlyInSource,
+    OnlyInTarget,
+    Different,
+    Identical,
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectComparison {
+    pub object_type: String,
+    pub owner: String,
+    pub object_name: String,
+    pub status: CompareStatus,
+    pub source_ddl: Option<String>,
+    pub target_ddl: Option<String>,
+}
+
+impl ObjectComparison {
+    /// Unified diff from the target DDL to the source DDL, `None` for identical objects.
+    pub fn ddl_diff(&self, source_label: &str, target_label: &str) -> Option<String> {
+        if self.status == CompareStatus::Identical {
+            return None;
+        }
+        let source = with_trailing_newline(self.source_ddl.as_deref().unwrap_or_default());
+        let target = with_trailing_newline(self.target_ddl.as_deref().unwrap_or_default());
+        Some(
+            TextDiff::from_lines(&target, &source)
+                .unified_diff()
+                .header(target_label, source_label)
+                .to_string(),
+        )
+    }
+}
+
+fn with_trailing_newline(ddl: &str) -> String {
+    let ddl = ddl.trim_end();
+    if ddl.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", ddl)
+    }
+}
+
+/// Number of objects per status for one object type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompareSummary {
+    pub object_type: String,
+    pub only_in_source: usize,
+    pub only_in_target: usize,
+    pub different: usize,
+    pub identical: usize,
+}
+
+impl CompareSummary {
+    pub fn is_in_sync(&self) -> bool {
+        self.only_in_source == 0 && self.only_in_target == 0 && self.different == 0
+    }
+}
+
+/// Pairs source and target objects by owner, type and name. DDLs are compared with
+/// [`ddl_matches`] like when changesets are created, so whitespace outside of literals and
+/// trailing terminators do not count as differences.
+/// The result is sorted by type, owner and name.
+pub fn compare_objects(sources: Vec<Object>, targets: Vec<Object>) -> Vec<ObjectComparison> {
+    let key = |o: &Object| {
+        (
+            o.object_type.clone(),
+            o.owner.clone(),
+            o.object_name.clone(),
+        )
+    };
+    let mut targets: HashMap<(String, String, String), Object> =
+        targets.into_iter().map(|t| (key(&t), t)).collect();
+
+    let mut comparisons: Vec<ObjectComparison> = sources
+        .into_iter()
+        .map(|source| {
+            let target = targets.remove(&key(&source));
+            let status = match &target {
+                None => CompareStatus::OnlyInSource,
+                Some(t) if ddl_matches(source.ddl.as_deref(), t.ddl.as_deref()) => {
+                    CompareStatus::Identical
+                }
+                Some(_) => CompareStatus::Different,
+            };
+            ObjectComparison {
+                object_type: source.object_type,
+                owner: source.owner,
+                object_name: source.object_name,
+                status,
+                source_ddl: source.ddl,
+                target_ddl: target.and_then(|t| t.ddl),
+            }
+        })
+        .collect();
+
+    comparisons.extend(targets.into_values().map(|target| ObjectComparison {
+        object_type: target.object_type,
+        owner: target.owner,
+        object_name: target.object_name,
+        status: CompareStatus::OnlyInTarget,
+        source_ddl: None,
+        target_ddl: target.ddl,
+    }));
+
+    comparisons.sort_by(|a, b| {
+        (&a.object_type, &a.owner, &a.object_name).cmp(&(&b.object_type, &b.owner, &b.object_name))
+    });
+    comparisons
+}
+
+/// Counts the comparisons per object type, sorted by type.
+pub fn summarize(comparisons: &[ObjectComparison]) -> Vec<CompareSummary> {
+    let mut summaries: BTreeMap<&str, CompareSummary> = BTreeMap::new();
+    for comparison in comparisons {
+        let summary = summaries
+            .entry(&comparison.object_type)
+            .or_insert_with(|| CompareSummary {
+                object_type: comparison.object_type.clone(),
+                ..Default::default()
+            });
+        match comparison.status {
+            CompareStatus::OnlyInSource => summary.only_in_source += 1,
+            CompareStatus::OnlyInTarget => summary.only_in_target += 1,
+            CompareStatus::Different => summary.different += 1,
+            CompareStatus::Identical => summary.identical += 1,
+        }
+    }
+    summaries.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn object(object_type: &str, name: &str, ddl: &str) -> Object {
+        Object {
+            owner: "HR".to_string(),
+            object_name: name.to_string(),
+            object_type: object_type.to_string(),
+            last_ddl_time: NaiveDateTime::default(),
+            ddl: Some(ddl.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_compare_objects() {
+        let sources = vec![
+            object("VIEW", "V_SAME", "CREATE VIEW V_SAME AS SELECT 1 FROM DUAL"),
+            object("VIEW", "V_DIFF", "CREATE VIEW V_DIFF AS SELECT 2 FROM DUAL"),
+            object("TABLE", "NEW", "CREATE TABLE NEW (ID NUMBER)"),
+        ];
+        let targets = vec![
+            object(
+                "VIEW",
+                "V_SAME",
+                "CREATE VIEW V_SAME\n AS SELECT 1 FROM DUAL;",
+            ),
+            object("VIEW", "V_DIFF", "CREATE VIEW V_DIFF AS SELECT 1 FROM DUAL"),
+            object("VIEW", "V_OLD", "CREATE VIEW V_OLD AS SELECT 1 FROM DUAL"),
+        ];
+
+        let comparisons = compare_objects(sources, targets);
+
+        let statuses: Vec<(&str, CompareStatus)> = comparisons
+            .iter()
+            .map(|c| (c.object_name.as_str(), c.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("NEW", CompareStatus::OnlyInSource),
+                ("V_DIFF", CompareStatus::Different),
+                ("V_OLD", CompareStatus::OnlyInTarget),
+                ("V_SAME", CompareStatus::Identical),
+            ]
+        );
+
+        let summaries = summarize(&comparisons);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries[1],
+            CompareSummary {
+                object_type: "VIEW".to_string(),
+                only_in_source: 0,
+                only_in_target: 1,
+                different: 1,
+                identical: 1,
+            }
+        );
+        assert!(!summaries[1].is_in_sync());
+    }
+
+    #[test]
+    fn test_ddl_diff() {
+        let comparison = ObjectComparison {
+            object_type: "VIEW".to_string(),
+            owner: "HR".to_string(),
+            object_name: "V".to_string(),
+            status: CompareStatus::Different,
+            source_ddl: Some("CREATE VIEW V\nAS SELECT 2 FROM DUAL".to_string()),
+            target_ddl: Some("CREATE VIEW V\nAS SELECT 1 FROM DUAL".to_string()),
+        };
+
+        let diff = comparison.ddl_diff("dev", "prod").unwrap();
+
+        assert!(diff.starts_with("--- prod\n+++ dev\n"));
+        assert!(diff.contains("-AS SELECT 1 FROM DUAL\n+AS SELECT 2 FROM DUAL\n"));
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::{
+    delta::ddl_matches,
     types::{Delta, Object},
     utils::objects_as_map,
 };
@@ -105,7 +106,9 @@ fn parse_column(col_def: &str) -> Option<(String, String)> {
 }
 
 fn get_update_scripts(source: &Object, target: &Object) -> Vec<String> {
-    if source.ddl == target.ddl {
+    // Whitespace outside of literals does not count, a directory source holds the
+    // reformatted DDL of its files
+    if ddl_matches(source.ddl.as_deref(), target.ddl.as_deref()) {
         return Vec::new();
     }
 
@@ -579,6 +582,49 @@ END;"#
         assert!(delta.rollback_scripts.is_empty());
     }
 
+    #[test]
+    fn test_find_deltas_whitespace_in_literal_is_deployed() {
+        let s = mock_object(
+            "HR",
+            "GREET",
+            "PROCEDURE",
+            "CREATE OR REPLACE PROCEDURE GREET AS BEGIN DBMS_OUTPUT.PUT_LINE('Hello,  world'); END;",
+        );
+        let mut t = s.clone();
+        t.ddl = Some(
+            "CREATE OR REPLACE PROCEDURE GREET AS BEGIN DBMS_OUTPUT.PUT_LINE('Hello, world'); END;"
+                .to_string(),
+        );
+
+        let deltas = find_deltas(vec![s.clone()], vec![t], false);
+
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].scripts, vec![s.ddl.unwrap()]);
+    }
+
+    #[test]
+    fn test_find_deltas_agrees_with_ddl_matches() {
+        let source = "CREATE OR REPLACE VIEW V AS SELECT 'a  b' X FROM DUAL";
+        for target in [
+            source,
+            "\n  CREATE OR REPLACE VIEW V AS\n  SELECT 'a  b' X FROM DUAL;",
+            "CREATE OR REPLACE VIEW V AS SELECT 'a b' X FROM DUAL",
+            "CREATE OR REPLACE VIEW V AS SELECT 'a  b' Y FROM DUAL",
+        ] {
+            let s = mock_object("HR", "V", "VIEW", source);
+            let t = mock_object("HR", "V", "VIEW", target);
+
+            let deltas = find_deltas(vec![s], vec![t], false);
+
+            assert_eq!(
+                deltas[0].scripts.is_empty(),
+                ddl_matches(Some(source), Some(target)),
+                "{}",
+                target
+            );
+        }
+    }
+
     #[test]
     fn test_find_deltas_multiple_objects() {
         let s1 = mock_object(
@@ -0,0 +1,109 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::types::{Delta, DeltaOperation, DeltaRisk};
+
+/// Version of the [`DeltaDocument`] layout, bumped on breaking changes.
+pub const DELTA_DOCUMENT_VERSION: u32 = 1;
+
+/// Machine-readable description of the changes a plan produces, emitted by `--format json`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DeltaDocument {
+    pub version: u32,
+    pub plan: String,
+    pub cutoff_date: String,
+    pub dry_run: bool,
+    /// Prepared deployment, `None` in dry-run mode or when there are no changes
+    pub deployment_id: Option<i32>,
+    pub has_changes: bool,
+    pub change_count: usize,
+    pub changes: Vec<DeltaEntry>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DeltaEntry {
+    pub object_type: String,
+    pub object_owner: String,
+    pub object_name: String,
+    pub operation: DeltaOperation,
+    pub risk: DeltaRisk,
+    pub source_ddl_time: Option<String>,
+    pub target_ddl_time: Option<String>,
+    pub scripts: Vec<String>,
+    pub rollback_scripts: Vec<String>,
+}
+
+fn format_datetime(value: &NaiveDateTime) -> String {
+    value.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+impl From<&Delta> for DeltaEntry {
+    fn from(delta: &Delta) -> Self {
+        Self {
+            object_type: delta.object_type.clone(),
+            object_owner: delta.object_owner.clone(),
+            object_name: delta.object_name.clone(),
+            operation: delta.operation(),
+            risk: delta.risk(),
+            source_ddl_time: delta.source_ddl_time.as_ref().map(format_datetime),
+            target_ddl_time: delta.target_ddl_time.as_ref().map(format_datetime),
+            scripts: delta.scripts.clone(),
+            rollback_scripts: delta.rollback_scripts.clone(),
+        }
+    }
+}
+
+impl DeltaDocument {
+    pub fn new(
+        plan: &str,
+        cutoff_date: NaiveDateTime,
+        dry_run: bool,
+        deployment_id: Option<i32>,
+        deltas: &[Delta],
+    ) -> Self {
+        let changes: Vec<DeltaEntry> = deltas.iter().map(DeltaEntry::from).collect();
+        Self {
+            version: DELTA_DOCUMENT_VERSION,
+            plan: plan.to_string(),
+            cutoff_date: format_datetime(&cutoff_date),
+            dry_run,
+            deployment_id,
+            has_changes: !changes.is_empty(),
+            change_count: changes.len(),
+            changes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_delta_document() {
+        let cutoff_date = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let deltas = vec![Delta {
+            object_type: "TABLE".to_string(),
+            object_owner: "HR".to_string(),
+            object_name: "EMPLOYEES".to_string(),
+            target_ddl: Some("CREATE TABLE HR.EMPLOYEES (ID NUMBER)".to_string()),
+            scripts: vec!["DROP TABLE HR.EMPLOYEES".to_string()],
+            rollback_scripts: vec!["CREATE TABLE HR.EMPLOYEES (ID NUMBER)".to_string()],
+            ..Default::default()
+        }];
+
+        let document = DeltaDocument::new("demo", cutoff_date, true, None, &deltas);
+        let value = serde_json::to_value(&document).unwrap();
+
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["cutoff_date"], "2025-01-01T00:00:00");
+        assert_eq!(value["has_changes"], true);
+        assert_eq!(value["changes"][0]["operation"], "drop");
+        assert_eq!(value["changes"][0]["risk"], "high");
+        assert_eq!(value["changes"][0]["scripts"][0], "DROP TABLE HR.EMPLOYEES");
+    }
+}
@@ -1,3 +1,9 @@
+pub mod compare;
 pub mod delta;
+pub mod document;
+pub mod normalize;
 
+pub use compare::{CompareStatus, CompareSummary, ObjectComparison, compare_objects, summarize};
 pub use delta::{find_deltas, find_scripts};
+pub use document::DeltaDocument;
+pub use normalize::{ddl_matches, normalize_ddl};
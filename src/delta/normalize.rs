@@ -0,0 +1,181 @@
+use std::{iter::Peekable, str::Chars};
+
+/// Normalizes a DDL statement for comparison.
+///
+/// Whitespace runs are collapsed to a single space and trailing statement terminators
+/// (`;` and `/`) are removed, so formatting differences between two extractions of the
+/// same object do not count as a change. String literals (`'...'`, `q'[...]'`), quoted
+/// identifiers and comments are kept verbatim, whitespace inside them is significant.
+pub fn normalize_ddl(ddl: &str) -> String {
+    let mut out = String::with_capacity(ddl.len());
+    let mut chars = ddl.chars().peekable();
+    let mut pending_space = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space && !out.is_empty() {
+            out.push(' ');
+        }
+        pending_space = false;
+
+        match c {
+            '\'' => {
+                out.push(c);
+                copy_string_literal(&mut chars, &mut out);
+            }
+            '"' => {
+                out.push(c);
+                copy_until(&mut chars, &mut out, "\"");
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                out.push(c);
+                copy_until(&mut chars, &mut out, "\n");
+                // The line break ends the comment, it is whitespace of the statement
+                if out.ends_with('\n') {
+                    out.pop();
+                    pending_space = true;
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                out.push(c);
+                copy_until(&mut chars, &mut out, "*/");
+            }
+            'q' | 'Q' if chars.peek() == Some(&'\'') && starts_q_quote(&out) => {
+                out.push(c);
+                out.push(chars.next().unwrap());
+                if let Some(open) = chars.next() {
+                    out.push(open);
+                    let close = match open {
+                        '[' => ']',
+                        '{' => '}',
+                        '(' => ')',
+                        '<' => '>',
+                        other => other,
+                    };
+                    copy_until(&mut chars, &mut out, &format!("{}'", close));
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    out.trim_end_matches(|c: char| c == ';' || c == '/' || c.is_whitespace())
+        .to_string()
+}
+
+/// Copies the rest of a `'...'` literal after its opening quote, `''` is an escaped quote.
+fn copy_string_literal(chars: &mut Peekable<Chars>, out: &mut String) {
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c == '\'' {
+            if chars.peek() == Some(&'\'') {
+                out.push(chars.next().unwrap());
+            } else {
+                return;
+            }
+        }
+    }
+}
+
+/// Copies characters verbatim up to and including `end`, or to the end of the input.
+fn copy_until(chars: &mut Peekable<Chars>, out: &mut String, end: &str) {
+    for c in chars.by_ref() {
+        out.push(c);
+        if out.ends_with(end) {
+            return;
+        }
+    }
+}
+
+/// Returns true if a `q` following `out` starts a `q'...'` literal rather than ending an
+/// identifier. `nq'...'` is the national character set variant.
+fn starts_q_quote(out: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$' || c == '#';
+    let mut rev = out.chars().rev();
+    match rev.next() {
+        None => true,
+        Some('n' | 'N') => !rev.next().is_some_and(is_ident),
+        Some(c) => !is_ident(c),
+    }
+}
+
+/// Returns true if both DDLs describe the same object state.
+/// A missing DDL only matches another missing DDL.
+///
+/// This is the only comparison of DDLs: the delta engine skips the objects it matches, and
+/// verification, drift detection, `compare` and snapshot diffs use it too, so an object they
+/// report as in sync is never redeployed by the next prepare.
+pub fn ddl_matches(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => normalize_ddl(a) == normalize_ddl(b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_ddl_collapses_whitespace() {
+        let ddl = "\n  CREATE TABLE \"S\".\"T\"\n   (\t\"ID\" NUMBER\n   )  ;\n";
+        assert_eq!(
+            normalize_ddl(ddl),
+            "CREATE TABLE \"S\".\"T\" ( \"ID\" NUMBER )"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ddl_strips_terminators() {
+        assert_eq!(
+            normalize_ddl("CREATE OR REPLACE PROCEDURE P AS BEGIN NULL; END;\n/\n"),
+            "CREATE OR REPLACE PROCEDURE P AS BEGIN NULL; END"
+        );
+    }
+
+    #[test]
+    fn test_ddl_matches() {
+        assert!(ddl_matches(
+            Some("CREATE VIEW V AS SELECT 1 FROM DUAL"),
+            Some("  CREATE VIEW V\nAS SELECT 1 FROM DUAL;")
+        ));
+        assert!(!ddl_matches(
+            Some("CREATE VIEW V AS SELECT 1 FROM DUAL"),
+            Some("CREATE VIEW V AS SELECT 2 FROM DUAL")
+        ));
+        assert!(ddl_matches(None, None));
+        assert!(!ddl_matches(
+            Some("CREATE VIEW V AS SELECT 1 FROM DUAL"),
+            None
+        ));
+    }
+
+    #[test]
+    fn test_normalize_ddl_keeps_literals() {
+        assert!(!ddl_matches(
+            Some("CREATE VIEW V AS SELECT 'a  b' X FROM DUAL"),
+            Some("CREATE VIEW V AS SELECT 'a b' X FROM DUAL")
+        ));
+        assert!(!ddl_matches(
+            Some("BEGIN P(q'[it's  here]'); END;"),
+            Some("BEGIN P(q'[it's here]'); END;")
+        ));
+        assert!(!ddl_matches(
+            Some("CREATE TABLE \"A  B\" (ID NUMBER)"),
+            Some("CREATE TABLE \"A B\" (ID NUMBER)")
+        ));
+        // Quotes inside literals and comments do not leak into the rest of the statement
+        assert_eq!(
+            normalize_ddl("SELECT 'it''s'   ,  q'{x'  }'\n  FROM   DUAL"),
+            "SELECT 'it''s' , q'{x'  }' FROM DUAL"
+        );
+        assert_eq!(
+            normalize_ddl("BEGIN -- don't\n   NULL;   END;"),
+            "BEGIN -- don't NULL; END"
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel, Default)]
+#[sea_orm(table_name = "approvals")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub deployment_id: i32,
+
+    pub approved_by: String,
+
+    #[sea_orm(column_type = "Text")]
+    pub comment: Option<String>,
+
+    /// Checksum of all change scripts of the deployment at approval time
+    pub checksum: String,
+
+    #[sea_orm(default = "chrono::Utc::now().naive_utc()")]
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::deployment::Entity",
+        from = "Column::DeploymentId",
+        to = "super::deployment::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Deployment,
+}
+
+impl Related<super::deployment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Deployment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,49 @@
+use sea_orm::{JsonValue, entity::prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{AuditOperation, AuditOutcome, StringList};
+
+/// Who ran an operation, from where and how. Plans and deployments are referenced by ID
+/// without foreign keys, so events outlive the rows they describe.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "audit_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub operation: AuditOperation,
+
+    pub plan_id: Option<i32>,
+
+    pub deployment_id: Option<i32>,
+
+    pub outcome: AuditOutcome,
+
+    #[sea_orm(column_type = "Text")]
+    pub error: Option<String>,
+
+    /// Operating system user that ran leaf
+    pub os_user: String,
+
+    pub hostname: String,
+
+    pub leaf_version: String,
+
+    /// Command line arguments, secrets masked
+    #[sea_orm(column_type = "Text")]
+    pub arguments: StringList,
+
+    /// Effective settings, secrets masked
+    #[sea_orm(column_type = "Json")]
+    pub settings: JsonValue,
+
+    #[sea_orm(default = "chrono::Utc::now().naive_utc()")]
+    pub created_at: DateTime,
+
+    pub ended_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
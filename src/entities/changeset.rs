@@ -2,7 +2,7 @@ use std::hash::{Hash, Hasher};
 
 use sea_orm::{ActiveValue::Set, entity::prelude::*};
 
-use crate::types::{ChangesetStatus, StringList};
+use crate::types::{ChangesetStatus, StringList, VerificationStatus};
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Default)]
 #[sea_orm(table_name = "changesets")]
@@ -44,6 +44,11 @@ pub struct Model {
     pub started_at: Option<DateTime>,
 
     pub ended_at: Option<DateTime>,
+
+    /// Result of the post-apply verification, `None` if the changeset was not verified
+    pub verification_status: Option<VerificationStatus>,
+
+    pub verified_at: Option<DateTime>,
 }
 
 impl Hash for Model {
@@ -100,6 +105,15 @@ impl ActiveModel {
         self.ended_at = Set(Some(chrono::Utc::now().naive_utc()));
     }
 
+    /// Marks the changeset as cancelled, keeping the errors of the changes that did run
+    pub fn cancel(&mut self, errors: Option<StringList>) {
+        self.set_status(ChangesetStatus::Cancelled);
+        if errors.is_some() {
+            self.errors = Set(errors);
+        }
+        self.ended_at = Set(Some(chrono::Utc::now().naive_utc()));
+    }
+
     pub fn set_running(&mut self) {
         self.set_status(ChangesetStatus::Running);
     }
@@ -115,6 +129,11 @@ impl ActiveModel {
     pub fn set_rollback_error(&mut self) {
         self.set_status(ChangesetStatus::RollbackError);
     }
+
+    pub fn set_verification(&mut self, status: VerificationStatus) {
+        self.verification_status = Set(Some(status));
+        self.verified_at = Set(Some(chrono::Utc::now().naive_utc()));
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,6 +1,10 @@
 use sea_orm::entity::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Default)]
+use crate::{types::ConnectionKind, utils::MASK};
+
+/// A saved connection, `password` holds the stored form of the password, see
+/// [`SecretStore`](crate::utils::SecretStore)
+#[derive(Clone, PartialEq, Eq, DeriveEntityModel, Default)]
 #[sea_orm(table_name = "connections")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -13,11 +17,34 @@ pub struct Model {
 
     pub password: String,
 
+    /// Oracle connection string, or the path of the directory for directory connections
     pub connection_string: String,
 
+    #[sea_orm(default_value = "ORACLE")]
+    pub kind: ConnectionKind,
+
+    /// Number of distinct approvals a deployment targeting this connection needs
+    #[sea_orm(default_value = 0)]
+    pub required_approvals: i32,
+
     pub created_at: DateTime,
 }
 
+impl std::fmt::Debug for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Model")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("username", &self.username)
+            .field("password", &MASK)
+            .field("connection_string", &self.connection_string)
+            .field("kind", &self.kind)
+            .field("required_approvals", &self.required_approvals)
+            .field("created_at", &self.created_at)
+            .finish()
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
@@ -3,7 +3,7 @@ use chrono::Utc;
 use sea_orm::{ActiveValue::Set, JsonValue, entity::prelude::*};
 use serde::{Deserialize, Serialize};
 
-use crate::types::{DeploymentStatus, Hooks, StringList};
+use crate::types::{DeploymentStatus, HookExecution, Hooks, StringList};
 use sea_orm::ActiveModelBehavior;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
@@ -15,6 +15,9 @@ pub struct Model {
     #[sea_orm(unique)]
     pub plan_id: i32,
 
+    /// Revision of the plan the deployment was prepared with
+    pub plan_revision_id: Option<i32>,
+
     /// Deploy all objects modified after this date
     pub cutoff_date: DateTime,
 
@@ -33,6 +36,9 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub errors: Option<StringList>,
 
+    /// Release tag, unique per plan
+    pub tag: Option<String>,
+
     #[sea_orm(default = "chrono::Utc::now().naive_utc()")]
     pub created_at: DateTime,
 
@@ -43,6 +49,21 @@ pub struct Model {
     pub started_at: Option<DateTime>,
 
     pub ended_at: Option<DateTime>,
+
+    /// Process running the deployment
+    pub owner_pid: Option<i32>,
+
+    pub owner_host: Option<String>,
+
+    /// OS user running the deployment
+    pub owner_user: Option<String>,
+
+    /// Refreshed periodically while the deployment is running
+    pub heartbeat_at: Option<DateTime>,
+
+    /// Hook scripts executed for the deployment, see [`HookExecution`]
+    #[sea_orm(column_type = "Json", nullable)]
+    pub hook_executions: Option<JsonValue>,
 }
 
 impl Default for Model {
@@ -50,16 +71,23 @@ impl Default for Model {
         Self {
             id: 0,
             plan_id: 0,
+            plan_revision_id: None,
             cutoff_date: Utc::now().naive_utc(),
             payload: String::new(),
             disable_hooks: false,
             hooks: None,
             status: DeploymentStatus::default(),
             errors: None,
+            tag: None,
             created_at: Utc::now().naive_utc(),
             updated_at: Some(Utc::now().naive_utc()),
             started_at: Some(Utc::now().naive_utc()),
             ended_at: Some(Utc::now().naive_utc()),
+            owner_pid: None,
+            owner_host: None,
+            owner_user: None,
+            heartbeat_at: None,
+            hook_executions: None,
         }
     }
 }
@@ -128,6 +156,14 @@ impl Model {
         self.hooks = hooks.map(|h| serde_json::to_value(h)).transpose()?;
         Ok(())
     }
+
+    pub fn get_hook_executions(&self) -> Result<Vec<HookExecution>, serde_json::Error> {
+        self.hook_executions
+            .as_ref()
+            .map(|h| serde_json::from_value(h.clone()))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
 }
 
 impl ActiveModel {
@@ -144,7 +180,7 @@ impl ActiveModel {
             DeploymentStatus::Running => {
                 self.started_at = Set(Some(Utc::now().naive_utc()));
             }
-            DeploymentStatus::Success | DeploymentStatus::Error => {
+            DeploymentStatus::Success | DeploymentStatus::Error | DeploymentStatus::Cancelled => {
                 self.ended_at = Set(Some(Utc::now().naive_utc()));
             }
             _ => {}
@@ -174,6 +210,14 @@ impl ActiveModel {
         self.set_status(DeploymentStatus::Running);
     }
 
+    /// Records the process running the deployment and starts its heartbeat
+    pub fn set_owner(&mut self, pid: i32, host: String, user: String) {
+        self.owner_pid = Set(Some(pid));
+        self.owner_host = Set(Some(host));
+        self.owner_user = Set(Some(user));
+        self.heartbeat_at = Set(Some(Utc::now().naive_utc()));
+    }
+
     pub fn set_rolling_back(&mut self) {
         self.set_status(DeploymentStatus::RollingBack);
     }
@@ -0,0 +1,33 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A lock held by a leaf process while it prepares, applies or rolls back a plan.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel, Default)]
+#[sea_orm(table_name = "deployment_locks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub target_connection_id: i32,
+
+    #[sea_orm(unique)]
+    pub plan_id: i32,
+
+    /// Operation holding the lock, e.g. `apply`
+    pub operation: String,
+
+    pub owner_pid: i32,
+
+    pub owner_host: String,
+
+    pub acquired_at: DateTime,
+
+    /// Refreshed periodically while the owner is alive
+    pub heartbeat_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,10 +1,22 @@
+pub mod approval;
+pub mod audit_event;
 pub mod change;
 pub mod changeset;
 pub mod connection;
 pub mod deployment;
+pub mod deployment_lock;
 pub mod plan;
+pub mod plan_revision;
 pub mod rollback;
 
+pub use approval::{
+    ActiveModel as ApprovalActiveModel, Column as ApprovalColumn, Entity as ApprovalsEntity,
+    Model as ApprovalModel,
+};
+pub use audit_event::{
+    ActiveModel as AuditEventActiveModel, Column as AuditEventColumn, Entity as AuditEventsEntity,
+    Model as AuditEventModel,
+};
 pub use change::{
     ActiveModel as ChangeActiveModel, Column as ChangeColumn, Entity as ChangesEntity,
     Model as ChangeModel, Relation as ChangeRelation,
@@ -21,9 +33,17 @@ pub use deployment::{
     ActiveModel as DeploymentActiveModel, Column as DeploymentColumn, Entity as DeploymentsEntity,
     Model as DeploymentModel,
 };
+pub use deployment_lock::{
+    ActiveModel as DeploymentLockActiveModel, Column as DeploymentLockColumn,
+    Entity as DeploymentLocksEntity, Model as DeploymentLockModel,
+};
 pub use plan::{
     ActiveModel as PlanActiveModel, Column as PlanColumn, Entity as PlansEntity, Model as PlanModel,
 };
+pub use plan_revision::{
+    ActiveModel as PlanRevisionActiveModel, Column as PlanRevisionColumn,
+    Entity as PlanRevisionsEntity, Model as PlanRevisionModel,
+};
 
 pub use rollback::{
     ActiveModel as RollbackActiveModel, Column as RollbackColumn, Entity as RollbacksEntity,
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     hooks::{HookRunner, HookRunnerContext},
     oracle::OracleClient,
-    types::{Hooks, PlanStatus, StringList},
-    utils::{DeploymentContext, ProgressReporter},
+    types::{HookExecution, Hooks, PlanStatus, StringList},
+    utils::{DeploymentContext, ProgressEvent, ProgressReporter},
 };
 use anyhow::Result;
 use tera::Context as TeraContext;
@@ -50,6 +50,10 @@ pub struct Model {
     #[sea_orm(default_value = "IDLE")]
     pub status: PlanStatus,
 
+    /// Number of distinct approvals a deployment needs before it can be applied
+    #[sea_orm(default_value = 0)]
+    pub required_approvals: i32,
+
     #[sea_orm(default = "chrono::Utc::now().naive_utc()")]
     pub created_at: DateTime,
 }
@@ -77,9 +81,53 @@ impl Model {
             .transpose()
     }
 
+    /// Snapshot of the plan settings stored with each deployment.
+    ///
+    /// Runtime state such as the status is left out.
     pub fn as_payload(&self) -> serde_json::Value {
-        // reserved for future use
-        todo!()
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "source_connection_id": self.source_connection_id,
+            "target_connection_id": self.target_connection_id,
+            "schemas": self.schemas,
+            "exclude_object_types": self.exclude_object_types,
+            "exclude_object_names": self.exclude_object_names,
+            "disabled_drop_types": self.disabled_drop_types,
+            "disable_all_drops": self.disable_all_drops,
+            "fail_fast": self.fail_fast,
+            "disable_hooks": self.disable_hooks,
+            "hooks": self.hooks,
+            "required_approvals": self.required_approvals,
+        })
+    }
+
+    /// Names of the settings that differ between two plans, in payload order. Empty lists
+    /// equal missing ones, NULL list columns are read back empty.
+    pub fn changed_fields(&self, other: &Model) -> Vec<String> {
+        let (before, after) = (self.as_payload(), other.as_payload());
+        let value = |payload: &serde_json::Value, field: &str| match &payload[field] {
+            serde_json::Value::Array(a) if a.is_empty() => serde_json::Value::Null,
+            v => v.clone(),
+        };
+        [
+            "name",
+            "source_connection_id",
+            "target_connection_id",
+            "schemas",
+            "exclude_object_types",
+            "exclude_object_names",
+            "disabled_drop_types",
+            "disable_all_drops",
+            "fail_fast",
+            "disable_hooks",
+            "hooks",
+            "required_approvals",
+        ]
+        .into_iter()
+        .filter(|field| value(&before, field) != value(&after, field))
+        .map(str::to_string)
+        .collect()
     }
 
     pub async fn run_pre_prepare_hooks(
@@ -87,13 +135,14 @@ impl Model {
         disable_hooks: Option<bool>,
         client: &OracleClient,
         ctx: &mut DeploymentContext,
+        executions: &mut Vec<HookExecution>,
     ) -> Result<()> {
         let plan_name = self.name.clone();
         let mut tera_ctx = TeraContext::new();
         tera_ctx.insert("plan", &plan_name);
 
-        let progress = |msg: String| {
-            ctx.progress(msg);
+        let progress = |event: ProgressEvent| {
+            ctx.progress(event);
         };
 
         let mut hook_runner = HookRunner::new(
@@ -102,7 +151,9 @@ impl Model {
             HookRunnerContext::new(tera_ctx, progress),
         );
 
-        hook_runner.run_pre_prepare_deployment(client).await
+        let result = hook_runner.run_pre_prepare_deployment(client).await;
+        executions.extend(hook_runner.take_executions());
+        result
     }
 
     pub async fn run_post_prepare_hooks(
@@ -110,13 +161,14 @@ impl Model {
         disable_hooks: Option<bool>,
         client: &OracleClient,
         ctx: &mut DeploymentContext,
+        executions: &mut Vec<HookExecution>,
     ) -> Result<()> {
         let plan_name = self.name.clone();
         let mut tera_ctx = TeraContext::new();
         tera_ctx.insert("plan", &plan_name);
 
-        let progress = |msg: String| {
-            ctx.progress(msg);
+        let progress = |event: ProgressEvent| {
+            ctx.progress(event);
         };
 
         let mut hook_runner = HookRunner::new(
@@ -125,7 +177,9 @@ impl Model {
             HookRunnerContext::new(tera_ctx, progress),
         );
 
-        hook_runner.run_post_apply_deployment(client).await
+        let result = hook_runner.run_post_prepare_deployment(client).await;
+        executions.extend(hook_runner.take_executions());
+        result
     }
 
     pub async fn run_pre_apply_hooks(
@@ -133,13 +187,14 @@ impl Model {
         disable_hooks: Option<bool>,
         client: &OracleClient,
         ctx: &mut DeploymentContext,
+        executions: &mut Vec<HookExecution>,
     ) -> Result<()> {
         let plan_name = self.name.clone();
         let mut tera_ctx = TeraContext::new();
         tera_ctx.insert("plan", &plan_name);
 
-        let progress = |msg: String| {
-            ctx.progress(msg);
+        let progress = |event: ProgressEvent| {
+            ctx.progress(event);
         };
 
         let mut hook_runner = HookRunner::new(
@@ -148,7 +203,9 @@ impl Model {
             HookRunnerContext::new(tera_ctx, progress),
         );
 
-        hook_runner.run_pre_apply_deployment(client).await
+        let result = hook_runner.run_pre_apply_deployment(client).await;
+        executions.extend(hook_runner.take_executions());
+        result
     }
 
     pub async fn run_post_apply_hooks(
@@ -156,13 +213,14 @@ impl Model {
         disable_hooks: Option<bool>,
         client: &OracleClient,
         ctx: &mut DeploymentContext,
+        executions: &mut Vec<HookExecution>,
     ) -> Result<()> {
         let plan_name = self.name.clone();
         let mut tera_ctx = TeraContext::new();
         tera_ctx.insert("plan", &plan_name);
 
-        let progress = |msg: String| {
-            ctx.progress(msg);
+        let progress = |event: ProgressEvent| {
+            ctx.progress(event);
         };
 
         let mut hook_runner = HookRunner::new(
@@ -171,7 +229,9 @@ impl Model {
             HookRunnerContext::new(tera_ctx, progress),
         );
 
-        hook_runner.run_post_apply_deployment(client).await
+        let result = hook_runner.run_post_apply_deployment(client).await;
+        executions.extend(hook_runner.take_executions());
+        result
     }
 
     pub async fn run_pre_rollback_hooks(
@@ -179,13 +239,14 @@ impl Model {
         disable_hooks: Option<bool>,
         client: &OracleClient,
         progress: &ProgressReporter,
+        executions: &mut Vec<HookExecution>,
     ) -> Result<()> {
         let plan_name = self.name.clone();
         let mut tera_ctx = TeraContext::new();
         tera_ctx.insert("plan", &plan_name);
 
-        let progress = |msg: String| {
-            progress.report(msg);
+        let progress = |event: ProgressEvent| {
+            progress.report(event);
         };
 
         let mut hook_runner = HookRunner::new(
@@ -194,7 +255,9 @@ impl Model {
             HookRunnerContext::new(tera_ctx, progress),
         );
 
-        hook_runner.run_pre_rollback_hooks(client).await
+        let result = hook_runner.run_pre_rollback_hooks(client).await;
+        executions.extend(hook_runner.take_executions());
+        result
     }
 
     pub async fn run_post_rollback_hooks(
@@ -202,13 +265,14 @@ impl Model {
         disable_hooks: Option<bool>,
         client: &OracleClient,
         progress: &ProgressReporter,
+        executions: &mut Vec<HookExecution>,
     ) -> Result<()> {
         let plan_name = self.name.clone();
         let mut tera_ctx = TeraContext::new();
         tera_ctx.insert("plan", &plan_name);
 
-        let progress = |msg: String| {
-            progress.report(msg);
+        let progress = |event: ProgressEvent| {
+            progress.report(event);
         };
 
         let mut hook_runner = HookRunner::new(
@@ -217,7 +281,9 @@ impl Model {
             HookRunnerContext::new(tera_ctx, progress),
         );
 
-        hook_runner.run_post_rollback_hooks(client).await
+        let result = hook_runner.run_post_rollback_hooks(client).await;
+        executions.extend(hook_runner.take_executions());
+        result
     }
 }
 
@@ -285,30 +351,25 @@ impl Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 impl ActiveModel {
-    /// Create a new ActiveModel for insertion
-    pub fn new(
-        name: String,
-        source_connection_id: i32,
-        target_connection_id: i32,
-        schemas: Vec<String>,
-        exclude_object_types: Option<Vec<String>>,
-        exclude_object_names: Option<Vec<String>>,
-        disable_hooks: bool,
-        hooks: Option<Hooks>,
-    ) -> Result<Self, serde_json::Error> {
-        let hooks_json = hooks.map(|h| serde_json::to_value(h)).transpose()?;
-
-        Ok(Self {
-            name: Set(name),
-            source_connection_id: Set(source_connection_id),
-            target_connection_id: Set(target_connection_id),
-            schemas: Set(StringList(schemas)),
-            exclude_object_types: Set(exclude_object_types.map(StringList)),
-            exclude_object_names: Set(exclude_object_names.map(StringList)),
-            disable_hooks: Set(disable_hooks),
-            hooks: Set(hooks_json),
+    /// Create a new ActiveModel for insertion with the definition of `plan`, the id is
+    /// assigned on insert
+    pub fn new(plan: Model) -> Self {
+        Self {
+            name: Set(plan.name),
+            source_connection_id: Set(plan.source_connection_id),
+            target_connection_id: Set(plan.target_connection_id),
+            schemas: Set(plan.schemas),
+            exclude_object_types: Set(plan.exclude_object_types),
+            exclude_object_names: Set(plan.exclude_object_names),
+            disabled_drop_types: Set(plan.disabled_drop_types),
+            disable_all_drops: Set(plan.disable_all_drops),
+            fail_fast: Set(plan.fail_fast),
+            disable_hooks: Set(plan.disable_hooks),
+            hooks: Set(plan.hooks),
+            required_approvals: Set(plan.required_approvals),
+            status: Set(PlanStatus::default()),
             created_at: Set(chrono::Utc::now().naive_utc()),
             ..Default::default()
-        })
+        }
     }
 }
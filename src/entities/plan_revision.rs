@@ -0,0 +1,86 @@
+use sea_orm::{JsonValue, entity::prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::types::StringList;
+
+/// Definition of a plan after each change, numbered from 1 per plan. Deployments
+/// reference the revision they were prepared with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel, Default)]
+#[sea_orm(table_name = "plan_revisions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub plan_id: i32,
+
+    pub revision: i32,
+
+    pub name: String,
+
+    pub source_connection_id: i32,
+
+    pub target_connection_id: i32,
+
+    #[sea_orm(column_type = "Text")]
+    pub schemas: StringList,
+
+    #[sea_orm(column_type = "Text")]
+    pub exclude_object_types: Option<StringList>,
+
+    #[sea_orm(column_type = "Text")]
+    pub exclude_object_names: Option<StringList>,
+
+    #[sea_orm(column_type = "Text")]
+    pub disabled_drop_types: Option<StringList>,
+
+    #[sea_orm(column_type = "Integer")]
+    pub disable_all_drops: bool,
+
+    #[sea_orm(column_type = "Integer")]
+    pub fail_fast: bool,
+
+    #[sea_orm(column_type = "Integer")]
+    pub disable_hooks: bool,
+
+    #[sea_orm(column_type = "Json", nullable)]
+    pub hooks: Option<JsonValue>,
+
+    pub required_approvals: i32,
+
+    /// Fields that differ from the previous revision, empty for the first one. `None` for
+    /// revisions reconstructed when revisions were introduced, see [`Model::is_reconstructed`].
+    #[sea_orm(column_type = "Text")]
+    pub changed_fields: Option<StringList>,
+
+    #[sea_orm(default = "chrono::Utc::now().naive_utc()")]
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::plan::Entity",
+        from = "Column::PlanId",
+        to = "super::plan::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Plan,
+}
+
+impl Related<super::plan::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Plan.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Whether the revision was backfilled from the plan's definition at the time revisions
+    /// were introduced instead of recorded by a change. Deployments prepared before then
+    /// point to it but may have used an earlier definition.
+    pub fn is_reconstructed(&self) -> bool {
+        self.changed_fields.is_none()
+    }
+}
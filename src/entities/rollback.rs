@@ -19,6 +19,9 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub error: Option<String>,
 
+    /// Groups rollbacks created by a single rollback operation
+    pub operation_id: Option<String>,
+
     #[sea_orm(default = "chrono::Utc::now().naive_utc()")]
     pub created_at: DateTime,
 
@@ -16,8 +16,12 @@ impl SchemaValidationError {
 
 #[derive(Error, Debug)]
 pub enum DeployError {
-    #[error("Deployment failed with {0} errors: {1:?}")]
-    Errors(usize, Vec<String>),
+    #[error("Deployment {deployment_id} failed with {count} errors: {errors:?}")]
+    Errors {
+        deployment_id: i32,
+        count: usize,
+        errors: Vec<String>,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -31,3 +35,84 @@ pub enum PlanIsNotRunnableError {
     #[error("Target connection is in use")]
     TargetConnectionInUse,
 }
+
+#[derive(Error, Debug)]
+#[error(
+    "Rollback of deployment {failed} failed after reverting {} deployment(s) {reverted:?}: {message}",
+    reverted.len()
+)]
+pub struct RollbackChainError {
+    /// Deployments that were rolled back before the failure, in rollback order
+    pub reverted: Vec<i32>,
+    /// Deployment whose rollback failed
+    pub failed: i32,
+    /// Deployments that were not attempted because of the failure
+    pub skipped: Vec<i32>,
+    pub message: String,
+}
+
+#[derive(Error, Debug)]
+#[error("Tag '{tag}' is already used by deployment {deployment_id}")]
+pub struct TagInUseError {
+    pub tag: String,
+    pub deployment_id: i32,
+}
+
+#[derive(Error, Debug)]
+#[error(
+    "Target changed after the deployment was prepared, {} object(s) drifted: {}",
+    objects.len(),
+    objects.join(", ")
+)]
+pub struct DriftDetectedError {
+    pub objects: Vec<String>,
+}
+
+#[derive(Error, Debug)]
+#[error(
+    "Deployment {deployment_id} requires {required} approval(s), found {approved} for the current scripts{}",
+    if *stale > 0 { format!(" ({stale} approval(s) were given before the scripts changed)") } else { String::new() }
+)]
+pub struct ApprovalRequiredError {
+    pub deployment_id: i32,
+    pub required: usize,
+    /// Distinct approvers whose approval matches the current scripts
+    pub approved: usize,
+    /// Approvals given for a different version of the scripts
+    pub stale: usize,
+}
+
+#[derive(Error, Debug)]
+#[error(
+    "Plan {plan_id} / target connection {target_connection_id} is locked by `{operation}` on {owner_host} (pid {owner_pid}) since {acquired_at}, last heartbeat {heartbeat_at}"
+)]
+pub struct LockHeldError {
+    pub plan_id: i32,
+    pub target_connection_id: i32,
+    pub operation: String,
+    pub owner_pid: i32,
+    pub owner_host: String,
+    pub acquired_at: chrono::NaiveDateTime,
+    pub heartbeat_at: chrono::NaiveDateTime,
+}
+
+#[derive(Error, Debug)]
+#[error(
+    "Deployment lock {lock_id} was released or taken over by another process, the operation was cancelled"
+)]
+pub struct LockLostError {
+    pub lock_id: i32,
+}
+
+#[derive(Error, Debug)]
+#[error("Preparing a deployment of plan {plan_id} was cancelled, nothing was saved")]
+pub struct PrepareCancelledError {
+    pub plan_id: i32,
+}
+
+#[derive(Error, Debug)]
+#[error("Deployment {deployment_id} was cancelled, {not_applied} change(s) were not applied")]
+pub struct DeploymentCancelledError {
+    pub deployment_id: i32,
+    pub not_applied: usize,
+}
@@ -1,11 +1,15 @@
 use tera::Context;
 
-use crate::{oracle::OracleClient, types::Hooks};
+use crate::{
+    oracle::OracleClient,
+    types::{HookExecution, Hooks},
+    utils::ProgressEvent,
+};
 use anyhow::Result;
 
 pub struct HookRunnerContext<F>
 where
-    F: FnMut(String),
+    F: FnMut(ProgressEvent),
 {
     tera_ctx: Context,
     progress: F,
@@ -13,14 +17,14 @@ where
 
 impl<F> HookRunnerContext<F>
 where
-    F: FnMut(String),
+    F: FnMut(ProgressEvent),
 {
     pub fn new(tera_ctx: Context, progress: F) -> Self {
         Self { tera_ctx, progress }
     }
 
-    pub fn progress(&mut self, msg: String) {
-        (self.progress)(msg)
+    pub fn progress(&mut self, event: impl Into<ProgressEvent>) {
+        (self.progress)(event.into())
     }
 
     pub fn tera_ctx(&self) -> &Context {
@@ -30,26 +34,38 @@ where
 
 pub struct HookRunner<F>
 where
-    F: FnMut(String),
+    F: FnMut(ProgressEvent),
 {
     disable_hooks: bool,
     hooks: Option<Hooks>,
     ctx: HookRunnerContext<F>,
+    executions: Vec<HookExecution>,
 }
 
 impl<F> HookRunner<F>
 where
-    F: FnMut(String),
+    F: FnMut(ProgressEvent),
 {
     pub fn new(disable_hooks: bool, hooks: Option<Hooks>, ctx: HookRunnerContext<F>) -> Self {
         Self {
             disable_hooks,
             hooks,
             ctx,
+            executions: Vec::new(),
         }
     }
 
-    async fn run(&mut self, client: &OracleClient, scripts: Vec<String>) -> Result<()> {
+    /// Returns the hook scripts executed so far, including a failed one
+    pub fn take_executions(&mut self) -> Vec<HookExecution> {
+        std::mem::take(&mut self.executions)
+    }
+
+    async fn run(
+        &mut self,
+        client: &OracleClient,
+        stage: &str,
+        scripts: Vec<String>,
+    ) -> Result<()> {
         if self.disable_hooks {
             return Ok(());
         }
@@ -65,14 +81,30 @@ where
                 self.ctx.progress(format!("Skipping empty hook {}", i + 1));
                 continue;
             }
-            self.ctx
-                .progress(format!("Executing hook {} of {}", i + 1, script_count));
+            self.ctx.progress(ProgressEvent::HookStarted {
+                stage: stage.to_string(),
+                index: i + 1,
+                total: script_count,
+            });
 
             let script = script.trim();
-            if script.is_empty() {
-                continue;
-            }
-            client.execute(&script).await?;
+            let started_at = chrono::Utc::now().naive_utc();
+            let result = client.execute(&script).await;
+            let error = result.as_ref().err().map(|e| e.to_string());
+            self.ctx.progress(ProgressEvent::HookFinished {
+                stage: stage.to_string(),
+                index: i + 1,
+                total: script_count,
+                error: error.clone(),
+            });
+            self.executions.push(HookExecution {
+                stage: stage.to_string(),
+                script: script.to_string(),
+                started_at,
+                ended_at: chrono::Utc::now().naive_utc(),
+                error,
+            });
+            result?;
         }
 
         Ok(())
@@ -95,6 +127,7 @@ where
 
         self.run(
             client,
+            "pre_prepare_deployment",
             hooks
                 .get_pre_prepare_deployment(&self.ctx.tera_ctx())?
                 .unwrap_or_default(),
@@ -118,6 +151,7 @@ where
 
         self.run(
             client,
+            "post_prepare_deployment",
             hooks
                 .get_post_prepare_deployment(&self.ctx.tera_ctx())?
                 .unwrap_or_default(),
@@ -141,6 +175,7 @@ where
 
         self.run(
             client,
+            "pre_apply_deployment",
             hooks
                 .get_pre_apply_deployment(&self.ctx.tera_ctx())?
                 .unwrap_or_default(),
@@ -165,6 +200,7 @@ where
 
         self.run(
             client,
+            "post_apply_deployment",
             hooks
                 .get_post_apply_deployment(&self.ctx.tera_ctx())?
                 .unwrap_or_default(),
@@ -188,6 +224,7 @@ where
 
         self.run(
             client,
+            "pre_rollback",
             hooks
                 .get_pre_rollback(&self.ctx.tera_ctx())?
                 .unwrap_or_default(),
@@ -212,6 +249,7 @@ where
 
         self.run(
             client,
+            "post_rollback",
             hooks
                 .get_post_rollback(&self.ctx.tera_ctx())?
                 .unwrap_or_default(),
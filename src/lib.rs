@@ -1,12 +1,16 @@
 pub mod cli;
 pub mod config;
 pub mod db;
+pub mod declarative;
 pub mod delta;
 pub mod entities;
 pub mod errors;
 pub mod hooks;
 pub mod oracle;
 pub mod repo;
+pub mod report;
 pub mod services;
+pub mod snapshot;
+pub mod source;
 pub mod types;
 pub mod utils;
@@ -30,6 +30,33 @@ async fn main() {
             }
         };
 
+        if settings.recovery.on_startup && cli.should_recover_on_startup() {
+            match app_services.deployment_service.recover_interrupted().await {
+                Ok(records) if !records.is_empty() => eprintln!(
+                    "⚠️ Marked {} deployment(s) and {} plan(s) left RUNNING by a stopped process as INTERRUPTED",
+                    records.deployments.len(),
+                    records.plans.len()
+                ),
+                Ok(_) => {}
+                Err(e) => eprintln!("⚠️ Failed to recover interrupted deployments: {}", e),
+            }
+        }
+
+        if settings.retention.auto_purge && cli.should_purge_on_startup() {
+            match app_services
+                .deployment_service
+                .apply_retention(&settings.retention)
+                .await
+            {
+                Ok(summary) if summary.deployments > 0 => eprintln!(
+                    "🧹 Purged {} deployment(s) outside of the retention policy",
+                    summary.deployments
+                ),
+                Ok(_) => {}
+                Err(e) => eprintln!("⚠️ Failed to apply the retention policy: {}", e),
+            }
+        }
+
         cli.execute(&Context {
             settings: &settings,
             services: &app_services,
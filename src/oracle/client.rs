@@ -33,6 +33,16 @@ impl OracleClient {
         Ok(ddl)
     }
 
+    /// Same as [`get_ddl`](Self::get_ddl) but returns `None` if the object does not exist.
+    pub fn find_ddl(&self, object_type: &str, name: &str, schema: &str) -> Result<Option<String>> {
+        match self.get_ddl(object_type, name, schema) {
+            Ok(ddl) => Ok(Some(ddl)),
+            // ORA-31603: object "NAME" of type TYPE not found in schema "SCHEMA"
+            Err(e) if format!("{:#}", e).contains("ORA-31603") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn recompile_invalid_objects(&self, parallel_degree: Option<u32>) -> Result<()> {
         let degree = parallel_degree.unwrap_or(0);
         let sql = "BEGIN sys.UTL_RECOMP.recomp_parallel(:1); END;";
@@ -94,11 +104,16 @@ impl OracleClient {
         &self,
         schemas: Vec<String>,
         cutoff_date: Option<NaiveDateTime>,
+        object_types: Option<Vec<String>>,
         exclude_object_types: Option<Vec<String>>,
         exclude_object_names: Option<Vec<String>>,
     ) -> Result<Vec<Object>> {
         let mut ctx = Context::new();
         ctx.insert("schemas", &format_sql_list(schemas));
+        ctx.insert(
+            "object_types",
+            &format_sql_list(object_types.unwrap_or(vec![])),
+        );
         ctx.insert(
             "exclude_object_types",
             &format_sql_list(exclude_object_types.unwrap_or(vec![])),
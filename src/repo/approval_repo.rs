@@ -0,0 +1,64 @@
+use crate::entities::{ApprovalActiveModel, ApprovalColumn, ApprovalModel, ApprovalsEntity};
+
+use anyhow::{Context, Result};
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+
+pub struct ApprovalRepository {
+    db: DatabaseConnection,
+}
+
+impl ApprovalRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        deployment_id: i32,
+        approved_by: &str,
+        comment: Option<&str>,
+        checksum: &str,
+    ) -> Result<ApprovalModel> {
+        let active_model = ApprovalActiveModel {
+            id: NotSet,
+            deployment_id: Set(deployment_id),
+            approved_by: Set(approved_by.to_string()),
+            comment: Set(comment.map(|c| c.to_string())),
+            checksum: Set(checksum.to_string()),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        };
+        let result = ApprovalsEntity::insert(active_model)
+            .exec(&self.db)
+            .await
+            .context(format!(
+                "Failed to insert approval for deployment {}",
+                deployment_id
+            ))?;
+
+        self.get_by_id(result.last_insert_id)
+            .await
+            .context("Approval was created but could not be retrieved")
+    }
+
+    pub async fn get_by_id(&self, id: i32) -> Result<ApprovalModel> {
+        ApprovalsEntity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .context(format!("Approval with ID {} not found", id))
+    }
+
+    pub async fn find_by_deployment_id(&self, deployment_id: i32) -> Result<Vec<ApprovalModel>> {
+        ApprovalsEntity::find()
+            .filter(ApprovalColumn::DeploymentId.eq(deployment_id))
+            .order_by_asc(ApprovalColumn::Id)
+            .all(&self.db)
+            .await
+            .context(format!(
+                "Failed to find approvals for deployment {}",
+                deployment_id
+            ))
+    }
+}
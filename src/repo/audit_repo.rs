@@ -0,0 +1,105 @@
+use crate::{
+    entities::{AuditEventActiveModel, AuditEventColumn, AuditEventModel, AuditEventsEntity},
+    types::{AuditOperation, AuditOrigin, AuditOutcome, StringList},
+};
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+pub struct AuditRepository {
+    db: DatabaseConnection,
+}
+
+impl AuditRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        operation: AuditOperation,
+        plan_id: Option<i32>,
+        deployment_id: Option<i32>,
+        origin: &AuditOrigin,
+    ) -> Result<AuditEventModel> {
+        let active_model = AuditEventActiveModel {
+            id: NotSet,
+            operation: Set(operation),
+            plan_id: Set(plan_id),
+            deployment_id: Set(deployment_id),
+            outcome: Set(AuditOutcome::Started),
+            error: Set(None),
+            os_user: Set(origin.os_user.clone()),
+            hostname: Set(origin.hostname.clone()),
+            leaf_version: Set(origin.leaf_version.clone()),
+            arguments: Set(StringList(origin.arguments.clone())),
+            settings: Set(origin.settings.clone()),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            ended_at: Set(None),
+        };
+        let result = AuditEventsEntity::insert(active_model)
+            .exec(&self.db)
+            .await
+            .context(format!("Failed to insert audit event for {}", operation))?;
+
+        self.get_by_id(result.last_insert_id)
+            .await
+            .context("Audit event was created but could not be retrieved")
+    }
+
+    pub async fn get_by_id(&self, id: i32) -> Result<AuditEventModel> {
+        AuditEventsEntity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .context(format!("Audit event with ID {} not found", id))
+    }
+
+    /// Records the outcome of an operation. `deployment_id` is only set if the event
+    /// has none yet, e.g. for the deployment a prepare created.
+    pub async fn finish(
+        &self,
+        id: i32,
+        outcome: AuditOutcome,
+        deployment_id: Option<i32>,
+        error: Option<String>,
+    ) -> Result<AuditEventModel> {
+        let event = self.get_by_id(id).await?;
+        let mut active: AuditEventActiveModel = event.clone().into();
+        active.outcome = Set(outcome);
+        active.error = Set(error);
+        active.ended_at = Set(Some(chrono::Utc::now().naive_utc()));
+        if event.deployment_id.is_none() && deployment_id.is_some() {
+            active.deployment_id = Set(deployment_id);
+        }
+        active
+            .update(&self.db)
+            .await
+            .context(format!("Failed to update audit event {}", id))
+    }
+
+    /// Events newest first, optionally created at or after `since` and of one plan.
+    pub async fn find(
+        &self,
+        since: Option<NaiveDateTime>,
+        plan_id: Option<i32>,
+        limit: Option<u64>,
+    ) -> Result<Vec<AuditEventModel>> {
+        let mut query = AuditEventsEntity::find();
+        if let Some(since) = since {
+            query = query.filter(AuditEventColumn::CreatedAt.gte(since));
+        }
+        if let Some(plan_id) = plan_id {
+            query = query.filter(AuditEventColumn::PlanId.eq(plan_id));
+        }
+        query
+            .order_by_desc(AuditEventColumn::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .context("Failed to find audit events")
+    }
+}
@@ -12,6 +12,7 @@ use sea_orm::{
     ActiveValue::{NotSet, Set},
     ColumnTrait, DatabaseConnection, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
     QuerySelect,
+    sea_query::Expr,
 };
 
 pub struct ChangeRepository {
@@ -142,4 +143,39 @@ impl ChangeRepository {
             .await
             .context("Change was updated but could not be retrieved")
     }
+
+    /// Moves the running changes of a deployment to `status`. Returns the number of changes.
+    pub async fn set_running_status_by_deployment_id(
+        &self,
+        deployment_id: i32,
+        status: ChangeStatus,
+    ) -> Result<u64> {
+        let changeset_ids: Vec<i32> = ChangesetsEntity::find()
+            .select_only()
+            .column(ChangesetColumn::Id)
+            .filter(ChangesetColumn::DeploymentId.eq(deployment_id))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .context(format!(
+                "Failed to find changesets of deployment {}",
+                deployment_id
+            ))?;
+
+        let res = ChangesEntity::update_many()
+            .col_expr(ChangeColumn::Status, Expr::value(status))
+            .col_expr(
+                ChangeColumn::UpdatedAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(ChangeColumn::ChangesetId.is_in(changeset_ids))
+            .filter(ChangeColumn::Status.eq(ChangeStatus::Running))
+            .exec(&self.db)
+            .await
+            .context(format!(
+                "Failed to update running changes of deployment {}",
+                deployment_id
+            ))?;
+        Ok(res.rows_affected)
+    }
 }
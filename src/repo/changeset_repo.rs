@@ -1,7 +1,7 @@
 use crate::{
     entities::{
-        ChangeModel, ChangesEntity, ChangesetActiveModel, ChangesetColumn, ChangesetModel,
-        ChangesetsEntity,
+        ChangeActiveModel, ChangeColumn, ChangeModel, ChangesEntity, ChangesetActiveModel,
+        ChangesetColumn, ChangesetModel, ChangesetsEntity,
     },
     types::ChangesetStatus,
 };
@@ -11,6 +11,7 @@ use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
     ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
 };
 
 pub struct ChangesetRepository {
@@ -134,6 +135,47 @@ impl ChangesetRepository {
         Ok(saved.try_into()?)
     }
 
+    /// Saves the changeset and replaces its changes with `scripts`, pairs of script and
+    /// rollback script, in one transaction.
+    pub async fn replace_changes(
+        &self,
+        changeset: &ChangesetActiveModel,
+        scripts: &[(String, String)],
+    ) -> Result<ChangesetModel> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .context("Failed to start changeset transaction")?;
+
+        let saved: ChangesetModel = changeset.clone().save(&txn).await?.try_into()?;
+        ChangesEntity::delete_many()
+            .filter(ChangeColumn::ChangesetId.eq(saved.id))
+            .exec(&txn)
+            .await
+            .context(format!(
+                "Failed to delete changes of changeset {}",
+                saved.id
+            ))?;
+        for (script, rollback_script) in scripts {
+            ChangeActiveModel {
+                id: NotSet,
+                changeset_id: Set(saved.id),
+                script: Set(script.clone()),
+                rollback_script: Set(rollback_script.clone()),
+                ..Default::default()
+            }
+            .save(&txn)
+            .await
+            .context(format!("Failed to create change of changeset {}", saved.id))?;
+        }
+
+        txn.commit()
+            .await
+            .context("Failed to commit changeset transaction")?;
+        Ok(saved)
+    }
+
     pub async fn delete(&self, id: i32) -> Result<u64> {
         let res = ChangesetsEntity::delete_by_id(id).exec(&self.db).await?;
         Ok(res.rows_affected)
@@ -168,6 +210,45 @@ impl ChangesetRepository {
             ))
     }
 
+    /// Successful changesets of the deployments, oldest first
+    pub async fn find_successful_by_deployment_ids(
+        &self,
+        deployment_ids: Vec<i32>,
+    ) -> Result<Vec<ChangesetModel>> {
+        ChangesetsEntity::find()
+            .filter(ChangesetColumn::DeploymentId.is_in(deployment_ids))
+            .filter(ChangesetColumn::Status.eq(ChangesetStatus::Success))
+            .order_by_asc(ChangesetColumn::Id)
+            .all(&self.db)
+            .await
+            .context("Failed to find successful changesets")
+    }
+
+    /// Every changeset of an object with its changes, oldest first. All object types
+    /// with the name are returned if `object_type` is not set.
+    pub async fn find_by_object_with_changes(
+        &self,
+        object_owner: &str,
+        object_name: &str,
+        object_type: Option<&str>,
+    ) -> Result<Vec<(ChangesetModel, Vec<ChangeModel>)>> {
+        let mut query = ChangesetsEntity::find()
+            .filter(ChangesetColumn::ObjectOwner.eq(object_owner))
+            .filter(ChangesetColumn::ObjectName.eq(object_name));
+        if let Some(object_type) = object_type {
+            query = query.filter(ChangesetColumn::ObjectType.eq(object_type));
+        }
+        query
+            .order_by_asc(ChangesetColumn::Id)
+            .find_with_related(ChangesEntity)
+            .all(&self.db)
+            .await
+            .context(format!(
+                "Failed to find changesets of {}.{}",
+                object_owner, object_name
+            ))
+    }
+
     pub async fn set_status(&self, id: i32, status: ChangesetStatus) -> Result<ChangesetModel> {
         let changeset = self.get_by_id(id).await?;
         let mut active: ChangesetActiveModel = changeset.into();
@@ -182,4 +263,27 @@ impl ChangesetRepository {
             .await
             .context("Changeset was updated but could not be retrieved")
     }
+
+    /// Moves the running changesets of a deployment to `status`. Returns the number of changesets.
+    pub async fn set_running_status_by_deployment_id(
+        &self,
+        deployment_id: i32,
+        status: ChangesetStatus,
+    ) -> Result<u64> {
+        let res = ChangesetsEntity::update_many()
+            .col_expr(ChangesetColumn::Status, Expr::value(status))
+            .col_expr(
+                ChangesetColumn::UpdatedAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(ChangesetColumn::DeploymentId.eq(deployment_id))
+            .filter(ChangesetColumn::Status.eq(ChangesetStatus::Running))
+            .exec(&self.db)
+            .await
+            .context(format!(
+                "Failed to update running changesets of deployment {}",
+                deployment_id
+            ))?;
+        Ok(res.rows_affected)
+    }
 }
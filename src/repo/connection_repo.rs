@@ -1,20 +1,32 @@
-use crate::entities::{
-    ConnectionActiveModel, ConnectionColumn, ConnectionModel, ConnectionsEntity,
+use std::sync::Arc;
+
+use crate::{
+    entities::{ConnectionActiveModel, ConnectionColumn, ConnectionModel, ConnectionsEntity},
+    repo::name_matches,
+    types::ConnectionKind,
+    utils::{SecretKey, SecretStore, is_secret_reference},
 };
 use anyhow::{Context, Result};
 use sea_orm::{
+    ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    Condition, DatabaseConnection, EntityTrait, ExprTrait, QueryFilter,
-    sea_query::Expr,
+    Condition, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait,
 };
 
+/// Connections of the repository, passwords are sealed with the [`SecretStore`] before
+/// they are written and returned in their stored form.
 pub struct ConnectionRepository {
     db: DatabaseConnection,
+    secrets: Arc<SecretStore>,
 }
 
 impl ConnectionRepository {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, secrets: Arc<SecretStore>) -> Self {
+        Self { db, secrets }
+    }
+
+    pub fn secrets(&self) -> Arc<SecretStore> {
+        self.secrets.clone()
     }
 
     pub async fn get_db(&self) -> DatabaseConnection {
@@ -30,7 +42,7 @@ impl ConnectionRepository {
 
     pub async fn find_by_name(&self, name: &str) -> Result<Option<ConnectionModel>> {
         ConnectionsEntity::find()
-            .filter(Condition::all().add(Expr::col(ConnectionColumn::Name).like(name)))
+            .filter(Condition::all().add(name_matches(ConnectionColumn::Name, name)))
             .one(&self.db)
             .await
             .context(format!("Failed to find connection by name: {}", name))
@@ -61,13 +73,15 @@ impl ConnectionRepository {
         username: &str,
         password: &str,
         connection_string: &str,
+        required_approvals: i32,
     ) -> Result<ConnectionModel> {
         let active_model = ConnectionActiveModel {
             id: NotSet,
             name: Set(name.to_string()),
             username: Set(username.to_string()),
-            password: Set(password.to_string()),
+            password: Set(self.secrets.seal(password)?),
             connection_string: Set(connection_string.to_string()),
+            required_approvals: Set(required_approvals),
             ..Default::default()
         };
 
@@ -80,6 +94,140 @@ impl ConnectionRepository {
             .unwrap())
     }
 
+    /// Creates a directory connection, the path is stored as its connection string
+    pub async fn create_directory(
+        &self,
+        name: &str,
+        path: &str,
+        required_approvals: i32,
+    ) -> Result<ConnectionModel> {
+        let active_model = ConnectionActiveModel {
+            id: NotSet,
+            name: Set(name.to_string()),
+            username: Set(String::new()),
+            password: Set(String::new()),
+            connection_string: Set(path.to_string()),
+            kind: Set(ConnectionKind::Directory),
+            required_approvals: Set(required_approvals),
+            ..Default::default()
+        };
+
+        let res = ConnectionsEntity::insert(active_model)
+            .exec(&self.db)
+            .await
+            .context(format!("Failed to create directory connection '{}'", name))?;
+        self.get_by_id(res.last_insert_id).await
+    }
+
+    /// Updates the credentials and connection string, or the path of directory connections
+    pub async fn update(
+        &self,
+        id: i32,
+        username: &str,
+        password: &str,
+        connection_string: &str,
+        required_approvals: i32,
+    ) -> Result<ConnectionModel> {
+        let connection = self.get_by_id(id).await?;
+
+        let mut active: ConnectionActiveModel = connection.into();
+        active.username = Set(username.to_string());
+        active.password = Set(self.secrets.seal(password)?);
+        active.connection_string = Set(connection_string.to_string());
+        active.required_approvals = Set(required_approvals);
+        active
+            .update(&self.db)
+            .await
+            .context(format!("Failed to update connection {}", id))?;
+
+        self.get_by_id(id).await
+    }
+
+    /// Renames a connection and replaces its credentials and connection string in a single
+    /// update, plans reference it by id and keep working
+    pub async fn edit(
+        &self,
+        id: i32,
+        name: &str,
+        username: &str,
+        password: &str,
+        connection_string: &str,
+    ) -> Result<ConnectionModel> {
+        let connection = self.get_by_id(id).await?;
+
+        let mut active: ConnectionActiveModel = connection.into();
+        active.name = Set(name.to_string());
+        active.username = Set(username.to_string());
+        active.password = Set(self.secrets.seal(password)?);
+        active.connection_string = Set(connection_string.to_string());
+        active
+            .update(&self.db)
+            .await
+            .context(format!("Failed to update connection {}", id))?;
+
+        self.get_by_id(id).await
+    }
+
+    pub async fn set_required_approvals(
+        &self,
+        id: i32,
+        required_approvals: i32,
+    ) -> Result<ConnectionModel> {
+        let connection = self.get_by_id(id).await?;
+
+        let mut active: ConnectionActiveModel = connection.into();
+        active.required_approvals = Set(required_approvals);
+        active.update(&self.db).await.context(format!(
+            "Failed to update required approvals for connection {}",
+            id
+        ))?;
+
+        self.get_by_id(id).await
+    }
+
+    /// Encrypts the passwords stored in plain text before encryption at rest, returns
+    /// how many were encrypted
+    pub async fn encrypt_plaintext_passwords(&self) -> Result<usize> {
+        let connections = self.get_all().await?;
+        let txn = self.db.begin().await?;
+        let mut count = 0;
+        for connection in connections {
+            let password = self.secrets.seal(&connection.password)?;
+            if password == connection.password {
+                continue;
+            }
+            let mut active: ConnectionActiveModel = connection.into();
+            active.password = Set(password);
+            active.update(&txn).await?;
+            count += 1;
+        }
+        txn.commit().await?;
+        Ok(count)
+    }
+
+    /// Re-encrypts every encrypted password with `key` in one transaction, returns how
+    /// many were re-encrypted. References are kept as is.
+    pub async fn reencrypt_passwords(&self, key: &SecretKey) -> Result<usize> {
+        let connections = self.get_all().await?;
+        let txn = self.db.begin().await?;
+        let mut count = 0;
+        for connection in connections {
+            if connection.password.is_empty() || is_secret_reference(&connection.password) {
+                continue;
+            }
+            let plaintext = self.secrets.reveal(&connection.password).context(format!(
+                "Failed to decrypt the password of connection '{}'",
+                connection.name
+            ))?;
+            let mut active: ConnectionActiveModel = connection.into();
+            active.password = Set(self.secrets.seal_with(key, &plaintext)?);
+            active.update(&txn).await?;
+            count += 1;
+        }
+        txn.commit().await?;
+        Ok(count)
+    }
+
     pub async fn delete(&self, id: i32) -> Result<u64, sea_orm::DbErr> {
         let res = ConnectionsEntity::delete_by_id(id).exec(&self.db).await?;
         Ok(res.rows_affected)
@@ -1,13 +1,20 @@
 use crate::{
-    entities::{DeploymentActiveModel, DeploymentColumn, DeploymentModel, DeploymentsEntity},
-    types::{DeploymentStatus, Hooks, StringList},
+    entities::{
+        ApprovalColumn, ApprovalsEntity, ChangeColumn, ChangesEntity, ChangesetColumn,
+        ChangesetsEntity, DeploymentActiveModel, DeploymentColumn, DeploymentModel,
+        DeploymentsEntity, RollbackColumn, RollbacksEntity,
+    },
+    errors::TagInUseError,
+    types::{DeploymentStatus, HookExecution, Hooks, PurgeSummary, StringList},
 };
 use anyhow::{Context, Result};
 use chrono::NaiveDateTime;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect,
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder,
+    QuerySelect, SqlErr, TransactionTrait,
+    sea_query::Expr,
 };
 
 pub struct DeploymentRepository {
@@ -86,6 +93,71 @@ impl DeploymentRepository {
         Ok(res.rows_affected)
     }
 
+    /// Deletes the deployments with their changesets, changes, rollbacks and approvals in
+    /// one transaction.
+    pub async fn purge(&self, ids: &[i32]) -> Result<PurgeSummary> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .context("Failed to start purge transaction")?;
+
+        let changeset_ids: Vec<i32> = ChangesetsEntity::find()
+            .select_only()
+            .column(ChangesetColumn::Id)
+            .filter(ChangesetColumn::DeploymentId.is_in(ids.to_vec()))
+            .into_tuple()
+            .all(&txn)
+            .await
+            .context("Failed to find changesets to purge")?;
+        let change_ids: Vec<i32> = ChangesEntity::find()
+            .select_only()
+            .column(ChangeColumn::Id)
+            .filter(ChangeColumn::ChangesetId.is_in(changeset_ids.clone()))
+            .into_tuple()
+            .all(&txn)
+            .await
+            .context("Failed to find changes to purge")?;
+
+        let rollbacks = RollbacksEntity::delete_many()
+            .filter(RollbackColumn::ChangeId.is_in(change_ids.clone()))
+            .exec(&txn)
+            .await
+            .context("Failed to purge rollbacks")?;
+        let changes = ChangesEntity::delete_many()
+            .filter(ChangeColumn::Id.is_in(change_ids))
+            .exec(&txn)
+            .await
+            .context("Failed to purge changes")?;
+        let changesets = ChangesetsEntity::delete_many()
+            .filter(ChangesetColumn::Id.is_in(changeset_ids))
+            .exec(&txn)
+            .await
+            .context("Failed to purge changesets")?;
+        let approvals = ApprovalsEntity::delete_many()
+            .filter(ApprovalColumn::DeploymentId.is_in(ids.to_vec()))
+            .exec(&txn)
+            .await
+            .context("Failed to purge approvals")?;
+        let deployments = DeploymentsEntity::delete_many()
+            .filter(DeploymentColumn::Id.is_in(ids.to_vec()))
+            .exec(&txn)
+            .await
+            .context("Failed to purge deployments")?;
+
+        txn.commit()
+            .await
+            .context("Failed to commit purge transaction")?;
+
+        Ok(PurgeSummary {
+            deployments: deployments.rows_affected,
+            changesets: changesets.rows_affected,
+            changes: changes.rows_affected,
+            rollbacks: rollbacks.rows_affected,
+            approvals: approvals.rows_affected,
+        })
+    }
+
     pub async fn delete_all(&self) -> Result<u64> {
         Ok(DeploymentsEntity::delete_many()
             .exec(&self.db)
@@ -121,9 +193,75 @@ impl DeploymentRepository {
             ))
     }
 
+    /// Returns successful deployments of the plan created after the given deployment,
+    /// newest first.
+    pub async fn find_successful_after(
+        &self,
+        plan_id: i32,
+        deployment_id: i32,
+    ) -> Result<Vec<DeploymentModel>> {
+        DeploymentsEntity::find()
+            .filter(DeploymentColumn::PlanId.eq(plan_id))
+            .filter(DeploymentColumn::Id.gt(deployment_id))
+            .filter(DeploymentColumn::Status.eq(DeploymentStatus::Success))
+            .order_by(DeploymentColumn::CreatedAt, Order::Desc)
+            .order_by(DeploymentColumn::Id, Order::Desc)
+            .all(&self.db)
+            .await
+            .context(format!(
+                "Failed to find successful deployments after {} for plan {}",
+                deployment_id, plan_id
+            ))
+    }
+
+    pub async fn find_by_tag(&self, plan_id: i32, tag: &str) -> Result<Option<DeploymentModel>> {
+        DeploymentsEntity::find()
+            .filter(DeploymentColumn::PlanId.eq(plan_id))
+            .filter(DeploymentColumn::Tag.eq(tag))
+            .one(&self.db)
+            .await
+            .context(format!(
+                "Failed to find deployment tagged '{}' for plan {}",
+                tag, plan_id
+            ))
+    }
+
+    pub async fn set_tag(&self, id: i32, tag: Option<String>) -> Result<DeploymentModel> {
+        let deployment = self.get_by_id(id).await?;
+
+        let plan_id = deployment.plan_id;
+        let mut active: DeploymentActiveModel = deployment.into();
+        active.tag = Set(tag.clone());
+
+        match active.update(&self.db).await {
+            Ok(_) => {}
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                // Another deployment of the plan took the tag since it was checked
+                let tag = tag.unwrap_or_default();
+                let holder = self
+                    .find_by_tag(plan_id, &tag)
+                    .await?
+                    .context("Tag is in use but its deployment could not be found")?;
+                return Err(TagInUseError {
+                    tag,
+                    deployment_id: holder.id,
+                }
+                .into());
+            }
+            Err(e) => {
+                return Err(e).context(format!("Failed to update tag for deployment {}", id));
+            }
+        }
+
+        self.get_by_id(id)
+            .await
+            .context("Deployment was updated but could not be retrieved")
+    }
+
     pub async fn create(
         &self,
         plan_id: i32,
+        plan_revision_id: Option<i32>,
         cutoff_date: NaiveDateTime,
         payload: String,
         disable_hooks: bool,
@@ -132,6 +270,7 @@ impl DeploymentRepository {
         let active_model = DeploymentActiveModel {
             id: NotSet,
             plan_id: Set(plan_id),
+            plan_revision_id: Set(plan_revision_id),
             cutoff_date: Set(cutoff_date),
             payload: Set(payload),
             disable_hooks: Set(disable_hooks),
@@ -188,4 +327,93 @@ impl DeploymentRepository {
             .await
             .context("Deployment was updated but could not be retrieved")
     }
+
+    /// Records the process running the deployment
+    pub async fn set_owner(
+        &self,
+        id: i32,
+        pid: i32,
+        host: &str,
+        user: &str,
+    ) -> Result<DeploymentModel> {
+        let deployment = self.get_by_id(id).await?;
+
+        let mut active: DeploymentActiveModel = deployment.into();
+        active.set_owner(pid, host.to_string(), user.to_string());
+
+        active
+            .update(&self.db)
+            .await
+            .context(format!("Failed to set owner of deployment {}", id))?;
+
+        self.get_by_id(id)
+            .await
+            .context("Deployment was updated but could not be retrieved")
+    }
+
+    /// Refreshes the heartbeat of a running deployment. Returns false if it is no longer running.
+    pub async fn heartbeat(&self, id: i32) -> Result<bool> {
+        let res = DeploymentsEntity::update_many()
+            .col_expr(
+                DeploymentColumn::HeartbeatAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(DeploymentColumn::Id.eq(id))
+            .filter(DeploymentColumn::Status.eq(DeploymentStatus::Running))
+            .exec(&self.db)
+            .await
+            .context(format!("Failed to refresh heartbeat of deployment {}", id))?;
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Returns running deployments without a heartbeat since `stale_before`.
+    ///
+    /// Deployments started before heartbeats were recorded fall back to their last update.
+    pub async fn find_stale_running(
+        &self,
+        stale_before: NaiveDateTime,
+    ) -> Result<Vec<DeploymentModel>> {
+        DeploymentsEntity::find()
+            .filter(DeploymentColumn::Status.eq(DeploymentStatus::Running))
+            .filter(
+                Condition::any()
+                    .add(DeploymentColumn::HeartbeatAt.lt(stale_before))
+                    .add(
+                        Condition::all()
+                            .add(DeploymentColumn::HeartbeatAt.is_null())
+                            .add(DeploymentColumn::UpdatedAt.lt(stale_before)),
+                    ),
+            )
+            .order_by(DeploymentColumn::Id, Order::Asc)
+            .all(&self.db)
+            .await
+            .context("Failed to find stale running deployments")
+    }
+
+    /// Appends executed hook scripts to the deployment
+    pub async fn add_hook_executions(
+        &self,
+        id: i32,
+        executions: &[HookExecution],
+    ) -> Result<DeploymentModel> {
+        let deployment = self.get_by_id(id).await?;
+        if executions.is_empty() {
+            return Ok(deployment);
+        }
+
+        let mut all = deployment.get_hook_executions()?;
+        all.extend_from_slice(executions);
+
+        let mut active: DeploymentActiveModel = deployment.into();
+        active.hook_executions = Set(Some(serde_json::to_value(all)?));
+
+        active.update(&self.db).await.context(format!(
+            "Failed to record hook executions of deployment {}",
+            id
+        ))?;
+
+        self.get_by_id(id)
+            .await
+            .context("Deployment was updated but could not be retrieved")
+    }
 }
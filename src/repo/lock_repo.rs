@@ -0,0 +1,142 @@
+use crate::{
+    entities::{
+        DeploymentLockActiveModel, DeploymentLockColumn, DeploymentLockModel, DeploymentLocksEntity,
+    },
+    errors::LockHeldError,
+};
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, SqlErr,
+    TransactionTrait,
+    sea_query::Expr,
+};
+
+pub struct LockRepository {
+    db: DatabaseConnection,
+}
+
+impl LockRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Atomically acquires the lock for a plan and its target connection.
+    ///
+    /// Locks of the same plan or target whose heartbeat is older than `stale_before` are taken over.
+    /// Returns a [`LockHeldError`] if a live lock exists.
+    pub async fn acquire(
+        &self,
+        plan_id: i32,
+        target_connection_id: i32,
+        operation: &str,
+        owner_pid: i32,
+        owner_host: &str,
+        stale_before: NaiveDateTime,
+    ) -> Result<DeploymentLockModel> {
+        let conflicting = Condition::any()
+            .add(DeploymentLockColumn::PlanId.eq(plan_id))
+            .add(DeploymentLockColumn::TargetConnectionId.eq(target_connection_id));
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .context("Failed to start lock transaction")?;
+
+        DeploymentLocksEntity::delete_many()
+            .filter(conflicting.clone())
+            .filter(DeploymentLockColumn::HeartbeatAt.lt(stale_before))
+            .exec(&txn)
+            .await
+            .context("Failed to remove stale locks")?;
+
+        let now = chrono::Utc::now().naive_utc();
+        let active_model = DeploymentLockActiveModel {
+            id: NotSet,
+            target_connection_id: Set(target_connection_id),
+            plan_id: Set(plan_id),
+            operation: Set(operation.to_string()),
+            owner_pid: Set(owner_pid),
+            owner_host: Set(owner_host.to_string()),
+            acquired_at: Set(now),
+            heartbeat_at: Set(now),
+        };
+
+        let inserted = match DeploymentLocksEntity::insert(active_model).exec(&txn).await {
+            Ok(res) => res,
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                // PostgreSQL aborts the transaction on the violation, the holder is read after it
+                txn.rollback().await?;
+                let holder = DeploymentLocksEntity::find()
+                    .filter(conflicting)
+                    .one(&self.db)
+                    .await?;
+                let holder = holder.context("Lock is held but its owner could not be found")?;
+                return Err(LockHeldError {
+                    plan_id: holder.plan_id,
+                    target_connection_id: holder.target_connection_id,
+                    operation: holder.operation,
+                    owner_pid: holder.owner_pid,
+                    owner_host: holder.owner_host,
+                    acquired_at: holder.acquired_at,
+                    heartbeat_at: holder.heartbeat_at,
+                }
+                .into());
+            }
+            Err(e) => return Err(e).context("Failed to insert lock"),
+        };
+
+        txn.commit()
+            .await
+            .context("Failed to commit lock transaction")?;
+
+        self.get_by_id(inserted.last_insert_id)
+            .await
+            .context("Lock was acquired but could not be retrieved")
+    }
+
+    pub async fn get_by_id(&self, id: i32) -> Result<DeploymentLockModel> {
+        self.find_by_id(id)
+            .await?
+            .context(format!("Lock with ID {} not found", id))
+    }
+
+    pub async fn find_by_id(&self, id: i32) -> Result<Option<DeploymentLockModel>> {
+        DeploymentLocksEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .context(format!("Failed to find lock by id: {}", id))
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<DeploymentLockModel>> {
+        DeploymentLocksEntity::find()
+            .order_by_asc(DeploymentLockColumn::Id)
+            .all(&self.db)
+            .await
+            .context("Failed to get all locks")
+    }
+
+    /// Refreshes the heartbeat of a lock, returns false if the lock no longer exists
+    pub async fn heartbeat(&self, id: i32) -> Result<bool> {
+        let res = DeploymentLocksEntity::update_many()
+            .col_expr(
+                DeploymentLockColumn::HeartbeatAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(DeploymentLockColumn::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .context(format!("Failed to refresh heartbeat of lock {}", id))?;
+        Ok(res.rows_affected > 0)
+    }
+
+    pub async fn delete(&self, id: i32) -> Result<u64> {
+        let res = DeploymentLocksEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .context(format!("Failed to delete lock {}", id))?;
+        Ok(res.rows_affected)
+    }
+}
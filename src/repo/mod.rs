@@ -1,13 +1,30 @@
+pub mod approval_repo;
+pub mod audit_repo;
 pub mod change_repo;
 pub mod changeset_repo;
 pub mod connection_repo;
 pub mod deployment_repo;
+pub mod lock_repo;
 pub mod plan_repo;
 pub mod rollback_repo;
 
+pub use approval_repo::ApprovalRepository;
+pub use audit_repo::AuditRepository;
 pub use change_repo::ChangeRepository;
 pub use changeset_repo::ChangesetRepository;
 pub use connection_repo::ConnectionRepository;
 pub use deployment_repo::DeploymentRepository;
+pub use lock_repo::LockRepository;
 pub use plan_repo::PlanRepository;
 pub use rollback_repo::RollbackRepository;
+
+use sea_orm::{
+    ExprTrait,
+    sea_query::{Expr, Func, IntoColumnRef, SimpleExpr},
+};
+
+/// Case-insensitive name comparison, `LIKE` is case-sensitive on PostgreSQL and treats
+/// `_` and `%` in names as wildcards.
+pub(crate) fn name_matches(column: impl IntoColumnRef, name: &str) -> SimpleExpr {
+    Expr::expr(Func::lower(Expr::col(column))).eq(Func::lower(Expr::val(name)))
+}
@@ -1,14 +1,19 @@
 use crate::{
-    entities::{PlanActiveModel, PlanColumn, PlanModel, PlansEntity},
-    types::{Hooks, PlanStatus, StringList},
+    entities::{
+        PlanActiveModel, PlanColumn, PlanModel, PlanRevisionActiveModel, PlanRevisionColumn,
+        PlanRevisionModel, PlanRevisionsEntity, PlansEntity,
+    },
+    repo::name_matches,
+    types::{PlanStatus, StringList},
 };
 use anyhow::{Context, Result};
+use sea_orm::PaginatorTrait;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, Condition, DatabaseConnection, EntityTrait, ExprTrait, QueryFilter,
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
 };
-use sea_orm::{PaginatorTrait, sea_query::Expr};
 
 /// Repository for Plan entity operations
 pub struct PlanRepository {
@@ -23,7 +28,7 @@ impl PlanRepository {
     /// Find a plan by its unique name, case-insensitive
     pub async fn find_by_name(&self, name: &str) -> Result<Option<PlanModel>> {
         PlansEntity::find()
-            .filter(Condition::all().add(Expr::col(PlanColumn::Name).like(name)))
+            .filter(Condition::all().add(name_matches(PlanColumn::Name, name)))
             .one(&self.db)
             .await
             .context("Failed to query plan by name")
@@ -54,47 +59,112 @@ impl PlanRepository {
             .context(format!("Failed to query plans with status: {}", status))
     }
 
-    /// Create a new plan
-    pub async fn create(
+    /// Create a new plan with the definition of `plan`, recorded as its first revision
+    pub async fn create(&self, plan: &PlanModel) -> Result<PlanModel> {
+        let name = plan.name.clone();
+        let plan = PlanActiveModel::new(plan.clone());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .context("Failed to start plan transaction")?;
+        let result = PlansEntity::insert(plan)
+            .exec(&txn)
+            .await
+            .context(format!("Failed to insert plan '{}'", name))?;
+        let plan = PlansEntity::find_by_id(result.last_insert_id)
+            .one(&txn)
+            .await?
+            .context("Plan was created but could not be retrieved")?;
+        insert_revision(&txn, &plan, Some(vec![])).await?;
+        txn.commit()
+            .await
+            .context("Failed to commit plan transaction")?;
+
+        Ok(plan)
+    }
+
+    /// Update the name, connections, schemas, rules and hooks of a plan from the given
+    /// model and record a new revision if anything changed. Status and approvals are left
+    /// unchanged.
+    pub async fn update(&self, plan: &PlanModel) -> Result<PlanModel> {
+        let current = self
+            .get_by_id(plan.id)
+            .await
+            .context(format!("Plan with ID {} not found", plan.id))?;
+
+        let mut active: PlanActiveModel = current.clone().into();
+        active.name = Set(plan.name.clone());
+        active.source_connection_id = Set(plan.source_connection_id);
+        active.target_connection_id = Set(plan.target_connection_id);
+        active.schemas = Set(plan.schemas.clone());
+        active.exclude_object_types = Set(plan.exclude_object_types.clone());
+        active.exclude_object_names = Set(plan.exclude_object_names.clone());
+        active.disabled_drop_types = Set(plan.disabled_drop_types.clone());
+        active.disable_all_drops = Set(plan.disable_all_drops);
+        active.fail_fast = Set(plan.fail_fast);
+        active.disable_hooks = Set(plan.disable_hooks);
+        active.hooks = Set(plan.hooks.clone());
+        active.required_approvals = Set(plan.required_approvals);
+
+        self.save_with_revision(current, active).await
+    }
+
+    /// Saves a changed plan and its revision in one transaction
+    async fn save_with_revision(
         &self,
-        name: &str,
-        source_connection_id: i32,
-        target_connection_id: i32,
-        schemas: StringList,
-        exclude_object_types: Option<StringList>,
-        exclude_object_names: Option<StringList>,
-        disabled_drop_types: Option<StringList>,
-        disable_all_drops: bool,
-        fail_fast: bool,
-        disable_hooks: bool,
-        hooks: Option<Hooks>,
+        current: PlanModel,
+        active: PlanActiveModel,
     ) -> Result<PlanModel> {
-        let active_model = PlanActiveModel {
-            id: NotSet,
-            name: Set(name.to_string()),
-            source_connection_id: Set(source_connection_id),
-            target_connection_id: Set(target_connection_id),
-            schemas: Set(schemas),
-            exclude_object_types: Set(exclude_object_types),
-            exclude_object_names: Set(exclude_object_names),
-            status: Set(PlanStatus::default()),
-            created_at: Set(chrono::Utc::now().naive_utc()),
-            disabled_drop_types: Set(disabled_drop_types),
-            disable_all_drops: Set(disable_all_drops),
-            fail_fast: Set(fail_fast),
-            disable_hooks: Set(disable_hooks),
-            hooks: Set(hooks.map(|h| serde_json::to_value(h)).transpose()?),
-            ..Default::default()
-        };
-
-        let result = PlansEntity::insert(active_model)
-            .exec(&self.db)
+        let txn = self
+            .db
+            .begin()
             .await
-            .context(format!("Failed to insert plan '{}'", name))?;
+            .context("Failed to start plan transaction")?;
+        let updated = active
+            .update(&txn)
+            .await
+            .context(format!("Failed to update plan {}", current.id))?;
+        let changed_fields = current.changed_fields(&updated);
+        if !changed_fields.is_empty() {
+            insert_revision(&txn, &updated, Some(changed_fields)).await?;
+        }
+        txn.commit()
+            .await
+            .context("Failed to commit plan transaction")?;
+
+        Ok(updated)
+    }
 
-        self.get_by_id(result.last_insert_id)
+    /// Latest revision of a plan
+    pub async fn find_latest_revision(&self, plan_id: i32) -> Result<Option<PlanRevisionModel>> {
+        PlanRevisionsEntity::find()
+            .filter(PlanRevisionColumn::PlanId.eq(plan_id))
+            .order_by_desc(PlanRevisionColumn::Revision)
+            .one(&self.db)
             .await
-            .context("Plan was created but could not be retrieved")
+            .context(format!(
+                "Failed to query latest revision of plan {}",
+                plan_id
+            ))
+    }
+
+    /// Revisions of a plan, oldest first
+    pub async fn find_revisions(&self, plan_id: i32) -> Result<Vec<PlanRevisionModel>> {
+        PlanRevisionsEntity::find()
+            .filter(PlanRevisionColumn::PlanId.eq(plan_id))
+            .order_by_asc(PlanRevisionColumn::Revision)
+            .all(&self.db)
+            .await
+            .context(format!("Failed to query revisions of plan {}", plan_id))
+    }
+
+    pub async fn find_revision_by_id(&self, id: i32) -> Result<Option<PlanRevisionModel>> {
+        PlanRevisionsEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .context(format!("Failed to query plan revision {}", id))
     }
 
     /// Update a plan's status
@@ -117,6 +187,26 @@ impl PlanRepository {
             .context("Plan was updated but could not be retrieved")
     }
 
+    /// Update the number of approvals a plan's deployments need before apply
+    pub async fn set_required_approvals(
+        &self,
+        id: i32,
+        required_approvals: i32,
+    ) -> Result<PlanModel> {
+        let plan = self
+            .get_by_id(id)
+            .await
+            .context(format!("Plan with ID {} not found", id))?;
+
+        let mut active: PlanActiveModel = plan.clone().into();
+        active.required_approvals = Set(required_approvals);
+
+        self.save_with_revision(plan, active).await.context(format!(
+            "Failed to update required approvals for plan {}",
+            id
+        ))
+    }
+
     /// Delete a plan by ID
     pub async fn delete(&self, id: i32) -> Result<()> {
         let result = PlansEntity::delete_by_id(id)
@@ -179,3 +269,44 @@ impl PlanRepository {
         Ok(count > 0)
     }
 }
+
+/// Records the current definition of a plan as its next revision
+async fn insert_revision<C: ConnectionTrait>(
+    db: &C,
+    plan: &PlanModel,
+    changed_fields: Option<Vec<String>>,
+) -> Result<PlanRevisionModel> {
+    let latest = PlanRevisionsEntity::find()
+        .filter(PlanRevisionColumn::PlanId.eq(plan.id))
+        .order_by_desc(PlanRevisionColumn::Revision)
+        .one(db)
+        .await
+        .context(format!(
+            "Failed to query latest revision of plan {}",
+            plan.id
+        ))?;
+
+    let revision = PlanRevisionActiveModel {
+        id: NotSet,
+        plan_id: Set(plan.id),
+        revision: Set(latest.map(|r| r.revision + 1).unwrap_or(1)),
+        name: Set(plan.name.clone()),
+        source_connection_id: Set(plan.source_connection_id),
+        target_connection_id: Set(plan.target_connection_id),
+        schemas: Set(plan.schemas.clone()),
+        exclude_object_types: Set(plan.exclude_object_types.clone()),
+        exclude_object_names: Set(plan.exclude_object_names.clone()),
+        disabled_drop_types: Set(plan.disabled_drop_types.clone()),
+        disable_all_drops: Set(plan.disable_all_drops),
+        fail_fast: Set(plan.fail_fast),
+        disable_hooks: Set(plan.disable_hooks),
+        hooks: Set(plan.hooks.clone()),
+        required_approvals: Set(plan.required_approvals),
+        changed_fields: Set(changed_fields.map(StringList)),
+        created_at: Set(chrono::Utc::now().naive_utc()),
+    };
+    revision
+        .insert(db)
+        .await
+        .context(format!("Failed to record revision of plan {}", plan.id))
+}
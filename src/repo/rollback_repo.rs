@@ -82,12 +82,18 @@ impl RollbackRepository {
         Ok(rollbacks)
     }
 
-    pub async fn create(&self, change_id: i32, script: String) -> Result<RollbackModel> {
+    pub async fn create(
+        &self,
+        change_id: i32,
+        script: String,
+        operation_id: Option<&str>,
+    ) -> Result<RollbackModel> {
         let active_model = RollbackActiveModel {
             id: NotSet,
             change_id: Set(change_id),
             script: Set(script),
             status: Set(RollbackStatus::default()),
+            operation_id: Set(operation_id.map(|s| s.to_string())),
             ..Default::default()
         };
         let result = RollbacksEntity::insert(active_model)
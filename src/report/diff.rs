@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+
+use similar::TextDiff;
+
+use crate::{
+    entities::{ChangeModel, ChangesetModel},
+    types::ChangesetStatus,
+};
+
+/// One object of a deployment, reduced to what is compared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeployedObject {
+    pub status: ChangesetStatus,
+    /// Scripts of the changes in execution order
+    pub scripts: Vec<String>,
+}
+
+impl DeployedObject {
+    fn from_changeset(changeset: &ChangesetModel, changes: &[ChangeModel]) -> Self {
+        let mut changes: Vec<&ChangeModel> = changes.iter().collect();
+        changes.sort_by_key(|c| c.id);
+        Self {
+            status: changeset.status.clone(),
+            scripts: changes
+                .iter()
+                .map(|c| c.script.trim().to_string())
+                .collect(),
+        }
+    }
+
+    fn script_text(&self) -> String {
+        self.scripts
+            .iter()
+            .map(|s| format!("{}\n", s))
+            .collect::<String>()
+    }
+}
+
+/// An object that differs between two deployments. `a` or `b` is `None` when only one
+/// of the deployments changed the object.
+#[derive(Clone, Debug)]
+pub struct ObjectDifference {
+    pub object_type: String,
+    pub object_owner: String,
+    pub object_name: String,
+    pub a: Option<DeployedObject>,
+    pub b: Option<DeployedObject>,
+}
+
+impl ObjectDifference {
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{} {}.{}",
+            self.object_type, self.object_owner, self.object_name
+        )
+    }
+
+    pub fn scripts_differ(&self) -> bool {
+        matches!((&self.a, &self.b), (Some(a), Some(b)) if a.scripts != b.scripts)
+    }
+
+    pub fn status_differs(&self) -> bool {
+        matches!((&self.a, &self.b), (Some(a), Some(b)) if a.status != b.status)
+    }
+
+    /// Unified diff of the scripts from `a` to `b`, `None` if both ran the same scripts.
+    pub fn script_diff(&self, a_label: &str, b_label: &str) -> Option<String> {
+        let a = self.a.as_ref().map(|o| o.script_text()).unwrap_or_default();
+        let b = self.b.as_ref().map(|o| o.script_text()).unwrap_or_default();
+        if a == b {
+            return None;
+        }
+        Some(
+            TextDiff::from_lines(&a, &b)
+                .unified_diff()
+                .header(a_label, b_label)
+                .to_string(),
+        )
+    }
+}
+
+/// Differences between the changesets of two deployments.
+#[derive(Clone, Debug)]
+pub struct DeploymentDiff {
+    pub a_deployment_id: i32,
+    pub b_deployment_id: i32,
+    /// Objects only in one deployment or with different scripts or status, sorted by
+    /// type, owner and name
+    pub objects: Vec<ObjectDifference>,
+    /// Objects both deployments changed the same way
+    pub identical_count: usize,
+}
+
+impl DeploymentDiff {
+    pub fn build(
+        a_deployment_id: i32,
+        a: Vec<(ChangesetModel, Vec<ChangeModel>)>,
+        b_deployment_id: i32,
+        b: Vec<(ChangesetModel, Vec<ChangeModel>)>,
+    ) -> Self {
+        type Key = (String, String, String);
+        let key = |c: &ChangesetModel| {
+            (
+                c.object_type.clone(),
+                c.object_owner.clone(),
+                c.object_name.clone(),
+            )
+        };
+
+        let mut objects: BTreeMap<Key, (Option<DeployedObject>, Option<DeployedObject>)> =
+            BTreeMap::new();
+        for (changeset, changes) in &a {
+            objects.entry(key(changeset)).or_default().0 =
+                Some(DeployedObject::from_changeset(changeset, changes));
+        }
+        for (changeset, changes) in &b {
+            objects.entry(key(changeset)).or_default().1 =
+                Some(DeployedObject::from_changeset(changeset, changes));
+        }
+
+        let mut identical_count = 0;
+        let objects = objects
+            .into_iter()
+            .filter_map(|((object_type, object_owner, object_name), (a, b))| {
+                if a.is_some() && a == b {
+                    identical_count += 1;
+                    return None;
+                }
+                Some(ObjectDifference {
+                    object_type,
+                    object_owner,
+                    object_name,
+                    a,
+                    b,
+                })
+            })
+            .collect();
+
+        Self {
+            a_deployment_id,
+            b_deployment_id,
+            objects,
+            identical_count,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changeset(
+        id: i32,
+        name: &str,
+        status: ChangesetStatus,
+        scripts: &[&str],
+    ) -> (ChangesetModel, Vec<ChangeModel>) {
+        let changeset = ChangesetModel {
+            id,
+            object_type: "TABLE".to_string(),
+            object_owner: "HR".to_string(),
+            object_name: name.to_string(),
+            status,
+            ..Default::default()
+        };
+        let changes = scripts
+            .iter()
+            .enumerate()
+            .map(|(i, script)| ChangeModel {
+                id: id * 10 + i as i32,
+                changeset_id: id,
+                script: script.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        (changeset, changes)
+    }
+
+    #[test]
+    fn test_deployment_diff() {
+        let a = vec![
+            changeset(1, "SAME", ChangesetStatus::Success, &["CREATE TABLE SAME"]),
+            changeset(2, "SCRIPT", ChangesetStatus::Success, &["ALTER TABLE A"]),
+            changeset(3, "STATUS", ChangesetStatus::Success, &["ALTER TABLE S"]),
+            changeset(4, "ONLY_A", ChangesetStatus::Success, &["CREATE TABLE A"]),
+        ];
+        let b = vec![
+            changeset(
+                5,
+                "SAME",
+                ChangesetStatus::Success,
+                &["CREATE TABLE SAME\n"],
+            ),
+            changeset(6, "SCRIPT", ChangesetStatus::Success, &["ALTER TABLE B"]),
+            changeset(7, "STATUS", ChangesetStatus::Error, &["ALTER TABLE S"]),
+            changeset(8, "ONLY_B", ChangesetStatus::Idle, &[]),
+        ];
+
+        let diff = DeploymentDiff::build(1, a, 2, b);
+
+        assert_eq!(diff.identical_count, 1);
+        let names: Vec<&str> = diff
+            .objects
+            .iter()
+            .map(|o| o.object_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["ONLY_A", "ONLY_B", "SCRIPT", "STATUS"]);
+
+        assert!(diff.objects[0].b.is_none());
+        assert!(diff.objects[1].a.is_none());
+        assert!(diff.objects[2].scripts_differ());
+        assert!(!diff.objects[2].status_differs());
+        assert!(!diff.objects[3].scripts_differ());
+        assert!(diff.objects[3].status_differs());
+
+        let script_diff = diff.objects[2].script_diff("a", "b").unwrap();
+        assert!(script_diff.contains("-ALTER TABLE A\n+ALTER TABLE B\n"));
+        assert!(diff.objects[3].script_diff("a", "b").is_none());
+    }
+}
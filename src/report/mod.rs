@@ -0,0 +1,7 @@
+mod diff;
+mod model;
+mod render;
+
+pub use diff::{DeployedObject, DeploymentDiff, ObjectDifference};
+pub use model::{ChangeReport, DeploymentReport, HookReport, ObjectReport, RollbackReport};
+pub use render::render;
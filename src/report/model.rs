@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use serde::{Serialize, Serializer};
+
+use crate::{
+    entities::{ChangeModel, ChangesetModel, DeploymentModel, RollbackModel},
+    types::{
+        ChangeStatus, ChangesetStatus, DeploymentStatus, HookExecution, RollbackStatus,
+        VerificationStatus,
+    },
+    utils::format_duration,
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Everything known about a single deployment, ready to be rendered.
+#[derive(Clone, Debug, Serialize)]
+pub struct DeploymentReport {
+    pub deployment_id: i32,
+    pub plan_name: String,
+    pub tag: Option<String>,
+    #[serde(serialize_with = "display")]
+    pub status: DeploymentStatus,
+    #[serde(serialize_with = "datetime")]
+    pub cutoff_date: NaiveDateTime,
+    #[serde(serialize_with = "datetime")]
+    pub created_at: NaiveDateTime,
+    #[serde(serialize_with = "opt_datetime")]
+    pub started_at: Option<NaiveDateTime>,
+    #[serde(serialize_with = "opt_datetime")]
+    pub ended_at: Option<NaiveDateTime>,
+    pub duration: Option<String>,
+    /// Plan settings at the time the deployment was prepared
+    pub plan_settings: serde_json::Value,
+    pub errors: Vec<String>,
+    pub change_count: usize,
+    pub failed_change_count: usize,
+    pub objects: Vec<ObjectReport>,
+    pub hooks: Vec<HookReport>,
+    pub rollbacks: Vec<RollbackReport>,
+    #[serde(serialize_with = "datetime")]
+    pub generated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ObjectReport {
+    pub object_type: String,
+    pub object_owner: String,
+    pub object_name: String,
+    #[serde(serialize_with = "display")]
+    pub status: ChangesetStatus,
+    #[serde(serialize_with = "opt_display")]
+    pub verification: Option<VerificationStatus>,
+    #[serde(serialize_with = "opt_datetime")]
+    pub started_at: Option<NaiveDateTime>,
+    #[serde(serialize_with = "opt_datetime")]
+    pub ended_at: Option<NaiveDateTime>,
+    pub duration: Option<String>,
+    pub errors: Vec<String>,
+    pub changes: Vec<ChangeReport>,
+}
+
+impl ObjectReport {
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{} {}.{}",
+            self.object_type, self.object_owner, self.object_name
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ChangeReport {
+    pub id: i32,
+    #[serde(serialize_with = "display")]
+    pub status: ChangeStatus,
+    pub script: String,
+    pub rollback_script: String,
+    pub error: Option<String>,
+    pub duration: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HookReport {
+    pub stage: String,
+    pub script: String,
+    #[serde(serialize_with = "datetime")]
+    pub started_at: NaiveDateTime,
+    pub duration: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RollbackReport {
+    pub id: i32,
+    pub change_id: i32,
+    /// Qualified name of the rolled back object
+    pub object: String,
+    pub operation_id: Option<String>,
+    #[serde(serialize_with = "display")]
+    pub status: RollbackStatus,
+    pub script: String,
+    pub error: Option<String>,
+    #[serde(serialize_with = "datetime")]
+    pub created_at: NaiveDateTime,
+}
+
+impl DeploymentReport {
+    pub fn build(
+        deployment: &DeploymentModel,
+        plan_name: &str,
+        changesets: Vec<(ChangesetModel, Vec<ChangeModel>)>,
+        rollbacks: Vec<RollbackModel>,
+    ) -> Result<Self> {
+        // Deployments store the plan snapshot as JSON, older ones may not
+        let plan_settings = serde_json::from_str(&deployment.payload)
+            .unwrap_or_else(|_| serde_json::Value::String(deployment.payload.clone()));
+
+        let mut object_names = HashMap::new();
+        let objects: Vec<ObjectReport> = changesets
+            .into_iter()
+            .map(|(changeset, changes)| {
+                let object = ObjectReport {
+                    object_type: changeset.object_type,
+                    object_owner: changeset.object_owner,
+                    object_name: changeset.object_name,
+                    status: changeset.status,
+                    verification: changeset.verification_status,
+                    started_at: changeset.started_at,
+                    ended_at: changeset.ended_at,
+                    duration: duration(changeset.started_at, changeset.ended_at),
+                    errors: changeset.errors.map(|e| e.0).unwrap_or_default(),
+                    changes: changes
+                        .into_iter()
+                        .map(|change| ChangeReport {
+                            id: change.id,
+                            status: change.status,
+                            duration: duration(change.started_at, change.ended_at),
+                            script: change.script,
+                            rollback_script: change.rollback_script,
+                            error: change.error,
+                        })
+                        .collect(),
+                };
+                for change in &object.changes {
+                    object_names.insert(change.id, object.qualified_name());
+                }
+                object
+            })
+            .collect();
+
+        let changes = objects.iter().flat_map(|o| o.changes.iter());
+        let change_count = changes.clone().count();
+        let failed_change_count = changes.filter(|c| c.status == ChangeStatus::Error).count();
+
+        let hooks = deployment
+            .get_hook_executions()?
+            .into_iter()
+            .map(|h: HookExecution| HookReport {
+                duration: duration(Some(h.started_at), Some(h.ended_at)),
+                stage: h.stage,
+                script: h.script,
+                started_at: h.started_at,
+                error: h.error,
+            })
+            .collect();
+
+        let rollbacks = rollbacks
+            .into_iter()
+            .map(|r| RollbackReport {
+                id: r.id,
+                change_id: r.change_id,
+                object: object_names.get(&r.change_id).cloned().unwrap_or_default(),
+                operation_id: r.operation_id,
+                status: r.status,
+                script: r.script,
+                error: r.error,
+                created_at: r.created_at,
+            })
+            .collect();
+
+        Ok(Self {
+            deployment_id: deployment.id,
+            plan_name: plan_name.to_string(),
+            tag: deployment.tag.clone(),
+            status: deployment.status.clone(),
+            cutoff_date: deployment.cutoff_date,
+            created_at: deployment.created_at,
+            started_at: deployment.started_at,
+            ended_at: deployment.ended_at,
+            duration: duration(deployment.started_at, deployment.ended_at),
+            plan_settings,
+            errors: deployment
+                .errors
+                .as_ref()
+                .map(|e| e.0.clone())
+                .unwrap_or_default(),
+            change_count,
+            failed_change_count,
+            objects,
+            hooks,
+            rollbacks,
+            generated_at: chrono::Utc::now().naive_utc(),
+        })
+    }
+}
+
+fn duration(started_at: Option<NaiveDateTime>, ended_at: Option<NaiveDateTime>) -> Option<String> {
+    started_at
+        .zip(ended_at)
+        .map(|(s, e)| format_duration(Some(s.and_utc()), Some(e.and_utc())))
+}
+
+pub(crate) fn format_datetime(value: &NaiveDateTime) -> String {
+    value.format(DATE_FORMAT).to_string()
+}
+
+fn display<T: std::fmt::Display, S: Serializer>(value: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(value)
+}
+
+fn opt_display<T: std::fmt::Display, S: Serializer>(
+    value: &Option<T>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => s.collect_str(v),
+        None => s.serialize_none(),
+    }
+}
+
+fn datetime<S: Serializer>(value: &NaiveDateTime, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format_datetime(value))
+}
+
+fn opt_datetime<S: Serializer>(value: &Option<NaiveDateTime>, s: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => s.serialize_str(&format_datetime(v)),
+        None => s.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, min: u32, sec: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(hour, min, sec)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_build_report() {
+        let hooks = vec![HookExecution {
+            stage: "pre_apply_deployment".to_string(),
+            script: "select 1 from dual".to_string(),
+            started_at: at(10, 0, 0),
+            ended_at: at(10, 0, 1),
+            error: None,
+        }];
+        let deployment = DeploymentModel {
+            id: 7,
+            payload: r#"{"name":"demo","fail_fast":true}"#.to_string(),
+            status: DeploymentStatus::Error,
+            started_at: Some(at(10, 0, 0)),
+            ended_at: Some(at(10, 1, 30)),
+            hook_executions: Some(serde_json::to_value(&hooks).unwrap()),
+            ..Default::default()
+        };
+        let changeset = ChangesetModel {
+            id: 1,
+            object_type: "TABLE".to_string(),
+            object_owner: "HR".to_string(),
+            object_name: "EMPLOYEES".to_string(),
+            status: ChangesetStatus::Error,
+            ..Default::default()
+        };
+        let changes = vec![
+            ChangeModel {
+                id: 11,
+                status: ChangeStatus::Success,
+                ..Default::default()
+            },
+            ChangeModel {
+                id: 12,
+                status: ChangeStatus::Error,
+                error: Some("ORA-00942".to_string()),
+                ..Default::default()
+            },
+        ];
+        let rollbacks = vec![RollbackModel {
+            id: 3,
+            change_id: 11,
+            ..Default::default()
+        }];
+
+        let report =
+            DeploymentReport::build(&deployment, "demo", vec![(changeset, changes)], rollbacks)
+                .unwrap();
+
+        assert_eq!(report.plan_settings["fail_fast"], true);
+        assert_eq!(report.change_count, 2);
+        assert_eq!(report.failed_change_count, 1);
+        assert_eq!(report.objects[0].qualified_name(), "TABLE HR.EMPLOYEES");
+        assert_eq!(report.hooks.len(), 1);
+        assert_eq!(report.rollbacks[0].object, "TABLE HR.EMPLOYEES");
+        assert!(report.duration.is_some());
+    }
+
+    #[test]
+    fn test_build_report_with_plain_payload() {
+        let deployment = DeploymentModel {
+            payload: "not json".to_string(),
+            ..Default::default()
+        };
+
+        let report = DeploymentReport::build(&deployment, "demo", vec![], vec![]).unwrap();
+
+        assert_eq!(report.plan_settings, serde_json::json!("not json"));
+        assert!(report.objects.is_empty());
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::{Context as _, Result};
+use colored::Colorize;
+use rust_embed::RustEmbed;
+use tabled::{
+    Table, Tabled,
+    settings::{Alignment, Modify, Style, object::Rows},
+};
+use tera::{Context, Tera};
+
+use crate::{report::DeploymentReport, types::ReportFormat};
+
+#[derive(RustEmbed)]
+#[folder = "src/assets/report/"]
+struct Templates;
+
+/// Renders the report in the given format.
+pub fn render(report: &DeploymentReport, format: ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Terminal => Ok(render_terminal(report)),
+        ReportFormat::Markdown => render_template(report, "report.md.jinja", false),
+        ReportFormat::Html => render_template(report, "report.html.jinja", true),
+        ReportFormat::Json => {
+            serde_json::to_string_pretty(report).context("Failed to serialize report")
+        }
+    }
+}
+
+fn render_template(report: &DeploymentReport, name: &str, autoescape: bool) -> Result<String> {
+    let file = Templates::get(name)
+        .ok_or_else(|| anyhow::anyhow!("Failed to find report template: {}", name))?;
+    let template =
+        std::str::from_utf8(file.data.as_ref()).context("Failed to parse template as UTF-8")?;
+
+    let mut ctx = Context::new();
+    ctx.insert("report", report);
+    ctx.insert(
+        "plan_settings",
+        &serde_json::to_string_pretty(&report.plan_settings)?,
+    );
+
+    Tera::one_off(template, &ctx, autoescape).context(format!("Failed to render {}", name))
+}
+
+#[derive(Tabled)]
+struct KeyValueRow {
+    #[tabled(rename = "Attribute")]
+    key: String,
+
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+#[derive(Tabled)]
+struct ObjectRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "Object")]
+    object: String,
+
+    #[tabled(rename = "Status")]
+    status: String,
+
+    #[tabled(rename = "Verification")]
+    verification: String,
+
+    #[tabled(rename = "Duration")]
+    duration: String,
+
+    #[tabled(rename = "Changes")]
+    changes: String,
+}
+
+#[derive(Tabled)]
+struct HookRow {
+    #[tabled(rename = "#")]
+    index: String,
+
+    #[tabled(rename = "Stage")]
+    stage: String,
+
+    #[tabled(rename = "Started At")]
+    started_at: String,
+
+    #[tabled(rename = "Duration")]
+    duration: String,
+
+    #[tabled(rename = "Result")]
+    result: String,
+
+    #[tabled(rename = "Script")]
+    script: String,
+}
+
+#[derive(Tabled)]
+struct RollbackRow {
+    #[tabled(rename = "ID")]
+    id: String,
+
+    #[tabled(rename = "Object")]
+    object: String,
+
+    #[tabled(rename = "Operation")]
+    operation: String,
+
+    #[tabled(rename = "Status")]
+    status: String,
+
+    #[tabled(rename = "Error")]
+    error: String,
+}
+
+fn table<T: Tabled>(rows: Vec<T>) -> String {
+    Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()))
+        .to_string()
+}
+
+fn first_line(script: &str) -> String {
+    let line = script.trim().lines().next().unwrap_or_default().trim();
+    if script.trim().lines().count() > 1 {
+        format!("{}...", line)
+    } else {
+        line.to_string()
+    }
+}
+
+fn or_dash(value: Option<&str>) -> String {
+    value.unwrap_or("-").to_string()
+}
+
+fn render_terminal(report: &DeploymentReport) -> String {
+    let mut out = Vec::new();
+
+    out.push(
+        format!(
+            "=== Deployment {} of plan '{}' ===",
+            report.deployment_id, report.plan_name
+        )
+        .blue()
+        .to_string(),
+    );
+    let mut summary = vec![
+        ("Status", report.status.to_colored_string()),
+        ("Tag", or_dash(report.tag.as_deref())),
+        (
+            "Cutoff Date",
+            crate::report::model::format_datetime(&report.cutoff_date),
+        ),
+        (
+            "Started At",
+            report
+                .started_at
+                .as_ref()
+                .map(crate::report::model::format_datetime)
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "Ended At",
+            report
+                .ended_at
+                .as_ref()
+                .map(crate::report::model::format_datetime)
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        ("Duration", or_dash(report.duration.as_deref())),
+        ("Objects", report.objects.len().to_string()),
+        (
+            "Changes",
+            format!(
+                "{} ({} failed)",
+                report.change_count, report.failed_change_count
+            ),
+        ),
+    ];
+    if let Some(settings) = report.plan_settings.as_object() {
+        for key in ["schemas", "fail_fast", "disable_hooks", "disable_all_drops"] {
+            if let Some(value) = settings.get(key) {
+                summary.push((key, value.to_string()));
+            }
+        }
+    }
+    out.push(table(
+        summary
+            .into_iter()
+            .map(|(key, value)| KeyValueRow {
+                key: key.to_string(),
+                value,
+            })
+            .collect(),
+    ));
+
+    if !report.errors.is_empty() {
+        out.push("=== Errors ===".red().to_string());
+        for error in &report.errors {
+            out.push(format!("  - {}", error));
+        }
+    }
+
+    out.push("=== Objects ===".blue().to_string());
+    if report.objects.is_empty() {
+        out.push("No objects were changed".to_string());
+    } else {
+        out.push(table(
+            report
+                .objects
+                .iter()
+                .enumerate()
+                .map(|(i, object)| ObjectRow {
+                    index: (i + 1).to_string().bright_black().to_string(),
+                    object: object.qualified_name(),
+                    status: object.status.to_colored_string(),
+                    verification: object
+                        .verification
+                        .as_ref()
+                        .map(|v| v.to_colored_string())
+                        .unwrap_or_else(|| "-".bright_black().to_string()),
+                    duration: or_dash(object.duration.as_deref()),
+                    changes: object.changes.len().to_string(),
+                })
+                .collect(),
+        ));
+        for object in &report.objects {
+            for change in object.changes.iter().filter(|c| c.error.is_some()) {
+                out.push(format!(
+                    "  {} change {}: {}",
+                    object.qualified_name(),
+                    change.id,
+                    change.error.as_deref().unwrap_or_default().red()
+                ));
+            }
+        }
+    }
+
+    out.push("=== Hooks ===".blue().to_string());
+    if report.hooks.is_empty() {
+        out.push("No hooks were executed".to_string());
+    } else {
+        out.push(table(
+            report
+                .hooks
+                .iter()
+                .enumerate()
+                .map(|(i, hook)| HookRow {
+                    index: (i + 1).to_string().bright_black().to_string(),
+                    stage: hook.stage.clone(),
+                    started_at: crate::report::model::format_datetime(&hook.started_at),
+                    duration: or_dash(hook.duration.as_deref()),
+                    result: match &hook.error {
+                        Some(error) => error.red().to_string(),
+                        None => "OK".green().bold().to_string(),
+                    },
+                    script: first_line(&hook.script),
+                })
+                .collect(),
+        ));
+    }
+
+    if !report.rollbacks.is_empty() {
+        out.push("=== Rollbacks ===".blue().to_string());
+        out.push(table(
+            report
+                .rollbacks
+                .iter()
+                .map(|rollback| RollbackRow {
+                    id: rollback.id.to_string(),
+                    object: rollback.object.clone(),
+                    operation: or_dash(rollback.operation_id.as_deref()),
+                    status: rollback.status.to_colored_string(),
+                    error: or_dash(rollback.error.as_deref()),
+                })
+                .collect(),
+        ));
+    }
+
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{ChangeModel, ChangesetModel, DeploymentModel},
+        types::ChangesetStatus,
+    };
+
+    fn report() -> DeploymentReport {
+        let deployment = DeploymentModel {
+            id: 3,
+            payload: r#"{"name":"demo","schemas":["HR"]}"#.to_string(),
+            errors: Some(vec!["<boom>".to_string()].into()),
+            ..Default::default()
+        };
+        let changeset = ChangesetModel {
+            object_type: "VIEW".to_string(),
+            object_owner: "HR".to_string(),
+            object_name: "V_EMP".to_string(),
+            status: ChangesetStatus::Success,
+            ..Default::default()
+        };
+        let change = ChangeModel {
+            id: 1,
+            script: "create or replace view v_emp as select 1 from dual".to_string(),
+            ..Default::default()
+        };
+        DeploymentReport::build(&deployment, "demo", vec![(changeset, vec![change])], vec![])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let content = render(&report(), ReportFormat::Markdown).unwrap();
+
+        assert!(content.contains("VIEW HR.V_EMP"));
+        assert!(content.contains("create or replace view v_emp"));
+        assert!(content.contains("\"schemas\""));
+    }
+
+    #[test]
+    fn test_render_html_escapes_content() {
+        let content = render(&report(), ReportFormat::Html).unwrap();
+
+        assert!(content.starts_with("<!DOCTYPE html>"));
+        assert!(content.contains("&lt;boom&gt;"));
+        assert!(!content.contains("<boom>"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let content = render(&report(), ReportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(value["deployment_id"], 3);
+        assert_eq!(value["objects"][0]["status"], "SUCCESS");
+        assert_eq!(value["plan_settings"]["name"], "demo");
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use tracing::warn;
+
+use crate::{
+    entities::AuditEventModel,
+    repo::AuditRepository,
+    types::{AuditOperation, AuditOrigin, AuditOutcome},
+};
+
+/// Records who ran prepare, apply, rollback and reset operations
+pub struct AuditService {
+    repo: Arc<AuditRepository>,
+    origin: AuditOrigin,
+}
+
+impl AuditService {
+    pub fn new(repo: Arc<AuditRepository>, origin: AuditOrigin) -> Self {
+        Self { repo, origin }
+    }
+
+    /// Records the start of an operation and returns the event ID. Dry runs change nothing
+    /// and are not recorded.
+    pub async fn start(
+        &self,
+        operation: AuditOperation,
+        plan_id: Option<i32>,
+        deployment_id: Option<i32>,
+        dry_run: bool,
+    ) -> Result<Option<i32>> {
+        if dry_run {
+            return Ok(None);
+        }
+        let event = self
+            .repo
+            .create(operation, plan_id, deployment_id, &self.origin)
+            .await?;
+        Ok(Some(event.id))
+    }
+
+    /// Records the outcome of a started operation. The operation already ran, so failing to
+    /// record it is only logged.
+    pub async fn finish<T>(
+        &self,
+        event_id: Option<i32>,
+        result: &Result<T>,
+        deployment_id: Option<i32>,
+    ) {
+        let Some(event_id) = event_id else {
+            return;
+        };
+        let (outcome, error) = match result {
+            Ok(_) => (AuditOutcome::Success, None),
+            Err(e) => (AuditOutcome::Error, Some(format!("{:#}", e))),
+        };
+        if let Err(e) = self
+            .repo
+            .finish(event_id, outcome, deployment_id, error)
+            .await
+        {
+            warn!(
+                "Failed to record the outcome of audit event {}: {:#}",
+                event_id, e
+            );
+        }
+    }
+
+    pub async fn find(
+        &self,
+        since: Option<NaiveDateTime>,
+        plan_id: Option<i32>,
+        limit: Option<u64>,
+    ) -> Result<Vec<AuditEventModel>> {
+        self.repo.find(since, plan_id, limit).await
+    }
+}
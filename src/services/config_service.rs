@@ -0,0 +1,317 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::{Context, Result, anyhow, ensure};
+
+use crate::{
+    declarative::{ConfigChange, ConfigDiff, DesiredConnection, DesiredPlan, LeafConfig},
+    services::{ConnectionService, PlanService, PlanSpec, ensure_required_approvals},
+    types::{ConnectionKind, PlanStatus},
+    utils::{is_secret_reference, resolve_secret},
+};
+
+/// Reconciles connections and plans with `leaf.yaml`/`leaf.toml` config files
+pub struct ConfigService {
+    plan_service: Arc<PlanService>,
+    connection_service: Arc<ConnectionService>,
+}
+
+impl ConfigService {
+    pub fn new(plan_service: Arc<PlanService>, connection_service: Arc<ConnectionService>) -> Self {
+        Self {
+            plan_service,
+            connection_service,
+        }
+    }
+
+    /// Changes that bring the repository to the state of the config, relative paths are
+    /// resolved from `base_dir`. Connections and plans missing from the config are only
+    /// deleted with `prune`.
+    pub async fn diff(
+        &self,
+        config: &LeafConfig,
+        base_dir: &Path,
+        prune: bool,
+    ) -> Result<ConfigDiff> {
+        let desired = config.resolve(base_dir)?;
+        // Passwords are compared as stored, a reference of the file matches the same
+        // reference and never an encrypted password
+        let connections = self.connection_service.get_all().await?;
+        let plans = self.plan_service.get_all().await?;
+
+        ConfigDiff::build(&connections, &plans, &desired, prune)
+    }
+
+    /// Applies the changes of a diff in order. Every change is validated against the
+    /// repository before the first one is written, Oracle connections whose settings change
+    /// must pass the connection test.
+    pub async fn apply(&self, diff: &ConfigDiff) -> Result<()> {
+        self.validate(diff).await?;
+        for change in &diff.changes {
+            self.apply_change(change).await?;
+        }
+        Ok(())
+    }
+
+    /// Checks each change against the repository as the previous changes leave it
+    async fn validate(&self, diff: &ConfigDiff) -> Result<()> {
+        let mut connections: HashMap<String, ConnectionKind> = self
+            .connection_service
+            .get_all()
+            .await?
+            .into_iter()
+            .map(|c| (c.name.to_lowercase(), c.kind))
+            .collect();
+        let plans = self.plan_service.get_all().await?;
+        let running: HashSet<String> = plans
+            .iter()
+            .filter(|p| p.status == PlanStatus::Running)
+            .map(|p| p.name.to_lowercase())
+            .collect();
+        let mut plans: HashSet<String> = plans.iter().map(|p| p.name.to_lowercase()).collect();
+
+        for change in &diff.changes {
+            match change {
+                ConfigChange::CreateConnection(connection) => {
+                    self.validate_connection(connection, false)
+                        .await
+                        .context(format!("Failed to create connection '{}'", connection.name))?;
+                    ensure!(
+                        connections
+                            .insert(connection.name.to_lowercase(), connection.kind.clone())
+                            .is_none(),
+                        "Connection with name '{}' already exists",
+                        connection.name
+                    );
+                }
+                ConfigChange::UpdateConnection { connection, .. } => {
+                    ensure!(
+                        connections.contains_key(&connection.name.to_lowercase()),
+                        "Connection '{}' not found",
+                        connection.name
+                    );
+                    self.validate_connection(connection, change.changes_definition())
+                        .await
+                        .context(format!("Failed to update connection '{}'", connection.name))?;
+                }
+                ConfigChange::DeleteConnection(name) => {
+                    ensure!(
+                        connections.remove(&name.to_lowercase()).is_some(),
+                        "Connection '{}' not found",
+                        name
+                    );
+                }
+                ConfigChange::CreatePlan(plan) => {
+                    validate_plan(plan, &connections)
+                        .context(format!("Failed to create plan '{}'", plan.name))?;
+                    ensure!(
+                        plans.insert(plan.name.to_lowercase()),
+                        "Plan with name '{}' already exists",
+                        plan.name
+                    );
+                }
+                ConfigChange::UpdatePlan { plan, .. } => {
+                    let name = plan.name.to_lowercase();
+                    ensure!(plans.contains(&name), "Plan '{}' not found", plan.name);
+                    ensure!(
+                        !running.contains(&name),
+                        "Plan '{}' is running and can not be updated",
+                        plan.name
+                    );
+                    validate_plan(plan, &connections)
+                        .context(format!("Failed to update plan '{}'", plan.name))?;
+                }
+                ConfigChange::DeletePlan(name) => {
+                    let lower = name.to_lowercase();
+                    ensure!(
+                        !running.contains(&lower),
+                        "Plan '{}' is running and can not be deleted",
+                        name
+                    );
+                    ensure!(plans.remove(&lower), "Plan '{}' not found", name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Oracle connections are tested with `test`, directory connections must exist
+    async fn validate_connection(&self, connection: &DesiredConnection, test: bool) -> Result<()> {
+        ensure_required_approvals(connection.required_approvals)?;
+        match connection.kind {
+            ConnectionKind::Oracle => {
+                let password = if is_secret_reference(&connection.password) {
+                    resolve_secret(&connection.password)?
+                } else {
+                    connection.password.clone()
+                };
+                if test {
+                    self.connection_service
+                        .test(
+                            &connection.username,
+                            &password,
+                            &connection.connection_string,
+                        )
+                        .await?;
+                }
+            }
+            ConnectionKind::Directory => {
+                ensure!(
+                    Path::new(&connection.connection_string).is_dir(),
+                    "Directory '{}' does not exist",
+                    connection.connection_string
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn apply_change(&self, change: &ConfigChange) -> Result<()> {
+        match change {
+            ConfigChange::CreateConnection(connection) => {
+                self.create_connection(connection)
+                    .await
+                    .context(format!("Failed to create connection '{}'", connection.name))?;
+            }
+            ConfigChange::UpdateConnection { connection, .. } => {
+                if change.changes_definition() {
+                    self.update_connection(connection)
+                        .await
+                        .context(format!("Failed to update connection '{}'", connection.name))?;
+                } else {
+                    self.connection_service
+                        .set_required_approvals(&connection.name, connection.required_approvals)
+                        .await
+                        .context(format!("Failed to update connection '{}'", connection.name))?;
+                }
+            }
+            ConfigChange::DeleteConnection(name) => {
+                self.connection_service.delete_by_name(name).await?;
+            }
+            ConfigChange::CreatePlan(plan) => {
+                self.plan_service.create(&plan_spec(plan)).await?;
+            }
+            ConfigChange::UpdatePlan { plan, .. } => {
+                if change.changes_definition() {
+                    self.plan_service.update(&plan_spec(plan)).await?;
+                } else {
+                    self.plan_service
+                        .set_required_approvals(&plan.name, plan.required_approvals)
+                        .await
+                        .context(format!("Failed to update plan '{}'", plan.name))?;
+                }
+            }
+            ConfigChange::DeletePlan(name) => {
+                self.plan_service.delete_by_name(name).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn create_connection(&self, connection: &DesiredConnection) -> Result<()> {
+        match connection.kind {
+            ConnectionKind::Oracle => {
+                self.connection_service
+                    .create(
+                        &connection.name,
+                        &connection.username,
+                        &connection.password,
+                        &connection.connection_string,
+                        connection.required_approvals,
+                    )
+                    .await?;
+            }
+            ConnectionKind::Directory => {
+                self.connection_service
+                    .create_directory(
+                        &connection.name,
+                        Path::new(&connection.connection_string),
+                        connection.required_approvals,
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn update_connection(&self, connection: &DesiredConnection) -> Result<()> {
+        match connection.kind {
+            ConnectionKind::Oracle => {
+                self.connection_service
+                    .update(
+                        &connection.name,
+                        &connection.username,
+                        &connection.password,
+                        &connection.connection_string,
+                        connection.required_approvals,
+                    )
+                    .await?;
+            }
+            ConnectionKind::Directory => {
+                self.connection_service
+                    .update_directory(
+                        &connection.name,
+                        Path::new(&connection.connection_string),
+                        connection.required_approvals,
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Describes the connections and plans of the repository, passwords as references
+    pub async fn export(&self) -> Result<LeafConfig> {
+        let connections = self.connection_service.get_all().await?;
+        let plans = self.plan_service.get_all().await?;
+        LeafConfig::export(&connections, &plans)
+    }
+}
+
+fn plan_spec(plan: &DesiredPlan) -> PlanSpec {
+    PlanSpec {
+        name: plan.name.clone(),
+        source: plan.source.clone(),
+        target: plan.target.clone(),
+        schemas: plan.schemas.clone(),
+        exclude_object_types: plan.exclude_object_types.clone(),
+        exclude_object_names: plan.exclude_object_names.clone(),
+        disabled_drop_types: plan.disabled_drop_types.clone(),
+        disable_all_drops: plan.disable_all_drops,
+        fail_fast: plan.fail_fast,
+        disable_hooks: plan.disable_hooks,
+        hooks: plan.hooks.clone(),
+        required_approvals: plan.required_approvals,
+    }
+}
+
+/// Validates a plan like [`PlanService::create`], against the connections the repository
+/// has once the previous changes are applied
+fn validate_plan(plan: &DesiredPlan, connections: &HashMap<String, ConnectionKind>) -> Result<()> {
+    ensure_required_approvals(plan.required_approvals)?;
+    connections
+        .get(&plan.source.to_lowercase())
+        .ok_or_else(|| anyhow!("Source connection '{}' not found", plan.source))?;
+    let target = connections
+        .get(&plan.target.to_lowercase())
+        .ok_or_else(|| anyhow!("Target connection '{}' not found", plan.target))?;
+    ensure!(
+        *target == ConnectionKind::Oracle,
+        "Target connection '{}' is a {} connection, only Oracle connections can be deployed to",
+        plan.target,
+        target
+    );
+    ensure!(
+        !plan.source.eq_ignore_ascii_case(&plan.target),
+        "Source and target connections cannot be the same"
+    );
+    ensure!(
+        !plan.schemas.is_empty(),
+        "At least one schema must be specified"
+    );
+    Ok(())
+}
@@ -1,17 +1,67 @@
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use crate::delta::{ObjectComparison, compare_objects};
 use crate::entities::ConnectionModel;
 use crate::oracle::OracleClient;
-use crate::repo::ConnectionRepository;
-use anyhow::{Context, Result, ensure};
+use crate::repo::{ConnectionRepository, PlanRepository};
+use crate::services::ensure_required_approvals;
+use crate::snapshot::DirectorySource;
+use crate::source::SourceClient;
+use crate::types::ConnectionKind;
+use crate::utils::{
+    ConnectionSide, ProgressEvent, ProgressReporter, SecretKey, is_secret_reference,
+    pending_key_file, resolve_secret,
+};
+use anyhow::{Context, Result, anyhow, ensure};
+
+/// Objects to compare with [`ConnectionService::compare`].
+#[derive(Debug, Clone, Default)]
+pub struct CompareOptions {
+    pub schemas: Vec<String>,
+    /// Object types to compare, all types if empty
+    pub object_types: Vec<String>,
+    pub exclude_object_types: Option<Vec<String>>,
+    pub exclude_object_names: Option<Vec<String>>,
+}
+
+/// Changes to an existing connection for [`ConnectionService::edit`], fields left `None`
+/// keep their value
+#[derive(Clone, Default)]
+pub struct ConnectionEdit {
+    pub rename: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub connection_string: Option<String>,
+}
+
+impl std::fmt::Debug for ConnectionEdit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionEdit")
+            .field("rename", &self.rename)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("connection_string", &self.connection_string)
+            .finish()
+    }
+}
+
+impl ConnectionEdit {
+    fn changes_credentials(&self) -> bool {
+        self.username.is_some() || self.password.is_some() || self.connection_string.is_some()
+    }
+}
 
 pub struct ConnectionService {
     repo: Arc<ConnectionRepository>,
+    plan_repo: Arc<PlanRepository>,
 }
 
 impl ConnectionService {
-    pub fn new(repo: Arc<ConnectionRepository>) -> Self {
-        Self { repo }
+    pub fn new(repo: Arc<ConnectionRepository>, plan_repo: Arc<PlanRepository>) -> Self {
+        Self { repo, plan_repo }
     }
 
     pub fn get_repo(&self) -> Arc<ConnectionRepository> {
@@ -35,22 +85,268 @@ impl ConnectionService {
         self.repo.delete_all().await
     }
 
+    /// Creates an Oracle connection, deployments targeting it need `required_approvals`
+    /// distinct approvals
     pub async fn create(
         &self,
         name: &str,
         username: &str,
         password: &str,
         connection_string: &str,
+        required_approvals: i32,
+    ) -> Result<ConnectionModel> {
+        ensure_required_approvals(required_approvals)?;
+        if self.repo.exists_by_name(name).await? {
+            anyhow::bail!(
+                "Connection with name '{}' already exists. Connection names are case-insensitive and must be unique.",
+                name
+            );
+        }
+
+        self.repo
+            .create(
+                name,
+                username,
+                password,
+                connection_string,
+                required_approvals,
+            )
+            .await
+    }
+
+    /// Creates a connection that reads objects from a directory of DDL files.
+    /// The path is stored absolute so plans work from any working directory.
+    pub async fn create_directory(
+        &self,
+        name: &str,
+        path: &Path,
+        required_approvals: i32,
     ) -> Result<ConnectionModel> {
+        ensure_required_approvals(required_approvals)?;
         if self.repo.exists_by_name(name).await? {
             anyhow::bail!(
                 "Connection with name '{}' already exists. Connection names are case-insensitive and must be unique.",
                 name
             );
         }
+        ensure!(
+            path.is_dir(),
+            "Directory '{}' does not exist",
+            path.display()
+        );
+        let path = path
+            .canonicalize()
+            .context(format!("Failed to resolve directory '{}'", path.display()))?;
 
         self.repo
-            .create(name, username, password, connection_string)
+            .create_directory(name, &path.to_string_lossy(), required_approvals)
+            .await
+    }
+
+    /// Replaces the credentials and connection string of an Oracle connection
+    pub async fn update(
+        &self,
+        name: &str,
+        username: &str,
+        password: &str,
+        connection_string: &str,
+        required_approvals: i32,
+    ) -> Result<ConnectionModel> {
+        ensure_required_approvals(required_approvals)?;
+        let connection = self
+            .repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection '{}' not found", name))?;
+        ensure!(
+            connection.kind == ConnectionKind::Oracle,
+            "Connection '{}' is a {} connection",
+            name,
+            connection.kind
+        );
+
+        self.repo
+            .update(
+                connection.id,
+                username,
+                password,
+                connection_string,
+                required_approvals,
+            )
+            .await
+    }
+
+    /// Points a directory connection to another directory, stored absolute
+    pub async fn update_directory(
+        &self,
+        name: &str,
+        path: &Path,
+        required_approvals: i32,
+    ) -> Result<ConnectionModel> {
+        ensure_required_approvals(required_approvals)?;
+        let connection = self
+            .repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection '{}' not found", name))?;
+        ensure!(
+            connection.kind == ConnectionKind::Directory,
+            "Connection '{}' is an {} connection",
+            name,
+            connection.kind
+        );
+        ensure!(
+            path.is_dir(),
+            "Directory '{}' does not exist",
+            path.display()
+        );
+        let path = path
+            .canonicalize()
+            .context(format!("Failed to resolve directory '{}'", path.display()))?;
+
+        self.repo
+            .update(
+                connection.id,
+                "",
+                "",
+                &path.to_string_lossy(),
+                required_approvals,
+            )
+            .await
+    }
+
+    /// Edits a connection in place, so the plans and deployments referencing it are kept.
+    /// New credentials of Oracle connections are tested before they are saved when `verify`
+    /// is set, directory connections can only be renamed. Connections of a running plan can
+    /// not be edited.
+    pub async fn edit(
+        &self,
+        name: &str,
+        edit: &ConnectionEdit,
+        verify: bool,
+    ) -> Result<ConnectionModel> {
+        let connection = self
+            .repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection '{}' not found", name))?;
+        ensure!(
+            edit.rename.is_some() || edit.changes_credentials(),
+            "Nothing to update for connection '{}'",
+            name
+        );
+        ensure!(
+            !self.plan_repo.is_connection_in_use(connection.id).await?,
+            "Connection '{}' is used by a running plan and can not be updated",
+            name
+        );
+
+        if let Some(rename) = &edit.rename {
+            ensure!(
+                !rename.trim().is_empty(),
+                "Connection name can not be empty"
+            );
+            if let Some(existing) = self.repo.find_by_name(rename).await? {
+                ensure!(
+                    existing.id == connection.id,
+                    "Connection with name '{}' already exists. Connection names are case-insensitive and must be unique.",
+                    rename
+                );
+            }
+        }
+
+        if edit.changes_credentials() {
+            ensure!(
+                connection.kind == ConnectionKind::Oracle,
+                "Connection '{}' is a {} connection, only its name can be updated",
+                name,
+                connection.kind
+            );
+        }
+        let username = edit.username.as_ref().unwrap_or(&connection.username);
+        let password = edit.password.as_ref().unwrap_or(&connection.password);
+        let connection_string = edit
+            .connection_string
+            .as_ref()
+            .unwrap_or(&connection.connection_string);
+        if verify && edit.changes_credentials() {
+            // A new password is given in plain text or as a reference, the current one is stored
+            let password = match &edit.password {
+                Some(password) if is_secret_reference(password) => resolve_secret(password)?,
+                Some(password) => password.clone(),
+                None => self.repo.secrets().reveal(password)?,
+            };
+            self.test(username, &password, connection_string)
+                .await
+                .context(format!(
+                    "Connection '{}' was not updated, the new settings failed the connection test",
+                    name
+                ))?;
+        }
+
+        // The name and the settings are written together, an edit is never half applied
+        self.repo
+            .edit(
+                connection.id,
+                edit.rename.as_ref().unwrap_or(&connection.name),
+                username,
+                password,
+                connection_string,
+            )
+            .await
+    }
+
+    /// Encrypts the passwords with a new key, written to `key_file` or to the configured key
+    /// file once every password is re-encrypted. Returns how many passwords were re-encrypted
+    /// and the key file.
+    pub async fn rotate_key(&self, key_file: Option<&Path>) -> Result<(usize, PathBuf)> {
+        let secrets = self.repo.secrets();
+        let path = key_file
+            .or(secrets.key_file())
+            .map(Path::to_path_buf)
+            .context(
+                "The key is set by LEAF__SECRETS__KEY, pass a key file to write the new key to",
+            )?;
+        ensure!(
+            secrets.key_file() == Some(path.as_path()) || !path.exists(),
+            "Key file '{}' already exists",
+            path.display()
+        );
+
+        let key = SecretKey::generate();
+        let pending = pending_key_file(&path);
+        key.write(&pending)?;
+        let count = match self.repo.reencrypt_passwords(&key).await {
+            Ok(count) => count,
+            Err(e) => {
+                let _ = std::fs::remove_file(&pending);
+                return Err(e);
+            }
+        };
+        std::fs::rename(&pending, &path).context(format!(
+            "Passwords are encrypted with the key in '{}', move it to '{}'",
+            pending.display(),
+            path.display()
+        ))?;
+
+        Ok((count, path))
+    }
+
+    /// Sets how many distinct approvals deployments targeting this connection need
+    pub async fn set_required_approvals(
+        &self,
+        name: &str,
+        required_approvals: i32,
+    ) -> Result<ConnectionModel> {
+        ensure_required_approvals(required_approvals)?;
+        let connection = self
+            .repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection '{}' not found", name))?;
+
+        self.repo
+            .set_required_approvals(connection.id, required_approvals)
             .await
     }
 
@@ -78,12 +374,19 @@ impl ConnectionService {
             .map_err(|e| anyhow::anyhow!(e))?
             .ok_or_else(|| anyhow::anyhow!("Connection '{}' not found", name))?;
 
-        self.test(
-            &connection.username,
-            &connection.password,
-            &connection.connection_string,
-        )
-        .await?;
+        match connection.kind {
+            ConnectionKind::Oracle => {
+                self.test(
+                    &connection.username,
+                    &self.repo.secrets().reveal(&connection.password)?,
+                    &connection.connection_string,
+                )
+                .await?
+            }
+            ConnectionKind::Directory => {
+                DirectorySource::open(connection.connection_string.as_ref())?.schemas()?;
+            }
+        }
 
         Ok(connection)
     }
@@ -103,4 +406,55 @@ impl ConnectionService {
             .context("Failed to execute test query on Oracle database")?;
         Ok(())
     }
+
+    /// Compares the objects of two connections without creating a plan or a deployment.
+    pub async fn compare(
+        &self,
+        source: &str,
+        target: &str,
+        options: CompareOptions,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<ObjectComparison>> {
+        let source = self
+            .repo
+            .find_by_name(source)
+            .await?
+            .ok_or_else(|| anyhow!("Source connection '{}' not found", source))?;
+        let target = self
+            .repo
+            .find_by_name(target)
+            .await?
+            .ok_or_else(|| anyhow!("Target connection '{}' not found", target))?;
+
+        let mut objects = Vec::new();
+        for (connection, side) in [
+            (&source, ConnectionSide::Source),
+            (&target, ConnectionSide::Target),
+        ] {
+            progress.report(format!("Fetching objects of '{}'...", connection.name));
+            let client = SourceClient::connect(connection, &self.repo.secrets())?;
+            let fetched = client
+                .get_objects_with_ddls(
+                    options.schemas.clone(),
+                    None,
+                    (!options.object_types.is_empty()).then(|| options.object_types.clone()),
+                    options.exclude_object_types.clone(),
+                    options.exclude_object_names.clone(),
+                )
+                .await
+                .context(format!(
+                    "Failed to fetch objects of connection '{}'",
+                    connection.name
+                ))?;
+            progress.report(ProgressEvent::ObjectsFetched {
+                side,
+                count: fetched.len(),
+            });
+            objects.push(fetched);
+        }
+
+        let targets = objects.pop().unwrap_or_default();
+        let sources = objects.pop().unwrap_or_default();
+        Ok(compare_objects(sources, targets))
+    }
 }
@@ -1,24 +1,76 @@
 use crate::{
-    delta::{delta::with_disabled_drop_types_excluded, find_deltas},
+    config::RetentionConfig,
+    delta::{ddl_matches, delta::with_disabled_drop_types_excluded, find_deltas},
     entities::{
-        ChangeActiveModel, ChangeModel, ChangesetActiveModel, ChangesetModel, DeploymentModel,
-        PlanModel,
+        ApprovalModel, ChangeActiveModel, ChangeModel, ChangesetActiveModel, ChangesetModel,
+        DeploymentModel, PlanModel,
+    },
+    errors::{
+        ApprovalRequiredError, DeployError, DeploymentCancelledError, DriftDetectedError,
+        LockLostError, PlanIsNotRunnableError, PrepareCancelledError, RollbackChainError,
+        SchemaValidationError, TagInUseError,
     },
-    errors::{DeployError, PlanIsNotRunnableError, SchemaValidationError},
     oracle::OracleClient,
     repo::{
-        ChangeRepository, ChangesetRepository, ConnectionRepository, DeploymentRepository,
-        PlanRepository, rollback_repo::RollbackRepository,
+        ApprovalRepository, ChangeRepository, ChangesetRepository, ConnectionRepository,
+        DeploymentRepository, PlanRepository, rollback_repo::RollbackRepository,
+    },
+    report::{DeploymentDiff, DeploymentReport},
+    services::{AuditService, DeploymentLock, LockService},
+    source::SourceClient,
+    types::{
+        AuditOperation, ChangeStatus, ChangesetStatus, ConnectionKind, Delta, DeploymentStatus,
+        DriftKind, DriftPolicy, DriftedObject, Object, ObjectDrift, PlanStatus, PurgeSummary,
+        RetentionPolicy, RollbackStatus, RollbackTarget, StringList, VerificationStatus,
+        current_os_user,
+    },
+    utils::{
+        ConnectionSide, DeploymentContext, Phase, ProgressEvent, ProgressReporter, scripts_checksum,
     },
-    types::{ChangeStatus, Delta, DeploymentStatus, PlanStatus, RollbackStatus, StringList},
-    utils::{DeploymentContext, ProgressReporter},
 };
-use anyhow::{Context, Result, anyhow};
-use chrono::NaiveDateTime;
-use sea_orm::IntoActiveModel;
-use std::sync::Arc;
-use tokio::try_join;
+use anyhow::{Context, Result, anyhow, ensure};
+use chrono::{NaiveDateTime, Utc};
+use itertools::Itertools;
+use sea_orm::{ActiveValue::Set, IntoActiveModel};
+use std::{collections::HashMap, sync::Arc};
+use tokio::{task::JoinHandle, try_join};
 use tracing::warn;
+use uuid::Uuid;
+
+/// Plans and deployments left `RUNNING` by a process that stopped
+#[derive(Debug, Default)]
+pub struct InterruptedRecords {
+    pub deployments: Vec<DeploymentModel>,
+    pub plans: Vec<PlanModel>,
+}
+
+/// One changeset of an object, see [`DeploymentService::find_object_history`]
+#[derive(Debug, Clone)]
+pub struct ObjectVersion {
+    /// 1-based position in the history of the object
+    pub version: usize,
+    pub changeset: ChangesetModel,
+    pub changes: Vec<ChangeModel>,
+    pub deployment: DeploymentModel,
+    pub plan_name: String,
+}
+
+impl InterruptedRecords {
+    pub fn is_empty(&self) -> bool {
+        self.deployments.is_empty() && self.plans.is_empty()
+    }
+}
+
+/// Repositories read and written by a [`DeploymentService`]
+pub struct DeploymentRepositories {
+    pub deployments: Arc<DeploymentRepository>,
+    pub plans: Arc<PlanRepository>,
+    pub connections: Arc<ConnectionRepository>,
+    pub changesets: Arc<ChangesetRepository>,
+    pub changes: Arc<ChangeRepository>,
+    pub rollbacks: Arc<RollbackRepository>,
+    pub approvals: Arc<ApprovalRepository>,
+}
 
 pub struct DeploymentService {
     repo: Arc<DeploymentRepository>,
@@ -27,24 +79,27 @@ pub struct DeploymentService {
     changeset_repo: Arc<ChangesetRepository>,
     change_repo: Arc<ChangeRepository>,
     rollback_repo: Arc<RollbackRepository>,
+    approval_repo: Arc<ApprovalRepository>,
+    lock_service: Arc<LockService>,
+    audit_service: Arc<AuditService>,
 }
 
 impl DeploymentService {
     pub fn new(
-        repo: Arc<DeploymentRepository>,
-        plan_repo: Arc<PlanRepository>,
-        connection_repo: Arc<ConnectionRepository>,
-        changeset_repo: Arc<ChangesetRepository>,
-        change_repo: Arc<ChangeRepository>,
-        rollback_repo: Arc<RollbackRepository>,
+        repos: DeploymentRepositories,
+        lock_service: Arc<LockService>,
+        audit_service: Arc<AuditService>,
     ) -> Self {
         Self {
-            repo,
-            plan_repo,
-            connection_repo,
-            changeset_repo,
-            change_repo,
-            rollback_repo,
+            repo: repos.deployments,
+            plan_repo: repos.plans,
+            connection_repo: repos.connections,
+            changeset_repo: repos.changesets,
+            change_repo: repos.changes,
+            rollback_repo: repos.rollbacks,
+            approval_repo: repos.approvals,
+            lock_service,
+            audit_service,
         }
     }
 
@@ -58,19 +113,21 @@ impl DeploymentService {
         cutoff_date: NaiveDateTime,
         disable_hooks: Option<bool>,
     ) -> Result<DeploymentModel> {
+        let revision = self.plan_repo.find_latest_revision(plan.id).await?;
         self.repo
             .create(
                 plan.id,
+                revision.map(|r| r.id),
                 cutoff_date,
-                serde_json::to_string(&plan)?,
+                plan.as_payload().to_string(),
                 disable_hooks.unwrap_or(plan.disable_hooks),
                 plan.get_hooks()?.clone(),
             )
             .await
     }
 
-    pub async fn validate_schemas(&self, client: &OracleClient, schemas: &[String]) -> Result<()> {
-        let all_schemas = client.get_all_users().await?;
+    /// Fails with the schemas that are not in `all_schemas`
+    pub fn validate_schemas(all_schemas: &[String], schemas: &[String]) -> Result<()> {
         let missing = schemas
             .iter()
             .filter(|s| !all_schemas.contains(s))
@@ -84,6 +141,18 @@ impl DeploymentService {
         }
     }
 
+    async fn get_source_client(&self, connection_id: i32) -> Result<SourceClient> {
+        let connection = self
+            .connection_repo
+            .get_by_id(connection_id)
+            .await
+            .context(format!(
+                "Failed to find connection for id '{}'",
+                connection_id
+            ))?;
+        SourceClient::connect(&connection, &self.connection_repo.secrets())
+    }
+
     async fn get_client(&self, connection_id: i32) -> Result<OracleClient> {
         let connection = self
             .connection_repo
@@ -93,9 +162,17 @@ impl DeploymentService {
                 "Failed to find connection for id '{}'",
                 connection_id
             ))?;
+        ensure!(
+            connection.kind == ConnectionKind::Oracle,
+            "Connection '{}' is a directory connection and can only be used as a plan source",
+            connection.name
+        );
         OracleClient::connect(
             &connection.username,
-            &connection.password,
+            &self
+                .connection_repo
+                .secrets()
+                .reveal(&connection.password)?,
             &connection.connection_string,
         )
         .context("Failed to connect to Oracle database")
@@ -136,28 +213,44 @@ impl DeploymentService {
         Ok(())
     }
 
+    /// Finds the deltas between source and target objects honoring the drop rules of the plan.
+    fn build_deltas(plan: &PlanModel, sources: Vec<Object>, targets: Vec<Object>) -> Vec<Delta> {
+        let deltas = find_deltas(sources, targets, plan.disable_all_drops);
+        if !plan.disable_all_drops {
+            let disabled_drop_types = plan.disabled_drop_types.clone().map(|sl| sl.0);
+            with_disabled_drop_types_excluded(deltas, disabled_drop_types)
+        } else {
+            deltas
+        }
+    }
+
     async fn create_changesets(
         &self,
+        plan_id: i32,
         deployment_model: Option<DeploymentModel>,
         deltas: &Vec<Delta>,
         ctx: &mut DeploymentContext,
     ) -> Result<()> {
         for (i, delta) in deltas.into_iter().enumerate() {
-            if delta.source_ddl == delta.target_ddl {
+            if ctx.is_cancelled() {
+                return Err(PrepareCancelledError { plan_id }.into());
+            }
+            if ddl_matches(delta.source_ddl.as_deref(), delta.target_ddl.as_deref()) {
                 ctx.progress(format!(
                     "Skipping changeset for {}.{} because source and target DDLs are the same",
                     delta.object_owner, delta.object_name,
                 ));
                 continue;
             }
-            ctx.progress(format!(
-                "Creating changeset {} of {} for '{} {}.{}'",
-                i + 1,
-                deltas.len(),
-                delta.object_type,
-                delta.object_owner,
-                delta.object_name
-            ));
+            ctx.record_delta(delta);
+            ctx.progress(ProgressEvent::ChangesetCreated {
+                index: i + 1,
+                total: deltas.len(),
+                object: format!(
+                    "{} {}.{}",
+                    delta.object_type, delta.object_owner, delta.object_name
+                ),
+            });
 
             let changeset: Option<ChangesetModel> = if ctx.is_dry_run() {
                 Ok(None)
@@ -212,11 +305,66 @@ impl DeploymentService {
         disable_hooks: Option<bool>,
         ctx: &mut DeploymentContext,
     ) -> Result<Option<i32>> {
-        match self.prepare(plan_id, cutoff_date, disable_hooks, ctx).await {
+        let plan = self.plan_repo.get_by_id(plan_id).await?;
+        if !ctx.is_dry_run() {
+            let required = self.required_approvals(&plan).await?;
+            ensure!(
+                required == 0,
+                "Plan '{}' requires {} approval(s), prepare the deployment and approve it before applying",
+                plan.name,
+                required
+            );
+        }
+
+        let event = self
+            .audit_service
+            .start(AuditOperation::Run, Some(plan.id), None, ctx.is_dry_run())
+            .await?;
+        let result = async {
+            // A dry run changes nothing and needs no lock
+            if ctx.is_dry_run() {
+                return self
+                    .run_locked(plan_id, fail_fast, cutoff_date, disable_hooks, ctx)
+                    .await;
+            }
+            // Prepare and apply run under a single lock, so no other process can deploy in between.
+            let lock = self.acquire_lock(&plan, "run", ctx).await?;
+            let result = self
+                .run_locked(plan_id, fail_fast, cutoff_date, disable_hooks, ctx)
+                .await;
+            Self::release_lock(lock, result).await
+        }
+        .await;
+        let deployment_id = result.as_ref().ok().copied().flatten();
+        self.audit_service
+            .finish(event, &result, deployment_id)
+            .await;
+        result
+    }
+
+    async fn run_locked(
+        &self,
+        plan_id: i32,
+        fail_fast: bool,
+        cutoff_date: NaiveDateTime,
+        disable_hooks: Option<bool>,
+        ctx: &mut DeploymentContext,
+    ) -> Result<Option<i32>> {
+        match self
+            .prepare_locked(plan_id, cutoff_date, disable_hooks, ctx)
+            .await
+        {
             Ok(result) => match result {
                 Some(deployment_id) => {
+                    // The deployment was prepared right now, no need to check for drift.
                     match self
-                        .apply(deployment_id, fail_fast, disable_hooks, ctx)
+                        .apply_locked(
+                            deployment_id,
+                            fail_fast,
+                            disable_hooks,
+                            DriftPolicy::Ignore,
+                            ctx,
+                        )
                         .await
                     {
                         Ok(_) => {
@@ -224,10 +372,16 @@ impl DeploymentService {
                             ctx.progress("✅ Deployment completed successfully");
                             Ok(Some(deployment_id))
                         }
+                        Err(e) if e.is::<DeploymentCancelledError>() => {
+                            ctx.progress(format!("🛑 {}", e));
+                            Err(e)
+                        }
                         Err(e) => {
                             let (count, _) = e
                                 .downcast_ref::<DeployError>()
-                                .map(|DeployError::Errors(count, errors)| (*count, errors.clone()))
+                                .map(|DeployError::Errors { count, errors, .. }| {
+                                    (*count, errors.clone())
+                                })
                                 .unwrap_or_else(|| (1, vec![e.to_string()]));
                             ctx.progress(format!("❌ Deployment failed with {} error(s)", count));
 
@@ -238,6 +392,10 @@ impl DeploymentService {
                 // Ifd it's a dry mode.
                 None => Ok(None),
             },
+            Err(e) if e.is::<PrepareCancelledError>() => {
+                ctx.progress(format!("🛑 {}", e));
+                Err(e)
+            }
             Err(e) => {
                 // Failure during preparation
                 self.plan_repo
@@ -249,6 +407,36 @@ impl DeploymentService {
         }
     }
 
+    /// Acquires the deployment lock of the plan, failing if another process holds it.
+    async fn acquire_lock(
+        &self,
+        plan: &PlanModel,
+        operation: &str,
+        ctx: &mut DeploymentContext,
+    ) -> Result<DeploymentLock> {
+        ctx.progress(format!(
+            "Acquiring deployment lock for plan '{}'...",
+            plan.name
+        ));
+        self.lock_service
+            .acquire(plan, operation, ctx.cancel_token())
+            .await
+    }
+
+    /// Releases the lock and returns the result of the locked operation, which takes
+    /// precedence over a failed release. An operation that stopped because the lock was
+    /// lost fails with both errors.
+    async fn release_lock<T>(lock: DeploymentLock, result: Result<T>) -> Result<T> {
+        match (result, lock.release().await) {
+            (Err(e), Err(lost)) if lost.is::<LockLostError>() => Err(e.context(lost.to_string())),
+            (result, released) => {
+                let value = result?;
+                released?;
+                Ok(value)
+            }
+        }
+    }
+
     pub async fn prepare(
         &self,
         plan_id: i32,
@@ -257,22 +445,64 @@ impl DeploymentService {
         ctx: &mut DeploymentContext,
     ) -> Result<Option<i32>> {
         let plan = self.plan_repo.get_by_id(plan_id).await?;
-        let source_client = self.get_client(plan.source_connection_id).await?;
+        let event = self
+            .audit_service
+            .start(
+                AuditOperation::Prepare,
+                Some(plan.id),
+                None,
+                ctx.is_dry_run(),
+            )
+            .await?;
+        let result = async {
+            if ctx.is_dry_run() {
+                return self
+                    .prepare_locked(plan_id, cutoff_date, disable_hooks, ctx)
+                    .await;
+            }
+            let lock = self.acquire_lock(&plan, "prepare", ctx).await?;
+            let result = self
+                .prepare_locked(plan_id, cutoff_date, disable_hooks, ctx)
+                .await;
+            Self::release_lock(lock, result).await
+        }
+        .await;
+        let deployment_id = result.as_ref().ok().copied().flatten();
+        self.audit_service
+            .finish(event, &result, deployment_id)
+            .await;
+        result
+    }
+
+    async fn prepare_locked(
+        &self,
+        plan_id: i32,
+        cutoff_date: NaiveDateTime,
+        disable_hooks: Option<bool>,
+        ctx: &mut DeploymentContext,
+    ) -> Result<Option<i32>> {
+        let plan = self.plan_repo.get_by_id(plan_id).await?;
+        let source_client = self.get_source_client(plan.source_connection_id).await?;
         let target_client = self.get_client(plan.target_connection_id).await?;
 
-        plan.run_pre_prepare_hooks(disable_hooks, &target_client, ctx)
+        // The deployment does not exist yet, hook executions are recorded once it is created
+        let mut hook_executions = Vec::new();
+        plan.run_pre_prepare_hooks(disable_hooks, &target_client, ctx, &mut hook_executions)
             .await?;
 
         let plan_id = plan.id;
 
+        ctx.progress(ProgressEvent::PhaseStarted {
+            phase: Phase::Prepare,
+        });
         ctx.progress(format!("Preparing deployment for plan '{}' ...", plan.name));
 
         let result = async {
             let schemas = plan.get_schemas();
 
             ctx.progress(format!("Validating schemas..."));
-            self.validate_schemas(&source_client, &schemas).await?;
-            self.validate_schemas(&target_client, &schemas).await?;
+            Self::validate_schemas(&source_client.get_all_users().await?, &schemas)?;
+            Self::validate_schemas(&target_client.get_all_users().await?, &schemas)?;
 
             let exclude_object_types = plan.get_exclude_object_types();
             let exclude_object_names = plan.get_exclude_object_names();
@@ -282,28 +512,37 @@ impl DeploymentService {
                 .get_objects_with_ddls(
                     schemas.clone(),
                     Some(cutoff_date),
+                    None,
                     exclude_object_types.clone(),
                     exclude_object_names.clone(),
                 )
                 .await?;
-            ctx.progress(format!("Fetched {} source objects", sources.len()));
+            ctx.progress(ProgressEvent::ObjectsFetched {
+                side: ConnectionSide::Source,
+                count: sources.len(),
+            });
+            ensure!(!ctx.is_cancelled(), PrepareCancelledError { plan_id });
 
             ctx.progress(format!("Fetching target objects..."));
 
             let targets = target_client
-                .get_objects_with_ddls(schemas, None, exclude_object_types, exclude_object_names)
+                .get_objects_with_ddls(
+                    schemas,
+                    None,
+                    None,
+                    exclude_object_types,
+                    exclude_object_names,
+                )
                 .await?;
-            ctx.progress(format!("Fetched {} target objects", targets.len()));
+            ctx.progress(ProgressEvent::ObjectsFetched {
+                side: ConnectionSide::Target,
+                count: targets.len(),
+            });
+            ensure!(!ctx.is_cancelled(), PrepareCancelledError { plan_id });
 
             ctx.progress(format!("Finding deltas..."));
 
-            let deltas = find_deltas(sources, targets, plan.disable_all_drops);
-            let deltas = if !plan.disable_all_drops {
-                let disabled_drop_types = plan.disabled_drop_types.clone().map(|sl| sl.0);
-                with_disabled_drop_types_excluded(deltas, disabled_drop_types.clone())
-            } else {
-                deltas
-            };
+            let deltas = Self::build_deltas(&plan, sources, targets);
 
             ctx.progress(format!("Creating deployment..."));
             let deployment_model: Option<DeploymentModel> = if ctx.is_dry_run() {
@@ -327,34 +566,406 @@ impl DeploymentService {
                 return Ok(None);
             }
             let deployment_id: Option<i32> = deployment_model.as_ref().map(|d| d.id);
-            self.create_changesets(deployment_model, &deltas, ctx)
-                .await?;
+            if let Err(e) = self
+                .create_changesets(plan_id, deployment_model, &deltas, ctx)
+                .await
+            {
+                // A partially prepared deployment must never be applied
+                if let (true, Some(id)) = (e.is::<PrepareCancelledError>(), deployment_id) {
+                    self.repo.purge(&[id]).await?;
+                }
+                return Err(e);
+            }
 
             Ok(deployment_id)
         }
         .await;
 
-        let final_status = if result.is_ok() {
-            PlanStatus::Success
-        } else {
-            PlanStatus::Error
+        ctx.progress(ProgressEvent::PhaseFinished {
+            phase: Phase::Prepare,
+            success: result.is_ok(),
+        });
+        let final_status = match &result {
+            Ok(_) => PlanStatus::Success,
+            Err(e) if e.is::<PrepareCancelledError>() => PlanStatus::Cancelled,
+            Err(_) => PlanStatus::Error,
         };
 
         self.plan_repo.set_status(plan_id, final_status).await?;
 
-        plan.run_post_prepare_hooks(disable_hooks, &target_client, ctx)
-            .await?;
+        let post_hooks_result = plan
+            .run_post_prepare_hooks(disable_hooks, &target_client, ctx, &mut hook_executions)
+            .await;
+        if let Ok(Some(deployment_id)) = &result {
+            self.repo
+                .add_hook_executions(*deployment_id, &hook_executions)
+                .await?;
+        }
+        post_hooks_result?;
 
         result
     }
 
+    /// Number of approvals a deployment of the plan needs, the stricter of the plan and
+    /// target connection settings.
+    pub async fn required_approvals(&self, plan: &PlanModel) -> Result<usize> {
+        let target = self
+            .connection_repo
+            .get_by_id(plan.target_connection_id)
+            .await?;
+        Ok(plan
+            .required_approvals
+            .max(target.required_approvals)
+            .max(0) as usize)
+    }
+
+    /// Checksum over all change scripts of the deployment, in execution order.
+    pub async fn scripts_checksum(&self, deployment_id: i32) -> Result<String> {
+        let mut changes = self
+            .change_repo
+            .find_by_deployment_id(deployment_id)
+            .await?;
+        changes.sort_by_key(|c| (c.changeset_id, c.id));
+        Ok(scripts_checksum(changes.iter().map(|c| c.script.as_str())))
+    }
+
+    /// Records an approval of the deployment's current scripts.
+    pub async fn approve(
+        &self,
+        deployment_id: i32,
+        approved_by: &str,
+        comment: Option<&str>,
+    ) -> Result<ApprovalModel> {
+        let approved_by = approved_by.trim();
+        ensure!(!approved_by.is_empty(), "Approver name can not be empty");
+
+        let deployment = self.repo.get_by_id(deployment_id).await?;
+        ensure!(
+            deployment.status == DeploymentStatus::Idle,
+            "Deployment {} is in {} status, only IDLE deployments can be approved",
+            deployment_id,
+            deployment.status
+        );
+
+        let checksum = self.scripts_checksum(deployment_id).await?;
+        let already_approved = self
+            .approval_repo
+            .find_by_deployment_id(deployment_id)
+            .await?
+            .iter()
+            .any(|a| a.checksum == checksum && a.approved_by.eq_ignore_ascii_case(approved_by));
+        ensure!(
+            !already_approved,
+            "Deployment {} is already approved by '{}'",
+            deployment_id,
+            approved_by
+        );
+
+        self.approval_repo
+            .create(deployment_id, approved_by, comment, &checksum)
+            .await
+    }
+
+    pub async fn find_approvals(&self, deployment_id: i32) -> Result<Vec<ApprovalModel>> {
+        self.approval_repo
+            .find_by_deployment_id(deployment_id)
+            .await
+    }
+
+    /// Fails unless enough distinct approvers approved the deployment's current scripts.
+    async fn ensure_approved(&self, plan: &PlanModel, deployment_id: i32) -> Result<()> {
+        let required = self.required_approvals(plan).await?;
+        if required == 0 {
+            return Ok(());
+        }
+
+        let checksum = self.scripts_checksum(deployment_id).await?;
+        let approvals = self
+            .approval_repo
+            .find_by_deployment_id(deployment_id)
+            .await?;
+
+        let (current, stale): (Vec<_>, Vec<_>) =
+            approvals.iter().partition(|a| a.checksum == checksum);
+        let approved = current
+            .iter()
+            .map(|a| a.approved_by.to_lowercase())
+            .unique()
+            .count();
+
+        if approved < required {
+            return Err(ApprovalRequiredError {
+                deployment_id,
+                required,
+                approved,
+                stale: stale.len(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Re-extracts the DDL of every changeset object of the deployment from the target and
+    /// returns the objects whose DDL no longer matches the recorded `target_ddl`.
+    pub async fn find_drifted_objects(
+        &self,
+        deployment_id: i32,
+        client: &OracleClient,
+        ctx: &mut DeploymentContext,
+    ) -> Result<Vec<DriftedObject>> {
+        // Changesets of an interrupted or cancelled deployment that already ran are expected to differ
+        let changesets: Vec<ChangesetModel> = self
+            .changeset_repo
+            .find_by_deployment_id(deployment_id)
+            .await?
+            .into_iter()
+            .filter(|c| {
+                c.status == ChangesetStatus::Idle
+                    || (c.status == ChangesetStatus::Cancelled && c.started_at.is_none())
+            })
+            .collect();
+
+        let mut drifted = Vec::new();
+        for (i, changeset) in changesets.iter().enumerate() {
+            ctx.progress(format!(
+                "Checking drift {} of {} for '{} {}.{}'",
+                i + 1,
+                changesets.len(),
+                changeset.object_type,
+                changeset.object_owner,
+                changeset.object_name
+            ));
+            let actual_ddl = client.find_ddl(
+                &changeset.object_type,
+                &changeset.object_name,
+                &changeset.object_owner,
+            )?;
+
+            if !ddl_matches(changeset.target_ddl.as_deref(), actual_ddl.as_deref()) {
+                drifted.push(DriftedObject {
+                    changeset_id: changeset.id,
+                    object_type: changeset.object_type.clone(),
+                    object_owner: changeset.object_owner.clone(),
+                    object_name: changeset.object_name.clone(),
+                    expected_ddl: changeset.target_ddl.clone(),
+                    actual_ddl,
+                });
+            }
+        }
+
+        Ok(drifted)
+    }
+
+    /// Compares every object the plan ever deployed with the target. The expected state of an
+    /// object comes from its latest successful changeset: the source DDL, or the target DDL
+    /// if the deployment was rolled back. Objects of partially rolled back deployments are
+    /// skipped since their state is unknown.
+    ///
+    /// Returns the objects that were altered, dropped or created outside of leaf.
+    pub async fn find_plan_drift(
+        &self,
+        plan_id: i32,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<ObjectDrift>> {
+        let plan = self.plan_repo.get_by_id(plan_id).await?;
+        let deployments = self.repo.get_by_plan_id(plan.id).await?;
+        let changesets = self
+            .changeset_repo
+            .find_successful_by_deployment_ids(deployments.iter().map(|d| d.id).collect())
+            .await?;
+
+        // Changesets are ordered oldest first, later ones replace earlier ones of the same object
+        let mut latest: HashMap<(&str, &str, &str), &ChangesetModel> = HashMap::new();
+        for changeset in &changesets {
+            latest.insert(
+                (
+                    &changeset.object_type,
+                    &changeset.object_owner,
+                    &changeset.object_name,
+                ),
+                changeset,
+            );
+        }
+        let mut latest: Vec<&ChangesetModel> = latest.into_values().collect();
+        latest.sort_by_key(|c| c.id);
+
+        progress.report(ProgressEvent::PhaseStarted {
+            phase: Phase::Verify,
+        });
+        let client = self.get_client(plan.target_connection_id).await?;
+
+        let mut drifted = Vec::new();
+        for (i, changeset) in latest.iter().enumerate() {
+            let Some(deployment) = deployments.iter().find(|d| d.id == changeset.deployment_id)
+            else {
+                continue;
+            };
+            let expected_ddl = match deployment.status {
+                DeploymentStatus::RolledBack => changeset.target_ddl.clone(),
+                DeploymentStatus::RollingBack | DeploymentStatus::RollbackError => continue,
+                _ => changeset.source_ddl.clone(),
+            };
+
+            progress.report(format!(
+                "Checking drift {} of {} for '{} {}.{}'",
+                i + 1,
+                latest.len(),
+                changeset.object_type,
+                changeset.object_owner,
+                changeset.object_name
+            ));
+            let actual_ddl = client.find_ddl(
+                &changeset.object_type,
+                &changeset.object_name,
+                &changeset.object_owner,
+            )?;
+
+            if let Some(kind) = DriftKind::classify(expected_ddl.as_deref(), actual_ddl.as_deref())
+            {
+                drifted.push(ObjectDrift {
+                    object_type: changeset.object_type.clone(),
+                    object_owner: changeset.object_owner.clone(),
+                    object_name: changeset.object_name.clone(),
+                    kind,
+                    deployment_id: changeset.deployment_id,
+                    changeset_id: changeset.id,
+                    expected_ddl,
+                    actual_ddl,
+                });
+            }
+        }
+        progress.report(ProgressEvent::PhaseFinished {
+            phase: Phase::Verify,
+            success: true,
+        });
+
+        Ok(drifted)
+    }
+
+    /// Regenerates the changes of drifted changesets from the current target DDL.
+    async fn refresh_drifted_changesets(
+        &self,
+        plan: &PlanModel,
+        drifted: &[DriftedObject],
+        ctx: &mut DeploymentContext,
+    ) -> Result<()> {
+        for object in drifted {
+            ctx.progress(format!(
+                "Re-preparing changeset for '{}'",
+                object.qualified_name()
+            ));
+            let changeset = self.changeset_repo.get_by_id(object.changeset_id).await?;
+            let now = Utc::now().naive_utc();
+
+            let as_object = |ddl: &String, last_ddl_time: NaiveDateTime| Object {
+                owner: changeset.object_owner.clone(),
+                object_name: changeset.object_name.clone(),
+                object_type: changeset.object_type.clone(),
+                last_ddl_time,
+                ddl: Some(ddl.clone()),
+            };
+            let sources = changeset
+                .source_ddl
+                .iter()
+                .map(|ddl| as_object(ddl, changeset.source_ddl_time.unwrap_or(now)))
+                .collect();
+            let targets = object
+                .actual_ddl
+                .iter()
+                .map(|ddl| as_object(ddl, now))
+                .collect();
+            let scripts: Vec<(String, String)> = Self::build_deltas(plan, sources, targets)
+                .into_iter()
+                .flat_map(|delta| delta.scripts.into_iter().zip(delta.rollback_scripts))
+                .collect();
+
+            // The target DDL and the changes are replaced together, a failure leaves the
+            // changeset as it was prepared
+            let mut changeset_active = changeset.into_active_model();
+            changeset_active.target_ddl = Set(object.actual_ddl.clone());
+            changeset_active.target_ddl_time = Set(object.actual_ddl.as_ref().map(|_| now));
+            self.changeset_repo
+                .replace_changes(&changeset_active, &scripts)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Compares the target with the prepared changesets and acts on drift according to the policy.
+    async fn check_drift(
+        &self,
+        plan: &PlanModel,
+        deployment_id: i32,
+        client: &OracleClient,
+        drift_policy: DriftPolicy,
+        ctx: &mut DeploymentContext,
+    ) -> Result<()> {
+        if drift_policy == DriftPolicy::Ignore {
+            return Ok(());
+        }
+
+        ctx.progress(format!(
+            "Checking target for drift since deployment {} was prepared...",
+            deployment_id
+        ));
+        let drifted = self
+            .find_drifted_objects(deployment_id, client, ctx)
+            .await?;
+        if drifted.is_empty() {
+            return Ok(());
+        }
+
+        match drift_policy {
+            DriftPolicy::Refresh => self.refresh_drifted_changesets(plan, &drifted, ctx).await,
+            _ => Err(DriftDetectedError {
+                objects: drifted.iter().map(|o| o.qualified_name()).collect(),
+            }
+            .into()),
+        }
+    }
+
     pub async fn apply(
         &self,
         deployment_id: i32,
         fail_fast: bool,
         disable_hooks: Option<bool>,
+        drift_policy: DriftPolicy,
         ctx: &mut DeploymentContext,
     ) -> Result<()> {
+        let deployment = self.repo.get_by_id(deployment_id).await?;
+        let plan = self.plan_repo.get_by_id(deployment.plan_id).await?;
+        let event = self
+            .audit_service
+            .start(
+                AuditOperation::Apply,
+                Some(plan.id),
+                Some(deployment_id),
+                ctx.is_dry_run(),
+            )
+            .await?;
+        let result = async {
+            let lock = self.acquire_lock(&plan, "apply", ctx).await?;
+            let result = self
+                .apply_locked(deployment_id, fail_fast, disable_hooks, drift_policy, ctx)
+                .await;
+            Self::release_lock(lock, result).await
+        }
+        .await;
+        self.audit_service.finish(event, &result, None).await;
+        result
+    }
+
+    async fn apply_locked(
+        &self,
+        deployment_id: i32,
+        fail_fast: bool,
+        disable_hooks: Option<bool>,
+        drift_policy: DriftPolicy,
+        ctx: &mut DeploymentContext,
+    ) -> Result<()> {
+        ctx.progress(ProgressEvent::PhaseStarted {
+            phase: Phase::Apply,
+        });
         ctx.progress(format!("Applying changes ..."));
 
         let deployment = self.repo.get_by_id(deployment_id).await?;
@@ -366,8 +977,26 @@ impl DeploymentService {
         ctx.progress(format!("Getting target client for plan '{}'...", plan.name));
         let client = self.get_client(plan.target_connection_id).await?;
 
-        plan.run_pre_apply_hooks(disable_hooks, &client, ctx)
+        self.check_drift(&plan, deployment_id, &client, drift_policy, ctx)
+            .await?;
+
+        // Refreshing drifted changesets changes the scripts, so approvals are checked afterwards.
+        self.ensure_approved(&plan, deployment_id).await?;
+
+        let mut hook_executions = Vec::new();
+        let pre_hooks_result = plan
+            .run_pre_apply_hooks(disable_hooks, &client, ctx, &mut hook_executions)
+            .await;
+        self.repo
+            .add_hook_executions(deployment_id, &hook_executions)
             .await?;
+        pre_hooks_result?;
+
+        let resuming = matches!(
+            deployment.status,
+            DeploymentStatus::Interrupted | DeploymentStatus::Cancelled
+        );
+        let heartbeat = self.spawn_heartbeat(deployment_id);
 
         let result: Result<()> = async {
             ctx.progress(format!("Setting plan '{}' status to RUNNING...", plan.name));
@@ -382,6 +1011,14 @@ impl DeploymentService {
             self.repo
                 .set_status(deployment_id, DeploymentStatus::Running)
                 .await?;
+            self.repo
+                .set_owner(
+                    deployment_id,
+                    std::process::id() as i32,
+                    &gethostname::gethostname().to_string_lossy(),
+                    &current_os_user(),
+                )
+                .await?;
 
             ctx.progress(format!(
                 "Retrieving changesets for deployment ID('{}')...",
@@ -400,48 +1037,99 @@ impl DeploymentService {
                 return Ok(());
             }
 
+            let total_changes = changesets_with_changes
+                .iter()
+                .map(|(_, changes)| changes.len())
+                .sum();
+            let mut change_index = 0;
             let mut errors: Vec<String> = Vec::new();
+            let mut not_applied = 0;
             for (changeset, changes) in changesets_with_changes {
                 let object_type = changeset.object_type.clone();
                 let object_owner = changeset.object_owner.clone();
                 let object_name = changeset.object_name.clone();
+                let object = format!("{} {}.{}", object_type, object_owner, object_name);
 
                 if changes.is_empty() {
                     ctx.progress(format!(
-                        "Skipping changeset for '{} {}.{}' because no changes were found",
-                        object_type, object_owner, object_name
+                        "Skipping changeset for '{}' because no changes were found",
+                        object
+                    ));
+                    continue;
+                }
+
+                if resuming && changeset.status == ChangesetStatus::Success {
+                    change_index += changes.len();
+                    ctx.progress(format!(
+                        "Skipping changeset for '{}' because it was already applied",
+                        object
                     ));
                     continue;
                 }
 
                 let mut changeset_active: ChangesetActiveModel = changeset.into_active_model();
-                changeset_active.start();
+                if !ctx.is_cancelled() {
+                    changeset_active.start();
 
-                self.changeset_repo
-                    .save_changeset(&changeset_active)
-                    .await?;
+                    self.changeset_repo
+                        .save_changeset(&changeset_active)
+                        .await?;
+                }
 
                 let mut changeset_errors = Vec::new();
+                let mut changeset_cancelled = false;
 
                 for change in changes {
+                    change_index += 1;
+
+                    // Interrupted changes are executed again, their outcome is unknown
+                    if resuming && change.status == ChangeStatus::Success {
+                        continue;
+                    }
+
                     let change_id = change.id;
                     let script = change.script.clone();
 
                     let mut change_active: ChangeActiveModel = change.into_active_model();
+
+                    // The statement in flight completes, the remaining ones are not started
+                    if ctx.is_cancelled() {
+                        change_active.set_status(ChangeStatus::Cancelled);
+                        self.change_repo.save_change(&change_active).await?;
+                        changeset_cancelled = true;
+                        not_applied += 1;
+                        continue;
+                    }
+
                     change_active.start();
 
                     self.change_repo.save_change(&change_active).await?;
 
-                    ctx.progress(format!(
-                        "Executing change for '{} {}.{}'",
-                        object_type, object_owner, object_name
-                    ));
+                    ctx.progress(ProgressEvent::ChangeExecuting {
+                        change_id,
+                        index: change_index,
+                        total: total_changes,
+                        object: object.clone(),
+                    });
                     let result = match client.execute(&script).await {
                         Ok(_) => {
                             change_active.end(None);
+                            ctx.progress(ProgressEvent::ChangeSucceeded {
+                                change_id,
+                                index: change_index,
+                                total: total_changes,
+                                object: object.clone(),
+                            });
                             Ok(())
                         }
                         Err(e) => {
+                            ctx.progress(ProgressEvent::ChangeFailed {
+                                change_id,
+                                index: change_index,
+                                total: total_changes,
+                                object: object.clone(),
+                                error: e.to_string(),
+                            });
                             let error_msg =
                                 format!("Change {} ({}): {}", change_id, object_name, e);
 
@@ -459,14 +1147,21 @@ impl DeploymentService {
                     self.change_repo.save_change(&change_active).await?;
 
                     if result.is_err() && fail_fast {
-                        return Err(DeployError::Errors(1, errors).into());
+                        return Err(DeployError::Errors {
+                            deployment_id,
+                            count: 1,
+                            errors,
+                        }
+                        .into());
                     }
                 }
 
-                if changeset_errors.is_empty() {
-                    changeset_active.end(None);
+                let changeset_errors =
+                    (!changeset_errors.is_empty()).then_some(StringList(changeset_errors));
+                if changeset_cancelled {
+                    changeset_active.cancel(changeset_errors);
                 } else {
-                    changeset_active.end(Some(StringList(changeset_errors)));
+                    changeset_active.end(changeset_errors);
                 }
 
                 self.changeset_repo
@@ -474,15 +1169,43 @@ impl DeploymentService {
                     .await?;
             }
 
+            if not_applied > 0 {
+                ctx.progress(format!(
+                    "Deployment {} cancelled, {} change(s) not applied",
+                    deployment_id, not_applied
+                ));
+                return Err(DeploymentCancelledError {
+                    deployment_id,
+                    not_applied,
+                }
+                .into());
+            }
+
             if !errors.is_empty() {
-                return Err(DeployError::Errors(errors.len(), errors).into());
+                return Err(DeployError::Errors {
+                    deployment_id,
+                    count: errors.len(),
+                    errors,
+                }
+                .into());
             }
             Ok(())
         }
         .await;
-
-        plan.run_post_apply_hooks(disable_hooks, &client, ctx)
+        heartbeat.abort();
+        ctx.progress(ProgressEvent::PhaseFinished {
+            phase: Phase::Apply,
+            success: result.is_ok(),
+        });
+
+        let mut hook_executions = Vec::new();
+        let post_hooks_result = plan
+            .run_post_apply_hooks(disable_hooks, &client, ctx, &mut hook_executions)
+            .await;
+        self.repo
+            .add_hook_executions(deployment_id, &hook_executions)
             .await?;
+        post_hooks_result?;
 
         match &result {
             Ok(_) => {
@@ -493,10 +1216,18 @@ impl DeploymentService {
                     .set_status(plan_id, PlanStatus::Success)
                     .await?;
             }
+            Err(e) if e.is::<DeploymentCancelledError>() => {
+                self.repo
+                    .set_status(deployment_id, DeploymentStatus::Cancelled)
+                    .await?;
+                self.plan_repo
+                    .set_status(plan_id, PlanStatus::Cancelled)
+                    .await?;
+            }
             Err(e) => {
                 let errors = e
                     .downcast_ref::<DeployError>()
-                    .map(|DeployError::Errors(_, errors)| errors.clone())
+                    .map(|DeployError::Errors { errors, .. }| errors.clone())
                     .unwrap_or_else(|| vec![e.to_string()]);
 
                 self.plan_repo
@@ -509,6 +1240,70 @@ impl DeploymentService {
         result
     }
 
+    /// Re-extracts every successfully applied object from the target and compares it with the
+    /// source DDL of its changeset. Each changeset is marked `VERIFIED` or `MISMATCH`.
+    ///
+    /// Returns the mismatched changesets.
+    pub async fn verify(
+        &self,
+        deployment_id: i32,
+        ctx: &mut DeploymentContext,
+    ) -> Result<Vec<ChangesetModel>> {
+        let deployment = self.repo.get_by_id(deployment_id).await?;
+        let plan = self.plan_repo.get_by_id(deployment.plan_id).await?;
+        let client = self.get_client(plan.target_connection_id).await?;
+
+        let changesets: Vec<ChangesetModel> = self
+            .changeset_repo
+            .find_by_deployment_id(deployment_id)
+            .await?
+            .into_iter()
+            .filter(|c| c.status == ChangesetStatus::Success)
+            .collect();
+
+        ctx.progress(ProgressEvent::PhaseStarted {
+            phase: Phase::Verify,
+        });
+        let mut mismatched = Vec::new();
+        for (i, changeset) in changesets.into_iter().enumerate() {
+            ctx.progress(format!(
+                "Verifying object {} '{} {}.{}'",
+                i + 1,
+                changeset.object_type,
+                changeset.object_owner,
+                changeset.object_name
+            ));
+            let actual_ddl = client.find_ddl(
+                &changeset.object_type,
+                &changeset.object_name,
+                &changeset.object_owner,
+            )?;
+
+            let status = if ddl_matches(changeset.source_ddl.as_deref(), actual_ddl.as_deref()) {
+                VerificationStatus::Verified
+            } else {
+                VerificationStatus::Mismatch
+            };
+
+            let mut changeset_active = changeset.into_active_model();
+            changeset_active.set_verification(status.clone());
+            let changeset = self
+                .changeset_repo
+                .save_changeset(&changeset_active)
+                .await?;
+
+            if status == VerificationStatus::Mismatch {
+                mismatched.push(changeset);
+            }
+        }
+        ctx.progress(ProgressEvent::PhaseFinished {
+            phase: Phase::Verify,
+            success: mismatched.is_empty(),
+        });
+
+        Ok(mismatched)
+    }
+
     pub async fn find_last_deployment_by_plan_id(
         &self,
         plan_id: i32,
@@ -569,21 +1364,124 @@ impl DeploymentService {
             .await
     }
 
+    /// Every changeset that touched an object across all plans and deployments, oldest
+    /// first. All object types with the name are returned if `object_type` is not set.
+    pub async fn find_object_history(
+        &self,
+        object_owner: &str,
+        object_name: &str,
+        object_type: Option<&str>,
+    ) -> Result<Vec<ObjectVersion>> {
+        let changesets = self
+            .changeset_repo
+            .find_by_object_with_changes(object_owner, object_name, object_type)
+            .await?;
+
+        let mut deployments: HashMap<i32, DeploymentModel> = HashMap::new();
+        let mut plan_names: HashMap<i32, String> = HashMap::new();
+        let mut versions = Vec::with_capacity(changesets.len());
+        for (i, (changeset, changes)) in changesets.into_iter().enumerate() {
+            if !deployments.contains_key(&changeset.deployment_id) {
+                let deployment = self.repo.get_by_id(changeset.deployment_id).await?;
+                deployments.insert(deployment.id, deployment);
+            }
+            let deployment = deployments[&changeset.deployment_id].clone();
+            if !plan_names.contains_key(&deployment.plan_id) {
+                let plan = self.plan_repo.get_by_id(deployment.plan_id).await?;
+                plan_names.insert(plan.id, plan.name);
+            }
+            versions.push(ObjectVersion {
+                version: i + 1,
+                plan_name: plan_names[&deployment.plan_id].clone(),
+                changeset,
+                changes,
+                deployment,
+            });
+        }
+        Ok(versions)
+    }
+
+    /// Builds a report of a deployment from its stored changesets, hooks and rollbacks.
+    pub async fn report(&self, deployment_id: i32) -> Result<DeploymentReport> {
+        let deployment = self.repo.get_by_id(deployment_id).await?;
+        let (plan, changesets, rollbacks) = try_join!(
+            self.plan_repo.get_by_id(deployment.plan_id),
+            self.changeset_repo
+                .get_by_deployment_id_with_changes(deployment_id),
+            self.rollback_repo.get_by_deployment_id(deployment_id),
+        )?;
+
+        DeploymentReport::build(&deployment, &plan.name, changesets, rollbacks)
+    }
+
+    /// Compares the changesets and change scripts of two deployments of the same plan.
+    pub async fn diff(&self, a_deployment_id: i32, b_deployment_id: i32) -> Result<DeploymentDiff> {
+        let (a, b) = try_join!(
+            self.repo.get_by_id(a_deployment_id),
+            self.repo.get_by_id(b_deployment_id),
+        )?;
+        ensure!(
+            a.plan_id == b.plan_id,
+            "Deployments {} and {} belong to different plans ({} and {}), only deployments of the same plan can be compared",
+            a.id,
+            b.id,
+            a.plan_id,
+            b.plan_id
+        );
+        let (a_changesets, b_changesets) = try_join!(
+            self.changeset_repo.find_by_deployment_id_with_changes(a.id),
+            self.changeset_repo.find_by_deployment_id_with_changes(b.id),
+        )?;
+
+        Ok(DeploymentDiff::build(
+            a.id,
+            a_changesets.unwrap_or_default(),
+            b.id,
+            b_changesets.unwrap_or_default(),
+        ))
+    }
+
+    /// Returns the changesets of a deployment with their changes in rollback order.
+    /// Changesets are reversed to keep dependencies, changes keep their order.
+    async fn find_changesets_in_rollback_order(
+        &self,
+        deployment_id: i32,
+    ) -> Result<Vec<(ChangesetModel, Vec<ChangeModel>)>> {
+        // Changes that never started have nothing to revert
+        Ok(self
+            .changeset_repo
+            .find_by_deployment_id_with_changes(deployment_id)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .map(|(changeset, changes)| {
+                let changes: Vec<ChangeModel> = changes
+                    .into_iter()
+                    .filter(|c| !matches!(c.status, ChangeStatus::Idle | ChangeStatus::Cancelled))
+                    .collect();
+                (changeset, changes)
+            })
+            .filter(|(_, changes)| !changes.is_empty())
+            .collect())
+    }
+
     async fn prepare_rollback(
         &self,
         deployment_id: i32,
+        operation_id: &str,
         progress: &ProgressReporter,
     ) -> Result<Option<u64>> {
-        let plan = self.plan_repo.get_by_id(deployment_id).await?;
+        let deployment = self.repo.get_by_id(deployment_id).await?;
+        let plan = self.plan_repo.get_by_id(deployment.plan_id).await?;
         progress.report(format!(
             "Preparing rollback for deployment {} for plan '{}' ...",
             deployment_id, plan.name
         ));
         let changesets_with_changes = self
-            .changeset_repo
-            .find_by_deployment_id_with_changes(deployment_id)
+            .find_changesets_in_rollback_order(deployment_id)
             .await?;
-        if changesets_with_changes.is_none() {
+        if changesets_with_changes.is_empty() {
             progress.report(format!("No changes found for deployment {}", deployment_id));
             return Ok(None);
         }
@@ -591,8 +1489,7 @@ impl DeploymentService {
         progress.report(format!("Creating rollback actions..."));
 
         let mut change_count = 0;
-        // rollbacks are saved in reverse change order to keep dependencies.
-        for (changeset, changes) in changesets_with_changes.unwrap().into_iter().rev() {
+        for (changeset, changes) in changesets_with_changes {
             for change in &changes {
                 // Add & here to borrow instead of move
                 progress.report(format!(
@@ -601,7 +1498,11 @@ impl DeploymentService {
                 ));
 
                 self.rollback_repo
-                    .create(change.id, change.rollback_script.clone())
+                    .create(
+                        change.id,
+                        change.rollback_script.clone(),
+                        Some(operation_id),
+                    )
                     .await?;
             }
             change_count += changes.len() as u64;
@@ -610,6 +1511,53 @@ impl DeploymentService {
         Ok(Some(change_count))
     }
 
+    /// Writes the rollback scripts of a deployment in execution order and a summary of
+    /// the affected objects to the context. Neither the target nor the repository is touched.
+    async fn collect_rollback_scripts(
+        &self,
+        deployment: &DeploymentModel,
+        ctx: &mut DeploymentContext,
+    ) -> Result<u64> {
+        let changesets_with_changes = self
+            .find_changesets_in_rollback_order(deployment.id)
+            .await?;
+        let change_count: usize = changesets_with_changes
+            .iter()
+            .map(|(_, changes)| changes.len())
+            .sum();
+
+        ctx.write_summary(&format!(
+            "Deployment {}{}: {} object(s), {} rollback script(s)",
+            deployment.id,
+            deployment
+                .tag
+                .as_ref()
+                .map(|t| format!(" ({})", t))
+                .unwrap_or_default(),
+            changesets_with_changes.len(),
+            change_count
+        ))?;
+
+        for (changeset, changes) in &changesets_with_changes {
+            ctx.progress(format!(
+                "Collecting rollback for '{} {}.{}'",
+                changeset.object_type, changeset.object_owner, changeset.object_name
+            ));
+            for change in changes {
+                ctx.write_rollback_script(&change.rollback_script)?;
+            }
+            ctx.write_summary(&format!(
+                "  {} {}.{} ({} script(s))",
+                changeset.object_type,
+                changeset.object_owner,
+                changeset.object_name,
+                changes.len()
+            ))?;
+        }
+
+        Ok(change_count as u64)
+    }
+
     async fn execute_rollbacks(
         &self,
         deployment_id: i32,
@@ -620,8 +1568,14 @@ impl DeploymentService {
         let plan = self.plan_repo.get_by_id(deployment.plan_id).await?;
         let client = self.get_client(plan.target_connection_id).await?;
 
-        plan.run_pre_rollback_hooks(disable_hooks, &client, progress)
+        let mut hook_executions = Vec::new();
+        let pre_hooks_result = plan
+            .run_pre_rollback_hooks(disable_hooks, &client, progress, &mut hook_executions)
+            .await;
+        self.repo
+            .add_hook_executions(deployment_id, &hook_executions)
             .await?;
+        pre_hooks_result?;
 
         let rollbacks = self
             .rollback_repo
@@ -643,6 +1597,9 @@ impl DeploymentService {
             .set_status(deployment_id, DeploymentStatus::RollingBack)
             .await?;
 
+        progress.report(ProgressEvent::PhaseStarted {
+            phase: Phase::Rollback,
+        });
         let result: Result<(), anyhow::Error> = async {
             for (i, (rollback, change, changeset)) in rollbacks.iter().enumerate() {
                 progress.report(format!(
@@ -692,12 +1649,22 @@ impl DeploymentService {
         }
         .await;
 
+        let mut hook_executions = Vec::new();
         let rollback_result = plan
-            .run_post_rollback_hooks(disable_hooks, &client, progress)
+            .run_post_rollback_hooks(disable_hooks, &client, progress, &mut hook_executions)
             .await;
+        self.repo
+            .add_hook_executions(deployment_id, &hook_executions)
+            .await?;
+
+        let result = rollback_result.and(result);
+        progress.report(ProgressEvent::PhaseFinished {
+            phase: Phase::Rollback,
+            success: result.is_ok(),
+        });
 
         // Handle the result and set appropriate final statuses
-        match rollback_result.and(result) {
+        match result {
             Ok(_) => {
                 self.repo
                     .set_status(deployment_id, DeploymentStatus::RolledBack)
@@ -718,13 +1685,23 @@ impl DeploymentService {
 
     async fn rollback_by_deployment_id(
         &self,
-        deployment_id: i32,
+        deployment: &DeploymentModel,
+        operation_id: &str,
         disable_hooks: Option<bool>,
-        progress: ProgressReporter,
+        ctx: &mut DeploymentContext,
     ) -> Result<()> {
-        match self.prepare_rollback(deployment_id, &progress).await? {
+        self.collect_rollback_scripts(deployment, ctx).await?;
+        if ctx.is_dry_run() {
+            return Ok(());
+        }
+
+        let progress = ctx.progress_reporter();
+        match self
+            .prepare_rollback(deployment.id, operation_id, progress)
+            .await?
+        {
             Some(change_count) if change_count > 0 => {
-                self.execute_rollbacks(deployment_id, disable_hooks, &progress)
+                self.execute_rollbacks(deployment.id, disable_hooks, progress)
                     .await?;
                 Ok(())
             }
@@ -735,19 +1712,351 @@ impl DeploymentService {
         }
     }
 
+    /// Rolls back the last deployment of the plan if it was interrupted or cancelled, otherwise
+    /// the last successful one.
+    ///
+    /// In dry-run mode the rollback scripts are only written to the context.
     pub async fn rollback(
         &self,
         plan_id: i32,
         disable_hooks: Option<bool>,
-        progress: ProgressReporter,
+        ctx: &mut DeploymentContext,
     ) -> Result<()> {
-        let deployment = self
+        let deployment = match self.repo.find_last_by_plan_id(plan_id).await? {
+            Some(d)
+                if matches!(
+                    d.status,
+                    DeploymentStatus::Interrupted | DeploymentStatus::Cancelled
+                ) =>
+            {
+                d
+            }
+            _ => self
+                .repo
+                .find_last_successful_by_plan_id(plan_id)
+                .await?
+                .ok_or_else(|| anyhow!("No successful deployment found for plan {}", plan_id))?,
+        };
+
+        let operation_id = Uuid::new_v4().to_string();
+        let event = self
+            .audit_service
+            .start(
+                AuditOperation::Rollback,
+                Some(plan_id),
+                Some(deployment.id),
+                ctx.is_dry_run(),
+            )
+            .await?;
+        let result = async {
+            let lock = self.acquire_rollback_lock(plan_id, ctx).await?;
+            let result = self
+                .rollback_by_deployment_id(&deployment, &operation_id, disable_hooks, ctx)
+                .await;
+            match lock {
+                Some(lock) => Self::release_lock(lock, result).await,
+                None => result,
+            }
+        }
+        .await;
+        self.audit_service.finish(event, &result, None).await;
+        result
+    }
+
+    /// Dry-run rollbacks do not touch the target, so they run without a lock.
+    async fn acquire_rollback_lock(
+        &self,
+        plan_id: i32,
+        ctx: &mut DeploymentContext,
+    ) -> Result<Option<DeploymentLock>> {
+        if ctx.is_dry_run() {
+            return Ok(None);
+        }
+        let plan = self.plan_repo.get_by_id(plan_id).await?;
+        Ok(Some(self.acquire_lock(&plan, "rollback", ctx).await?))
+    }
+
+    /// Finds the deployment a plan should be rolled back to.
+    async fn resolve_rollback_target(
+        &self,
+        plan_id: i32,
+        target: &RollbackTarget,
+    ) -> Result<DeploymentModel> {
+        let deployment = match target {
+            RollbackTarget::Deployment(id) => self.repo.get_by_id(*id).await?,
+            RollbackTarget::Tag(tag) => {
+                self.repo.find_by_tag(plan_id, tag).await?.ok_or_else(|| {
+                    anyhow!("No deployment tagged '{}' found for plan {}", tag, plan_id)
+                })?
+            }
+        };
+
+        if deployment.plan_id != plan_id {
+            return Err(anyhow!(
+                "Deployment {} does not belong to plan {}",
+                deployment.id,
+                plan_id
+            ));
+        }
+        Ok(deployment)
+    }
+
+    /// Rolls back every successful deployment after the target, newest first.
+    ///
+    /// All rollbacks created by the chain share one operation ID. The chain stops at the
+    /// first failure with a [`RollbackChainError`] listing the deployments that were
+    /// already reverted. Returns the IDs of the reverted deployments in rollback order.
+    /// In dry-run mode the rollback scripts are only written to the context and the
+    /// returned IDs are the deployments that would be reverted.
+    pub async fn rollback_to(
+        &self,
+        plan_id: i32,
+        target: RollbackTarget,
+        disable_hooks: Option<bool>,
+        ctx: &mut DeploymentContext,
+    ) -> Result<Vec<i32>> {
+        let anchor = self.resolve_rollback_target(plan_id, &target).await?;
+        let deployments = self.repo.find_successful_after(plan_id, anchor.id).await?;
+
+        if deployments.is_empty() {
+            ctx.progress(format!("No successful deployments found after {}", target));
+            return Ok(Vec::new());
+        }
+
+        let event = self
+            .audit_service
+            .start(
+                AuditOperation::Rollback,
+                Some(plan_id),
+                Some(anchor.id),
+                ctx.is_dry_run(),
+            )
+            .await?;
+        let result = async {
+            let lock = self.acquire_rollback_lock(plan_id, ctx).await?;
+            let result = self
+                .rollback_chain(&deployments, &target, disable_hooks, ctx)
+                .await;
+            match lock {
+                Some(lock) => Self::release_lock(lock, result).await,
+                None => result,
+            }
+        }
+        .await;
+        self.audit_service.finish(event, &result, None).await;
+        result
+    }
+
+    async fn rollback_chain(
+        &self,
+        deployments: &[DeploymentModel],
+        target: &RollbackTarget,
+        disable_hooks: Option<bool>,
+        ctx: &mut DeploymentContext,
+    ) -> Result<Vec<i32>> {
+        let operation_id = Uuid::new_v4().to_string();
+        ctx.progress(format!(
+            "Rolling back {} deployment(s) to {} (operation {})",
+            deployments.len(),
+            target,
+            operation_id
+        ));
+
+        let mut reverted = Vec::new();
+        for (i, deployment) in deployments.iter().enumerate() {
+            ctx.progress(format!(
+                "Rolling back deployment {} ({} of {})",
+                deployment.id,
+                i + 1,
+                deployments.len()
+            ));
+
+            if let Err(e) = self
+                .rollback_by_deployment_id(deployment, &operation_id, disable_hooks, ctx)
+                .await
+            {
+                return Err(RollbackChainError {
+                    reverted,
+                    failed: deployment.id,
+                    skipped: deployments[i + 1..].iter().map(|d| d.id).collect(),
+                    message: e.to_string(),
+                }
+                .into());
+            }
+            reverted.push(deployment.id);
+        }
+
+        Ok(reverted)
+    }
+
+    /// Keeps the heartbeat of a running deployment fresh until the returned task is aborted.
+    fn spawn_heartbeat(&self, deployment_id: i32) -> JoinHandle<()> {
+        let repo = self.repo.clone();
+        let interval = self.lock_service.heartbeat_interval();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = repo.heartbeat(deployment_id).await {
+                    warn!(
+                        "Failed to refresh heartbeat of deployment {}: {:?}",
+                        deployment_id, e
+                    );
+                }
+            }
+        })
+    }
+
+    /// Stops the operation of this process on the plan right away: its RUNNING deployments,
+    /// changesets and changes and the plan are marked `INTERRUPTED` and its locks are released.
+    ///
+    /// Called on a second Ctrl-C, the statement in flight is left to the database.
+    pub async fn abort(&self, plan_id: i32) -> Result<()> {
+        let pid = std::process::id() as i32;
+        for deployment in self.repo.get_by_plan_id(plan_id).await? {
+            if deployment.status != DeploymentStatus::Running || deployment.owner_pid != Some(pid) {
+                continue;
+            }
+            self.change_repo
+                .set_running_status_by_deployment_id(deployment.id, ChangeStatus::Interrupted)
+                .await?;
+            self.changeset_repo
+                .set_running_status_by_deployment_id(deployment.id, ChangesetStatus::Interrupted)
+                .await?;
+            self.repo
+                .set_status(deployment.id, DeploymentStatus::Interrupted)
+                .await?;
+        }
+        if self.plan_repo.get_by_id(plan_id).await?.status == PlanStatus::Running {
+            self.plan_repo
+                .set_status(plan_id, PlanStatus::Interrupted)
+                .await?;
+        }
+        self.lock_service.release_owned(plan_id).await?;
+        Ok(())
+    }
+
+    /// Finds RUNNING deployments whose heartbeat expired and RUNNING plans that no live
+    /// process holds a lock for.
+    pub async fn find_interrupted(&self) -> Result<InterruptedRecords> {
+        let mut deployments = Vec::new();
+        for deployment in self
             .repo
-            .find_last_successful_by_plan_id(plan_id)
+            .find_stale_running(self.lock_service.stale_before())
             .await?
-            .ok_or_else(|| anyhow!("No successful deployment found for plan {}", plan_id))?;
+        {
+            if !self.lock_service.is_plan_locked(deployment.plan_id).await? {
+                deployments.push(deployment);
+            }
+        }
+
+        let mut plans = Vec::new();
+        for plan in self.plan_repo.get_by_status(PlanStatus::Running).await? {
+            if !self.lock_service.is_plan_locked(plan.id).await? {
+                plans.push(plan);
+            }
+        }
+
+        Ok(InterruptedRecords { deployments, plans })
+    }
+
+    /// Marks the records returned by [`Self::find_interrupted`] as `INTERRUPTED`.
+    ///
+    /// Running changesets and changes of the deployments are marked as well, so the
+    /// deployment can be resumed with apply or reverted with rollback.
+    pub async fn recover_interrupted(&self) -> Result<InterruptedRecords> {
+        let records = self.find_interrupted().await?;
+
+        for deployment in &records.deployments {
+            warn!(
+                "Deployment {} run by {} (pid {}) stopped sending heartbeats, marking it INTERRUPTED",
+                deployment.id,
+                deployment.owner_host.as_deref().unwrap_or("unknown host"),
+                deployment
+                    .owner_pid
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| "unknown".to_string())
+            );
+            self.change_repo
+                .set_running_status_by_deployment_id(deployment.id, ChangeStatus::Interrupted)
+                .await?;
+            self.changeset_repo
+                .set_running_status_by_deployment_id(deployment.id, ChangesetStatus::Interrupted)
+                .await?;
+            self.repo
+                .set_status(deployment.id, DeploymentStatus::Interrupted)
+                .await?;
+        }
 
-        self.rollback_by_deployment_id(deployment.id, disable_hooks, progress)
+        for plan in &records.plans {
+            warn!(
+                "Plan '{}' is RUNNING without a live lock, marking it INTERRUPTED",
+                plan.name
+            );
+            self.plan_repo
+                .set_status(plan.id, PlanStatus::Interrupted)
+                .await?;
+        }
+
+        Ok(records)
+    }
+
+    /// Deployments outside of the retention policy, of one plan or all plans, oldest first.
+    pub async fn find_purgeable(
+        &self,
+        policy: &RetentionPolicy,
+        plan_id: Option<i32>,
+    ) -> Result<Vec<DeploymentModel>> {
+        let deployments = match plan_id {
+            Some(plan_id) => self.repo.get_by_plan_id(plan_id).await?,
+            None => self.repo.get_all().await?,
+        };
+        Ok(policy.select(&deployments, Utc::now().naive_utc()))
+    }
+
+    /// Deletes the deployments with everything recorded for them.
+    pub async fn purge(&self, deployment_ids: &[i32]) -> Result<PurgeSummary> {
+        if deployment_ids.is_empty() {
+            return Ok(PurgeSummary::default());
+        }
+        self.repo.purge(deployment_ids).await
+    }
+
+    /// Purges the deployments of all plans outside of the configured retention policy,
+    /// nothing if no policy is configured.
+    pub async fn apply_retention(&self, config: &RetentionConfig) -> Result<PurgeSummary> {
+        let Some(policy) = config.policy()? else {
+            return Ok(PurgeSummary::default());
+        };
+        let ids: Vec<i32> = self
+            .find_purgeable(&policy, None)
+            .await?
+            .iter()
+            .map(|d| d.id)
+            .collect();
+        self.purge(&ids).await
+    }
+
+    pub async fn find_by_tag(&self, plan_id: i32, tag: &str) -> Result<Option<DeploymentModel>> {
+        self.repo.find_by_tag(plan_id, tag).await
+    }
+
+    /// Sets the release tag of a deployment. Tags are unique per plan.
+    pub async fn tag_deployment(&self, deployment_id: i32, tag: &str) -> Result<DeploymentModel> {
+        let deployment = self.repo.get_by_id(deployment_id).await?;
+
+        if let Some(existing) = self.repo.find_by_tag(deployment.plan_id, tag).await?
+            && existing.id != deployment_id
+        {
+            return Err(TagInUseError {
+                tag: tag.to_string(),
+                deployment_id: existing.id,
+            }
+            .into());
+        }
+
+        self.repo
+            .set_tag(deployment_id, Some(tag.to_string()))
             .await
     }
 }
@@ -0,0 +1,178 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use anyhow::{Result, ensure};
+use chrono::NaiveDateTime;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+use crate::{
+    config::LocksConfig, entities::DeploymentLockModel, entities::PlanModel, errors::LockLostError,
+    repo::LockRepository, utils::CancelToken,
+};
+
+/// Service layer for the cross-process deployment locks
+pub struct LockService {
+    repo: Arc<LockRepository>,
+    config: LocksConfig,
+}
+
+/// A lock held by this process. The heartbeat stops when the lock is released or dropped.
+///
+/// Dropping the lock does not delete it from the repository, call [`DeploymentLock::release`].
+pub struct DeploymentLock {
+    id: i32,
+    repo: Arc<LockRepository>,
+    heartbeat: JoinHandle<()>,
+    lost: Arc<AtomicBool>,
+}
+
+impl DeploymentLock {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    /// Returns true if another process released or took over the lock while it was held.
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::SeqCst)
+    }
+
+    /// Deletes the lock, fails with [`LockLostError`] if it was lost while it was held.
+    pub async fn release(self) -> Result<()> {
+        self.heartbeat.abort();
+        if self.is_lost() {
+            return Err(LockLostError { lock_id: self.id }.into());
+        }
+        self.repo.delete(self.id).await?;
+        Ok(())
+    }
+}
+
+impl Drop for DeploymentLock {
+    fn drop(&mut self) {
+        self.heartbeat.abort();
+    }
+}
+
+impl LockService {
+    pub fn new(repo: Arc<LockRepository>, config: LocksConfig) -> Self {
+        Self { repo, config }
+    }
+
+    /// Locks the plan and its target connection for `operation` and keeps the lock alive
+    /// with a heartbeat until it is released. When another process releases or takes over
+    /// the lock, `cancel` is cancelled so the operation stops before its next statement.
+    pub async fn acquire(
+        &self,
+        plan: &PlanModel,
+        operation: &str,
+        cancel: CancelToken,
+    ) -> Result<DeploymentLock> {
+        let lock = self
+            .repo
+            .acquire(
+                plan.id,
+                plan.target_connection_id,
+                operation,
+                std::process::id() as i32,
+                &gethostname::gethostname().to_string_lossy(),
+                self.stale_before(),
+            )
+            .await?;
+
+        let repo = self.repo.clone();
+        let interval = self.heartbeat_interval();
+        let id = lock.id;
+        let lost = Arc::new(AtomicBool::new(false));
+        let heartbeat_lost = lost.clone();
+        let heartbeat = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match repo.heartbeat(id).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        error!(
+                            "Deployment lock {} was released by another process, cancelling",
+                            id
+                        );
+                        heartbeat_lost.store(true, Ordering::SeqCst);
+                        cancel.cancel();
+                        break;
+                    }
+                    Err(e) => warn!("Failed to refresh deployment lock {}: {:?}", id, e),
+                }
+            }
+        });
+
+        Ok(DeploymentLock {
+            id,
+            repo: self.repo.clone(),
+            heartbeat,
+            lost,
+        })
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<DeploymentLockModel>> {
+        self.repo.get_all().await
+    }
+
+    /// A lock is stale when its owner did not refresh the heartbeat within `stale_after_secs`
+    pub fn is_stale(&self, lock: &DeploymentLockModel) -> bool {
+        lock.heartbeat_at < self.stale_before()
+    }
+
+    /// Deletes a lock. Locks with a live heartbeat are only released with `force`.
+    pub async fn release(&self, id: i32, force: bool) -> Result<DeploymentLockModel> {
+        let lock = self.repo.get_by_id(id).await?;
+        ensure!(
+            force || self.is_stale(&lock),
+            "Lock {} is held by {} (pid {}) with a live heartbeat, use --force to release it anyway",
+            id,
+            lock.owner_host,
+            lock.owner_pid
+        );
+        self.repo.delete(id).await?;
+        Ok(lock)
+    }
+
+    /// Deletes the locks of the plan held by this process, returns the number of locks deleted
+    pub async fn release_owned(&self, plan_id: i32) -> Result<u64> {
+        let pid = std::process::id() as i32;
+        let host = gethostname::gethostname().to_string_lossy().to_string();
+        let mut released = 0;
+        for lock in self.repo.get_all().await? {
+            if lock.plan_id == plan_id && lock.owner_pid == pid && lock.owner_host == host {
+                released += self.repo.delete(lock.id).await?;
+            }
+        }
+        Ok(released)
+    }
+
+    /// Returns true if a lock of the plan is held by a process with a live heartbeat
+    pub async fn is_plan_locked(&self, plan_id: i32) -> Result<bool> {
+        Ok(self
+            .repo
+            .get_all()
+            .await?
+            .iter()
+            .any(|lock| lock.plan_id == plan_id && !self.is_stale(lock)))
+    }
+
+    pub fn heartbeat_interval(&self) -> Duration {
+        Duration::from_secs(self.config.heartbeat_interval_secs.max(1))
+    }
+
+    /// Heartbeats older than this are expired
+    pub fn stale_before(&self) -> NaiveDateTime {
+        chrono::Utc::now().naive_utc()
+            - chrono::Duration::seconds(self.config.stale_after_secs as i64)
+    }
+}
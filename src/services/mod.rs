@@ -1,27 +1,44 @@
+pub mod audit_service;
+pub mod config_service;
 pub mod connection_service;
 pub mod deployment_service;
+pub mod lock_service;
 pub mod plan_service;
+pub mod snapshot_service;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 use std::sync::Arc;
 
-pub use connection_service::ConnectionService;
-pub use deployment_service::DeploymentService;
-pub use plan_service::PlanService;
+pub use audit_service::AuditService;
+pub use config_service::ConfigService;
+pub use connection_service::{CompareOptions, ConnectionEdit, ConnectionService};
+pub use deployment_service::{
+    DeploymentRepositories, DeploymentService, InterruptedRecords, ObjectVersion,
+};
+pub use lock_service::{DeploymentLock, LockService};
+pub use plan_service::{ListEdit, PlanEdit, PlanService, PlanSpec};
+pub use snapshot_service::{DiffOptions, SnapshotService};
 
 use crate::{
     config::Settings,
     db::init_db,
     repo::{
-        ChangeRepository, ChangesetRepository, ConnectionRepository, DeploymentRepository,
-        PlanRepository, RollbackRepository,
+        ApprovalRepository, AuditRepository, ChangeRepository, ChangesetRepository,
+        ConnectionRepository, DeploymentRepository, LockRepository, PlanRepository,
+        RollbackRepository,
     },
+    types::AuditOrigin,
+    utils::SecretStore,
 };
 
 pub struct AppServices {
-    pub plan_service: PlanService,
-    pub deployment_service: DeploymentService,
-    pub connection_service: ConnectionService,
+    pub plan_service: Arc<PlanService>,
+    pub deployment_service: Arc<DeploymentService>,
+    pub connection_service: Arc<ConnectionService>,
+    pub lock_service: Arc<LockService>,
+    pub snapshot_service: SnapshotService,
+    pub audit_service: Arc<AuditService>,
+    pub config_service: ConfigService,
 }
 
 impl AppServices {
@@ -30,6 +47,7 @@ impl AppServices {
             init_db(&settings)
                 .await
                 .context("Failed to initialize database for ConnectionRepository")?,
+            Arc::new(SecretStore::new(&settings.secrets())),
         ));
         let plan_repo = Arc::new(PlanRepository::new(
             init_db(&settings)
@@ -60,21 +78,70 @@ impl AppServices {
                 .expect("Failed to initialize database for RollbackRepository"),
         ));
 
+        let approval_repo = Arc::new(ApprovalRepository::new(
+            init_db(settings)
+                .await
+                .expect("Failed to initialize database for ApprovalRepository"),
+        ));
+
+        let audit_service = Arc::new(AuditService::new(
+            Arc::new(AuditRepository::new(
+                init_db(settings)
+                    .await
+                    .expect("Failed to initialize database for AuditRepository"),
+            )),
+            AuditOrigin::current(settings),
+        ));
+
+        let lock_service = Arc::new(LockService::new(
+            Arc::new(LockRepository::new(
+                init_db(settings)
+                    .await
+                    .expect("Failed to initialize database for LockRepository"),
+            )),
+            settings.locks.clone(),
+        ));
+
+        let plan_service = Arc::new(PlanService::new(
+            plan_repo.clone(),
+            deployment_repo.clone(),
+            connection_repo.clone(),
+            audit_service.clone(),
+        ));
+        let connection_service = Arc::new(ConnectionService::new(
+            connection_repo.clone(),
+            plan_repo.clone(),
+        ));
+
         Ok(Self {
-            plan_service: PlanService::new(
-                plan_repo.clone(),
-                deployment_repo.clone(),
-                connection_repo.clone(),
-            ),
-            connection_service: ConnectionService::new(connection_repo.clone()),
-            deployment_service: DeploymentService::new(
-                deployment_repo,
-                plan_repo.clone(),
-                connection_repo,
-                changeset_repo,
-                change_repo,
-                rollback_repo,
-            ),
+            config_service: ConfigService::new(plan_service.clone(), connection_service.clone()),
+            plan_service,
+            connection_service,
+            snapshot_service: SnapshotService::new(connection_repo.clone()),
+            deployment_service: Arc::new(DeploymentService::new(
+                DeploymentRepositories {
+                    deployments: deployment_repo,
+                    plans: plan_repo.clone(),
+                    connections: connection_repo,
+                    changesets: changeset_repo,
+                    changes: change_repo,
+                    rollbacks: rollback_repo,
+                    approvals: approval_repo,
+                },
+                lock_service.clone(),
+                audit_service.clone(),
+            )),
+            lock_service,
+            audit_service,
         })
     }
 }
+
+/// Rejects a negative number of approvals required before deployments can be applied
+pub(crate) fn ensure_required_approvals(required_approvals: i32) -> Result<()> {
+    ensure!(
+        required_approvals >= 0,
+        "Required approvals can not be negative"
+    );
+    Ok(())
+}
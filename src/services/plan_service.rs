@@ -1,18 +1,90 @@
 use std::sync::Arc;
 
 use crate::{
-    entities::plan::Model as PlanModel,
+    entities::{PlanRevisionModel, plan::Model as PlanModel},
     repo::{ConnectionRepository, DeploymentRepository, plan_repo::PlanRepository},
-    types::{Hooks, PlanStatus, StringList},
+    services::{AuditService, ensure_required_approvals},
+    types::{AuditOperation, ConnectionKind, Hooks, PlanStatus, StringList},
 };
 use anyhow::{Context, Result, anyhow, ensure};
 use chrono::NaiveDateTime;
 
+/// Values to add to and remove from a list setting of a plan
+#[derive(Debug, Clone, Default)]
+pub struct ListEdit {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl ListEdit {
+    fn apply(&self, list: Vec<String>) -> Vec<String> {
+        let mut list: Vec<String> = list
+            .into_iter()
+            .filter(|v| !self.remove.contains(v))
+            .collect();
+        for value in &self.add {
+            if !list.contains(value) {
+                list.push(value.clone());
+            }
+        }
+        list
+    }
+
+    /// Edits an optional list setting, which is left as is without values to add or remove
+    fn apply_opt(&self, list: &Option<StringList>) -> Option<StringList> {
+        if self.add.is_empty() && self.remove.is_empty() {
+            return list.clone();
+        }
+        let list = list.as_ref().map(|l| l.0.clone()).unwrap_or_default();
+        Some(self.apply(list))
+            .filter(|l| !l.is_empty())
+            .map(StringList)
+    }
+}
+
+/// Definition of a plan for [`PlanService::create`] and [`PlanService::update`]
+#[derive(Debug, Clone, Default)]
+pub struct PlanSpec {
+    pub name: String,
+    pub source: String,
+    pub target: String,
+    pub schemas: Vec<String>,
+    pub exclude_object_types: Option<Vec<String>>,
+    pub exclude_object_names: Option<Vec<String>>,
+    pub disabled_drop_types: Option<Vec<String>>,
+    pub disable_all_drops: bool,
+    pub fail_fast: bool,
+    pub disable_hooks: bool,
+    pub hooks: Option<Hooks>,
+    pub required_approvals: i32,
+}
+
+/// Changes to an existing plan for [`PlanService::edit`], fields left `None` or empty
+/// keep their value
+#[derive(Debug, Clone, Default)]
+pub struct PlanEdit {
+    pub rename: Option<String>,
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub schemas: ListEdit,
+    pub exclude_object_types: ListEdit,
+    pub exclude_object_names: ListEdit,
+    pub disabled_drop_types: ListEdit,
+    pub disable_all_drops: Option<bool>,
+    pub fail_fast: Option<bool>,
+    pub disable_hooks: Option<bool>,
+    /// Scripts appended to hook stages, as `(stage, script)`
+    pub add_hooks: Vec<(String, String)>,
+    /// Hook stages whose scripts are removed, before `add_hooks` are appended
+    pub clear_hooks: Vec<String>,
+}
+
 /// Service layer for Plan business logic
 pub struct PlanService {
     repo: Arc<PlanRepository>,
     deployment_repo: Arc<DeploymentRepository>,
     connection_repo: Arc<ConnectionRepository>,
+    audit_service: Arc<AuditService>,
 }
 
 impl PlanService {
@@ -20,36 +92,222 @@ impl PlanService {
         repo: Arc<PlanRepository>,
         deployment_repo: Arc<DeploymentRepository>,
         connection_repo: Arc<ConnectionRepository>,
+        audit_service: Arc<AuditService>,
     ) -> Self {
         Self {
             repo,
             deployment_repo,
             connection_repo,
+            audit_service,
         }
     }
 
     /// Create a new plan with business validation
-    pub async fn create(
-        &self,
-        name: &str,
-        source: &str,
-        target: &str,
-        schemas: &[String],
-        exclude_object_types: Option<Vec<String>>,
-        exclude_object_names: Option<Vec<String>>,
-        disabled_drop_types: Option<Vec<String>>,
-        disable_all_drops: bool,
-        fail_fast: bool,
-        disable_hooks: bool,
-        hooks: Option<Hooks>,
-    ) -> Result<PlanModel> {
-        if self.repo.exists_by_name(name).await? {
+    pub async fn create(&self, spec: &PlanSpec) -> Result<PlanModel> {
+        ensure_required_approvals(spec.required_approvals)?;
+        if self.repo.exists_by_name(&spec.name).await? {
             anyhow::bail!(
                 "Plan with name '{}' already exists. Plan names are case-insensitive and must be unique.",
-                name
+                spec.name
+            );
+        }
+
+        let (source_connection_id, target_connection_id) = self
+            .validate_definition(&spec.source, &spec.target, &spec.schemas)
+            .await?;
+
+        let plan = PlanModel {
+            name: spec.name.clone(),
+            source_connection_id,
+            target_connection_id,
+            schemas: StringList(spec.schemas.clone()),
+            exclude_object_types: spec.exclude_object_types.clone().map(StringList),
+            exclude_object_names: spec.exclude_object_names.clone().map(StringList),
+            disabled_drop_types: spec.disabled_drop_types.clone().map(StringList),
+            disable_all_drops: spec.disable_all_drops,
+            fail_fast: spec.fail_fast,
+            disable_hooks: spec.disable_hooks,
+            hooks: spec.hooks.as_ref().map(serde_json::to_value).transpose()?,
+            required_approvals: spec.required_approvals,
+            ..Default::default()
+        };
+        self.repo
+            .create(&plan)
+            .await
+            .context("Failed to create plan")
+    }
+
+    /// Replace the connections, schemas, rules, hooks and required approvals of an existing
+    /// plan, validated like [`PlanService::create`]. Running plans can not be updated.
+    pub async fn update(&self, spec: &PlanSpec) -> Result<PlanModel> {
+        ensure_required_approvals(spec.required_approvals)?;
+        let plan = self
+            .repo
+            .find_by_name(&spec.name)
+            .await?
+            .ok_or_else(|| anyhow!("Plan '{}' not found", spec.name))?;
+        ensure!(
+            plan.status != PlanStatus::Running,
+            "Plan '{}' is running and can not be updated",
+            spec.name
+        );
+
+        let (source_connection_id, target_connection_id) = self
+            .validate_definition(&spec.source, &spec.target, &spec.schemas)
+            .await?;
+
+        let updated = PlanModel {
+            source_connection_id,
+            target_connection_id,
+            schemas: StringList(spec.schemas.clone()),
+            exclude_object_types: spec.exclude_object_types.clone().map(StringList),
+            exclude_object_names: spec.exclude_object_names.clone().map(StringList),
+            disabled_drop_types: spec.disabled_drop_types.clone().map(StringList),
+            disable_all_drops: spec.disable_all_drops,
+            fail_fast: spec.fail_fast,
+            disable_hooks: spec.disable_hooks,
+            hooks: spec.hooks.as_ref().map(serde_json::to_value).transpose()?,
+            required_approvals: spec.required_approvals,
+            ..plan
+        };
+        self.repo
+            .update(&updated)
+            .await
+            .context(format!("Failed to update plan '{}'", spec.name))
+    }
+
+    /// Edit a plan in place, keeping its deployment history. Returns the plan and whether
+    /// anything changed, each change is recorded as a new plan revision.
+    pub async fn edit(&self, name: &str, edit: &PlanEdit) -> Result<(PlanModel, bool)> {
+        let plan = self
+            .repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| anyhow!("Plan '{}' not found", name))?;
+        ensure!(
+            plan.status != PlanStatus::Running,
+            "Plan '{}' is running and can not be updated",
+            name
+        );
+
+        if let Some(new_name) = &edit.rename {
+            ensure!(!new_name.trim().is_empty(), "Plan name can not be empty");
+            if let Some(existing) = self.repo.find_by_name(new_name).await? {
+                ensure!(
+                    existing.id == plan.id,
+                    "Plan with name '{}' already exists. Plan names are case-insensitive and must be unique.",
+                    new_name
+                );
+            }
+        }
+
+        let source = match &edit.source {
+            Some(source) => source.clone(),
+            None => {
+                self.connection_repo
+                    .get_by_id(plan.source_connection_id)
+                    .await?
+                    .name
+            }
+        };
+        let target = match &edit.target {
+            Some(target) => target.clone(),
+            None => {
+                self.connection_repo
+                    .get_by_id(plan.target_connection_id)
+                    .await?
+                    .name
+            }
+        };
+        let schemas = edit.schemas.apply(plan.get_schemas());
+        let (source_connection_id, target_connection_id) =
+            self.validate_definition(&source, &target, &schemas).await?;
+
+        let mut hooks = plan.get_hooks()?.unwrap_or_default();
+        for stage in edit
+            .clear_hooks
+            .iter()
+            .chain(edit.add_hooks.iter().map(|(stage, _)| stage))
+        {
+            ensure!(
+                hooks.stage_mut(stage).is_some(),
+                "Unknown hook stage '{}'",
+                stage
             );
         }
+        for stage in &edit.clear_hooks {
+            if let Some(scripts) = hooks.stage_mut(stage) {
+                *scripts = None;
+            }
+        }
+        for (stage, script) in &edit.add_hooks {
+            if let Some(scripts) = hooks.stage_mut(stage) {
+                scripts.get_or_insert_with(Vec::new).push(script.clone());
+            }
+        }
+        let hooks_edited = !edit.clear_hooks.is_empty() || !edit.add_hooks.is_empty();
+
+        let updated = PlanModel {
+            name: edit.rename.clone().unwrap_or_else(|| plan.name.clone()),
+            source_connection_id,
+            target_connection_id,
+            schemas: StringList(schemas),
+            exclude_object_types: edit
+                .exclude_object_types
+                .apply_opt(&plan.exclude_object_types),
+            exclude_object_names: edit
+                .exclude_object_names
+                .apply_opt(&plan.exclude_object_names),
+            disabled_drop_types: edit
+                .disabled_drop_types
+                .apply_opt(&plan.disabled_drop_types),
+            disable_all_drops: edit.disable_all_drops.unwrap_or(plan.disable_all_drops),
+            fail_fast: edit.fail_fast.unwrap_or(plan.fail_fast),
+            disable_hooks: edit.disable_hooks.unwrap_or(plan.disable_hooks),
+            hooks: if hooks_edited {
+                Some(hooks)
+                    .filter(|h| *h != Hooks::default())
+                    .map(serde_json::to_value)
+                    .transpose()?
+            } else {
+                plan.hooks.clone()
+            },
+            ..plan.clone()
+        };
+
+        if plan.changed_fields(&updated).is_empty() {
+            return Ok((plan, false));
+        }
+        let updated = self
+            .repo
+            .update(&updated)
+            .await
+            .context(format!("Failed to update plan '{}'", name))?;
+        Ok((updated, true))
+    }
 
+    /// Revisions of a plan, oldest first
+    pub async fn find_revisions(&self, name: &str) -> Result<Vec<PlanRevisionModel>> {
+        let plan = self
+            .repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| anyhow!("Plan '{}' not found", name))?;
+        self.repo.find_revisions(plan.id).await
+    }
+
+    pub async fn find_revision_by_id(&self, id: i32) -> Result<Option<PlanRevisionModel>> {
+        self.repo.find_revision_by_id(id).await
+    }
+
+    /// Checks the connections and schemas of a plan, returns the source and target
+    /// connection IDs
+    async fn validate_definition(
+        &self,
+        source: &str,
+        target: &str,
+        schemas: &[String],
+    ) -> Result<(i32, i32)> {
         // Business validation: Source connection must exist
         let source_connection = self
             .connection_repo
@@ -64,6 +322,14 @@ impl PlanService {
             .await?
             .ok_or_else(|| anyhow!("Target connection '{}' not found", target))?;
 
+        if target_connection.kind != ConnectionKind::Oracle {
+            anyhow::bail!(
+                "Target connection '{}' is a {} connection, only Oracle connections can be deployed to",
+                target,
+                target_connection.kind
+            );
+        }
+
         if source_connection.id == target_connection.id {
             anyhow::bail!("Source and target connections cannot be the same");
         }
@@ -72,22 +338,25 @@ impl PlanService {
             anyhow::bail!("At least one schema must be specified");
         }
 
+        Ok((source_connection.id, target_connection.id))
+    }
+
+    /// Sets how many distinct approvals the plan's deployments need before apply
+    pub async fn set_required_approvals(
+        &self,
+        name: &str,
+        required_approvals: i32,
+    ) -> Result<PlanModel> {
+        ensure_required_approvals(required_approvals)?;
+        let plan = self
+            .repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| anyhow!("Plan '{}' not found", name))?;
+
         self.repo
-            .create(
-                name,
-                source_connection.id,
-                target_connection.id,
-                StringList(schemas.to_vec()),
-                exclude_object_types.map(StringList),
-                exclude_object_names.map(StringList),
-                disabled_drop_types.map(StringList),
-                disable_all_drops,
-                fail_fast,
-                disable_hooks,
-                hooks,
-            )
+            .set_required_approvals(plan.id, required_approvals)
             .await
-            .context("Failed to create plan")
     }
 
     /// Update plan status by name
@@ -144,10 +413,17 @@ impl PlanService {
 
     pub async fn reset_status_by_id(&self, id: i32) -> Result<PlanModel> {
         let plan = self.repo.get_by_id(id).await?;
-        self.repo
+        let event = self
+            .audit_service
+            .start(AuditOperation::Reset, Some(id), None, false)
+            .await?;
+        let result = self
+            .repo
             .set_status(id, PlanStatus::Idle)
             .await
-            .context(format!("Failed to reset status for plan '{}'", plan.name))?;
+            .context(format!("Failed to reset status for plan '{}'", plan.name));
+        self.audit_service.finish(event, &result, None).await;
+        result?;
 
         Ok(plan)
     }
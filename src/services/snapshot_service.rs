@@ -0,0 +1,153 @@
+use std::{collections::BTreeSet, path::Path, sync::Arc};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    delta::{ddl_matches, delta::with_disabled_drop_types_excluded, find_deltas},
+    repo::ConnectionRepository,
+    snapshot::{DirectorySource, SnapshotManifest, write_snapshot},
+    source::SourceClient,
+    utils::{ConnectionSide, DeploymentContext, ProgressEvent, ProgressReporter},
+};
+
+/// Objects and drop rules of an offline diff, the plan settings of [`SnapshotService::diff`].
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+    /// Schemas to compare, all schemas of both directories if empty
+    pub schemas: Vec<String>,
+    pub exclude_object_types: Option<Vec<String>>,
+    pub exclude_object_names: Option<Vec<String>>,
+    pub disabled_drop_types: Option<Vec<String>>,
+    pub disable_all_drops: bool,
+}
+
+/// Service layer for exporting schema snapshots to a directory
+pub struct SnapshotService {
+    connection_repo: Arc<ConnectionRepository>,
+}
+
+impl SnapshotService {
+    pub fn new(connection_repo: Arc<ConnectionRepository>) -> Self {
+        Self { connection_repo }
+    }
+
+    /// Extracts every object of the schemas and writes one DDL file per object and a
+    /// manifest to `dir`.
+    pub async fn export(
+        &self,
+        connection_name: &str,
+        schemas: &[String],
+        exclude_object_types: Option<Vec<String>>,
+        exclude_object_names: Option<Vec<String>>,
+        dir: &Path,
+        progress: &ProgressReporter,
+    ) -> Result<SnapshotManifest> {
+        let connection = self
+            .connection_repo
+            .find_by_name(connection_name)
+            .await?
+            .ok_or_else(|| anyhow!("Connection '{}' not found", connection_name))?;
+
+        progress.report(format!("Connecting to '{}'...", connection.name));
+        let client = SourceClient::connect(&connection, &self.connection_repo.secrets())?;
+
+        progress.report("Fetching objects...");
+        let objects = client
+            .get_objects_with_ddls(
+                schemas.to_vec(),
+                None,
+                None,
+                exclude_object_types,
+                exclude_object_names,
+            )
+            .await?;
+        progress.report(ProgressEvent::ObjectsFetched {
+            side: ConnectionSide::Source,
+            count: objects.len(),
+        });
+
+        progress.report(format!("Writing {} objects...", objects.len()));
+        write_snapshot(dir, &connection.name, schemas, &objects)
+    }
+
+    /// Finds the changes that turn the objects of the `from` directory into the objects of
+    /// the `to` directory, without any database.
+    ///
+    /// Scripts, rollback scripts and a summary line per change go to the script writer of
+    /// `ctx`, the deltas with changes are recorded in `ctx`. Returns the number of changes.
+    pub fn diff(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: DiffOptions,
+        ctx: &mut DeploymentContext,
+    ) -> Result<usize> {
+        let from = DirectorySource::open(from)?;
+        let to = DirectorySource::open(to)?;
+
+        let schemas = if options.schemas.is_empty() {
+            let mut schemas = BTreeSet::new();
+            schemas.extend(from.schemas()?);
+            schemas.extend(to.schemas()?);
+            schemas.into_iter().collect()
+        } else {
+            options.schemas
+        };
+
+        ctx.progress(format!("Reading {}...", to.dir().display()));
+        let sources = to.get_objects_with_ddls(
+            &schemas,
+            None,
+            None,
+            options.exclude_object_types.clone(),
+            options.exclude_object_names.clone(),
+        )?;
+        ctx.progress(ProgressEvent::ObjectsFetched {
+            side: ConnectionSide::Source,
+            count: sources.len(),
+        });
+
+        ctx.progress(format!("Reading {}...", from.dir().display()));
+        let targets = from.get_objects_with_ddls(
+            &schemas,
+            None,
+            None,
+            options.exclude_object_types,
+            options.exclude_object_names,
+        )?;
+        ctx.progress(ProgressEvent::ObjectsFetched {
+            side: ConnectionSide::Target,
+            count: targets.len(),
+        });
+
+        ctx.progress("Finding deltas...");
+        let mut deltas = find_deltas(sources, targets, options.disable_all_drops);
+        if !options.disable_all_drops {
+            deltas = with_disabled_drop_types_excluded(deltas, options.disabled_drop_types);
+        }
+
+        let mut count = 0;
+        for delta in &deltas {
+            // Formatting-only changes between two exports are not differences
+            if ddl_matches(delta.source_ddl.as_deref(), delta.target_ddl.as_deref())
+                || delta.scripts.is_empty()
+            {
+                continue;
+            }
+            count += 1;
+            ctx.record_delta(delta);
+            for (script, rollback) in delta.scripts.iter().zip(&delta.rollback_scripts) {
+                ctx.write_script(script)?;
+                ctx.write_rollback_script(rollback)?;
+            }
+            ctx.write_summary(&format!(
+                "{} {} {}.{}",
+                delta.operation().to_string().to_uppercase(),
+                delta.object_type,
+                delta.object_owner,
+                delta.object_name
+            ))?;
+        }
+        Ok(count)
+    }
+}
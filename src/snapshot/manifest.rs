@@ -0,0 +1,76 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result, ensure};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// File name of the manifest written to the root of a snapshot directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Version of the [`SnapshotManifest`] layout, bumped on breaking changes.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Index of a snapshot directory, one entry per object file.
+///
+/// The manifest has no generation time, so exporting an unchanged schema produces
+/// identical files.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    /// Connection the snapshot was taken from
+    pub connection: String,
+    pub schemas: Vec<String>,
+    pub objects: Vec<SnapshotEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub owner: String,
+    pub object_type: String,
+    pub object_name: String,
+    /// Path of the DDL file relative to the snapshot directory, always with `/` separators
+    pub path: String,
+    pub last_ddl_time: NaiveDateTime,
+    /// SHA-256 of the file content
+    pub sha256: String,
+}
+
+impl SnapshotEntry {
+    /// File of the entry in the snapshot directory `dir`. Manifests can be edited by hand
+    /// or come from a checkout, paths that are absolute or leave the directory are rejected.
+    pub fn file(&self, dir: &Path) -> Result<PathBuf> {
+        let path = Path::new(&self.path);
+        ensure!(
+            !self.path.is_empty() && path.components().all(|c| matches!(c, Component::Normal(_))),
+            "Invalid path '{}' of {} {}.{} in {}, paths must be relative to the snapshot directory",
+            self.path,
+            self.object_type,
+            self.owner,
+            self.object_name,
+            MANIFEST_FILE
+        );
+        Ok(dir.join(path))
+    }
+}
+
+impl SnapshotManifest {
+    /// Reads the manifest of a snapshot directory, `None` if there is none.
+    pub fn read(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            std::fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+        let manifest = serde_json::from_str(&content)
+            .context(format!("Failed to parse {}", path.display()))?;
+        Ok(Some(manifest))
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(MANIFEST_FILE);
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, format!("{}\n", content))
+            .context(format!("Failed to write {}", path.display()))
+    }
+}
@@ -0,0 +1,9 @@
+mod manifest;
+mod reader;
+mod timestamps;
+mod writer;
+
+pub use manifest::{MANIFEST_FILE, MANIFEST_VERSION, SnapshotEntry, SnapshotManifest};
+pub use reader::DirectorySource;
+pub use timestamps::FileTimestamps;
+pub use writer::{normalize_snapshot_ddl, object_path, write_snapshot};
@@ -0,0 +1,383 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, ensure};
+use chrono::NaiveDateTime;
+
+use crate::{
+    snapshot::{FileTimestamps, MANIFEST_FILE, SnapshotManifest},
+    types::Object,
+};
+
+/// A directory of DDL files laid out like a snapshot, read as the source of a plan.
+///
+/// Objects are taken from the manifest when there is one, so names that were changed to
+/// build file names keep their original spelling. `.sql` files that are not listed in
+/// the manifest are added from their `SCHEMA/TYPE/NAME.sql` path, and listed files that
+/// were deleted are treated as dropped objects.
+#[derive(Debug, Clone)]
+pub struct DirectorySource {
+    dir: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn open(dir: &Path) -> Result<Self> {
+        ensure!(dir.is_dir(), "Directory '{}' does not exist", dir.display());
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Schemas of the directory, the top level directories.
+    pub fn schemas(&self) -> Result<Vec<String>> {
+        let mut schemas = BTreeSet::new();
+        for entry in read_dir(&self.dir)? {
+            if entry.is_dir() {
+                schemas.insert(file_name(&entry));
+            }
+        }
+        Ok(schemas.into_iter().collect())
+    }
+
+    /// Reads the objects of the schemas, with the same filters as the database query:
+    /// only objects changed on or after the day of `cutoff_date`, only `object_types` if
+    /// given and no excluded types, excluded names or `SYS_` objects.
+    pub fn get_objects_with_ddls(
+        &self,
+        schemas: &[String],
+        cutoff_date: Option<NaiveDateTime>,
+        object_types: Option<Vec<String>>,
+        exclude_object_types: Option<Vec<String>>,
+        exclude_object_names: Option<Vec<String>>,
+    ) -> Result<Vec<Object>> {
+        let exclude_object_types = exclude_object_types.unwrap_or_default();
+        let exclude_object_names = exclude_object_names.unwrap_or_default();
+        let cutoff_date = cutoff_date.map(|d| d.date().and_time(Default::default()));
+        let timestamps = FileTimestamps::load(&self.dir);
+        let root = self.dir.canonicalize().context(format!(
+            "Failed to resolve directory {}",
+            self.dir.display()
+        ))?;
+
+        let mut objects = Vec::new();
+        for (owner, object_type, object_name, path) in self.list_files()? {
+            if !schemas.contains(&owner)
+                || object_types
+                    .as_ref()
+                    .is_some_and(|types| !types.contains(&object_type))
+                || exclude_object_types.contains(&object_type)
+                || exclude_object_names.contains(&object_name)
+                || object_name.starts_with("SYS_")
+            {
+                continue;
+            }
+            let last_ddl_time = timestamps.get(&self.dir, &path)?;
+            if cutoff_date.is_some_and(|cutoff| last_ddl_time < cutoff) {
+                continue;
+            }
+            let file = self.dir.join(&path);
+            // Symbolic links of a checkout must not point outside of it either
+            let resolved = file
+                .canonicalize()
+                .context(format!("Failed to resolve {}", file.display()))?;
+            ensure!(
+                resolved.starts_with(&root),
+                "{} points outside of {}",
+                file.display(),
+                self.dir.display()
+            );
+            let ddl = std::fs::read_to_string(&file)
+                .context(format!("Failed to read {}", file.display()))?;
+            objects.push(Object {
+                owner,
+                object_name,
+                object_type,
+                last_ddl_time,
+                ddl: Some(ddl),
+            });
+        }
+        Ok(objects)
+    }
+
+    /// Owner, type, name and relative path of every object file.
+    fn list_files(&self) -> Result<Vec<(String, String, String, String)>> {
+        let mut files = Vec::new();
+        let mut listed = HashSet::new();
+        if let Some(manifest) = SnapshotManifest::read(&self.dir)? {
+            for entry in manifest.objects {
+                let file = entry.file(&self.dir)?;
+                listed.insert(entry.path.clone());
+                if file.is_file() {
+                    files.push((
+                        entry.owner,
+                        entry.object_type,
+                        entry.object_name,
+                        entry.path,
+                    ));
+                }
+            }
+        }
+
+        for schema_dir in read_dir(&self.dir)? {
+            if !schema_dir.is_dir() {
+                continue;
+            }
+            for type_dir in read_dir(&schema_dir)? {
+                if !type_dir.is_dir() {
+                    continue;
+                }
+                for file in read_dir(&type_dir)? {
+                    let Some(object_name) = file
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .and_then(|n| n.strip_suffix(".sql"))
+                    else {
+                        continue;
+                    };
+                    let owner = file_name(&schema_dir);
+                    let type_dir_name = file_name(&type_dir);
+                    let path = format!("{}/{}/{}.sql", owner, type_dir_name, object_name);
+                    if !file.is_file() || listed.contains(&path) {
+                        continue;
+                    }
+                    files.push((
+                        owner,
+                        object_type_from_dir(&type_dir_name),
+                        object_name.to_string(),
+                        path,
+                    ));
+                }
+            }
+        }
+        files.sort_by(|a, b| (type_rank(&a.1), &a.3).cmp(&(type_rank(&b.1), &b.3)));
+        Ok(files)
+    }
+}
+
+/// Deployment order of object types, the same as the `rank_id` of the objects query:
+/// tables first, then constraints, indexes, sequences and types.
+fn type_rank(object_type: &str) -> u8 {
+    match object_type {
+        "TABLE" => 10,
+        "CONSTRAINT" => 12,
+        "REF_CONSTRAINT" => 13,
+        "INDEX" => 20,
+        "SEQUENCE" => 30,
+        "TYPE" => 40,
+        _ => 100,
+    }
+}
+
+/// Reverses the type directory naming of [`object_path`](crate::snapshot::object_path).
+/// `REF_CONSTRAINT` is the only object type that has an underscore in its name.
+fn object_type_from_dir(name: &str) -> String {
+    if name == "REF_CONSTRAINT" {
+        name.to_string()
+    } else {
+        name.replace('_', " ")
+    }
+}
+
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = std::fs::read_dir(dir)
+        .context(format!("Failed to read directory {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .context(format!("Failed to read directory {}", dir.display()))?;
+    entries.retain(|p| {
+        p.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| !n.starts_with('.') && n != MANIFEST_FILE)
+    });
+    Ok(entries)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::write_snapshot;
+    use chrono::NaiveDate;
+
+    fn object(object_type: &str, name: &str, ddl: &str) -> Object {
+        Object {
+            owner: "HR".to_string(),
+            object_name: name.to_string(),
+            object_type: object_type.to_string(),
+            last_ddl_time: NaiveDate::from_ymd_opt(2025, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            ddl: Some(ddl.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_read_directory_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let schemas = vec!["HR".to_string()];
+        write_snapshot(
+            dir.path(),
+            "dev",
+            &schemas,
+            &[
+                object("PACKAGE BODY", "PKG/A", "CREATE PACKAGE BODY \"PKG/A\""),
+                object("TABLE", "EMP", "CREATE TABLE EMP (ID NUMBER)"),
+            ],
+        )
+        .unwrap();
+        // A file added by hand and a deleted one
+        std::fs::create_dir_all(dir.path().join("HR/MATERIALIZED_VIEW")).unwrap();
+        std::fs::write(
+            dir.path().join("HR/MATERIALIZED_VIEW/MV.sql"),
+            "CREATE MATERIALIZED VIEW MV AS SELECT 1 FROM DUAL",
+        )
+        .unwrap();
+        std::fs::remove_file(dir.path().join("HR/TABLE/EMP.sql")).unwrap();
+
+        let source = DirectorySource::open(dir.path()).unwrap();
+        let objects = source
+            .get_objects_with_ddls(&schemas, None, None, None, None)
+            .unwrap();
+
+        let names: Vec<(&str, &str)> = objects
+            .iter()
+            .map(|o| (o.object_type.as_str(), o.object_name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("MATERIALIZED VIEW", "MV"), ("PACKAGE BODY", "PKG/A")]
+        );
+        assert_eq!(source.schemas().unwrap(), schemas);
+    }
+
+    #[test]
+    fn test_read_directory_source_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let schemas = vec!["HR".to_string()];
+        write_snapshot(
+            dir.path(),
+            "dev",
+            &schemas,
+            &[
+                object("VIEW", "V_EMP", "CREATE VIEW V_EMP AS SELECT 1 FROM DUAL"),
+                object("TABLE", "EMP", "CREATE TABLE EMP (ID NUMBER)"),
+                object("TABLE", "SYS_EXPORT", "CREATE TABLE SYS_EXPORT (ID NUMBER)"),
+            ],
+        )
+        .unwrap();
+        let source = DirectorySource::open(dir.path()).unwrap();
+
+        let objects = source
+            .get_objects_with_ddls(&schemas, None, None, Some(vec!["VIEW".to_string()]), None)
+            .unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].object_name, "EMP");
+
+        let objects = source
+            .get_objects_with_ddls(&schemas, None, Some(vec!["VIEW".to_string()]), None, None)
+            .unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].object_name, "V_EMP");
+
+        // Tables come first, like in the database query
+        let objects = source
+            .get_objects_with_ddls(&schemas, None, None, None, None)
+            .unwrap();
+        assert_eq!(objects[0].object_type, "TABLE");
+
+        let future = NaiveDate::from_ymd_opt(2999, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let objects = source
+            .get_objects_with_ddls(&schemas, Some(future), None, None, None)
+            .unwrap();
+        assert!(objects.is_empty());
+
+        let objects = source
+            .get_objects_with_ddls(&["SALES".to_string()], None, None, None, None)
+            .unwrap();
+        assert!(objects.is_empty());
+    }
+
+    #[test]
+    fn test_directory_source_matches_raw_ddl() {
+        let dir = tempfile::tempdir().unwrap();
+        let schemas = vec!["HR".to_string()];
+        // DDL as returned by dbms_metadata, with surrounding blank lines and indentation
+        let targets = vec![
+            object(
+                "TABLE",
+                "EMP",
+                "\n  CREATE TABLE \"HR\".\"EMP\" \n   (\t\"ID\" NUMBER\n   ) ",
+            ),
+            object(
+                "VIEW",
+                "V_EMP",
+                "\n  CREATE OR REPLACE FORCE VIEW \"HR\".\"V_EMP\" AS \n  SELECT 'a  b' AS X FROM DUAL  ",
+            ),
+        ];
+        write_snapshot(dir.path(), "dev", &schemas, &targets).unwrap();
+
+        let sources = DirectorySource::open(dir.path())
+            .unwrap()
+            .get_objects_with_ddls(&schemas, None, None, None, None)
+            .unwrap();
+        assert_ne!(sources[1].ddl, targets[1].ddl);
+
+        let deltas = crate::delta::find_deltas(sources, targets, false);
+        assert_eq!(deltas.len(), 2);
+        assert!(deltas.iter().all(|d| d.scripts.is_empty()));
+    }
+
+    #[test]
+    fn test_read_directory_source_rejects_paths_outside_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("checkout");
+        let schemas = vec!["HR".to_string()];
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(root.path().join("secret.sql"), "secret").unwrap();
+        let mut manifest = write_snapshot(
+            &dir,
+            "dev",
+            &schemas,
+            &[object("TABLE", "EMP", "CREATE TABLE EMP (ID NUMBER)")],
+        )
+        .unwrap();
+        manifest.objects[0].path = "../secret.sql".to_string();
+        manifest.write(&dir).unwrap();
+
+        let source = DirectorySource::open(&dir).unwrap();
+        let err = source
+            .get_objects_with_ddls(&schemas, None, None, None, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("../secret.sql"));
+
+        #[cfg(unix)]
+        {
+            std::fs::remove_file(dir.join(MANIFEST_FILE)).unwrap();
+            std::fs::remove_file(dir.join("HR/TABLE/EMP.sql")).unwrap();
+            std::os::unix::fs::symlink(
+                root.path().join("secret.sql"),
+                dir.join("HR/TABLE/EMP.sql"),
+            )
+            .unwrap();
+            let err = source
+                .get_objects_with_ddls(&schemas, None, None, None, None)
+                .unwrap_err();
+            assert!(err.to_string().contains("points outside"));
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::{collections::HashMap, path::Path, process::Command};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime};
+use tracing::debug;
+
+/// Change times of the files of a snapshot directory, keyed by their relative path.
+///
+/// Files of a git checkout get the time of the last commit that touched them. Files that
+/// are untracked or have uncommitted changes, and all files outside of a git checkout,
+/// fall back to their modification time. Times are local, like `LAST_DDL_TIME`.
+pub struct FileTimestamps {
+    commits: HashMap<String, NaiveDateTime>,
+}
+
+impl FileTimestamps {
+    pub fn load(dir: &Path) -> Self {
+        let commits = match git_commit_times(dir) {
+            Ok(commits) => commits,
+            Err(e) => {
+                debug!("Using file modification times for {}: {}", dir.display(), e);
+                HashMap::new()
+            }
+        };
+        Self { commits }
+    }
+
+    pub fn get(&self, dir: &Path, path: &str) -> Result<NaiveDateTime> {
+        if let Some(time) = self.commits.get(path) {
+            return Ok(*time);
+        }
+        let file = dir.join(path);
+        let modified = std::fs::metadata(&file)
+            .and_then(|m| m.modified())
+            .context(format!(
+                "Failed to read modification time of {}",
+                file.display()
+            ))?;
+        Ok(DateTime::<Local>::from(modified).naive_local())
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        // Keep non-ASCII file names unquoted so they match the paths of the directory
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn git_commit_times(dir: &Path) -> Result<HashMap<String, NaiveDateTime>> {
+    let log = git(
+        dir,
+        &[
+            "log",
+            "--format=%x00%ct",
+            "--name-only",
+            "--relative",
+            "--",
+            ".",
+        ],
+    )?;
+    let mut commits = parse_git_log(&log);
+
+    // Uncommitted changes are newer than their last commit
+    let changed = git(
+        dir,
+        &["diff", "--name-only", "--relative", "HEAD", "--", "."],
+    )?;
+    for path in changed.lines() {
+        commits.remove(path);
+    }
+    Ok(commits)
+}
+
+/// Parses `git log --format=%x00%ct --name-only`, newest commit first, into the time of
+/// the last commit of each file.
+fn parse_git_log(log: &str) -> HashMap<String, NaiveDateTime> {
+    let mut commits = HashMap::new();
+    let mut current = None;
+    for line in log.lines() {
+        if let Some(timestamp) = line.strip_prefix('\0') {
+            current = timestamp
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .and_then(|t| DateTime::from_timestamp(t, 0))
+                .map(|t| t.with_timezone(&Local).naive_local());
+        } else if !line.is_empty()
+            && let Some(time) = current
+        {
+            commits.entry(line.to_string()).or_insert(time);
+        }
+    }
+    commits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_log_keeps_last_commit() {
+        let log = "\u{0}200\n\nHR/VIEW/V.sql\n\u{0}100\n\nHR/VIEW/V.sql\nHR/TABLE/T.sql\n";
+
+        let commits = parse_git_log(log);
+
+        let time = |t| {
+            DateTime::from_timestamp(t, 0)
+                .unwrap()
+                .with_timezone(&Local)
+                .naive_local()
+        };
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits["HR/VIEW/V.sql"], time(200));
+        assert_eq!(commits["HR/TABLE/T.sql"], time(100));
+    }
+}
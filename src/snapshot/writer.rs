@@ -0,0 +1,275 @@
+use std::{collections::HashSet, path::Path};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    snapshot::{MANIFEST_VERSION, SnapshotEntry, SnapshotManifest},
+    types::Object,
+    utils::sha256_hex,
+};
+
+/// Relative path of an object file, `SCHEMA/TYPE/NAME.sql`.
+///
+/// Spaces in the object type become underscores (`PACKAGE BODY` is written to
+/// `PACKAGE_BODY`) and characters that are not valid in file names are replaced, so
+/// different objects can map to the same path, see [`write_snapshot`].
+pub fn object_path(owner: &str, object_type: &str, object_name: &str) -> String {
+    format!(
+        "{}/{}/{}.sql",
+        sanitize(owner),
+        sanitize(&object_type.replace(' ', "_")),
+        sanitize(object_name)
+    )
+}
+
+fn sanitize(part: &str) -> String {
+    // `.` and `..` are valid quoted Oracle names but would leave the snapshot directory
+    if part == "." || part == ".." {
+        return part.replace('.', "_");
+    }
+    part.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Makes `path` unique among `used`, comparing case-insensitively because Windows and
+/// macOS file systems do. A taken path gets a `~2`, `~3`, ... suffix before `.sql`.
+fn unique_path(path: String, used: &mut HashSet<String>) -> String {
+    let stem = path.strip_suffix(".sql").unwrap_or(&path).to_string();
+    let mut candidate = path;
+    let mut n = 1;
+    while !used.insert(candidate.to_lowercase()) {
+        n += 1;
+        candidate = format!("{}~{}.sql", stem, n);
+    }
+    candidate
+}
+
+/// Normalizes a DDL for a snapshot file, keeping its line structure so changes show up
+/// as readable diffs: line endings become `\n`, trailing whitespace and surrounding blank
+/// lines are removed and the file ends with a single newline.
+pub fn normalize_snapshot_ddl(ddl: &str) -> String {
+    let lines: Vec<&str> = ddl
+        .lines()
+        .map(|line| line.trim_end())
+        .skip_while(|line| line.is_empty())
+        .collect();
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |i| i + 1);
+    format!("{}\n", lines[..end].join("\n"))
+}
+
+/// Writes one file per object and the manifest to `dir`.
+///
+/// Objects without a DDL are skipped. Objects whose [`object_path`] collides with the
+/// path of another object, like `A/B` and `A_B` or `EMP` and `Emp`, get a numbered
+/// suffix in the order of owner, type and name; the manifest maps each file back to its
+/// object. Files listed in a previous manifest of the directory that are no longer part
+/// of the snapshot are removed, other files are left alone.
+pub fn write_snapshot(
+    dir: &Path,
+    connection: &str,
+    schemas: &[String],
+    objects: &[Object],
+) -> Result<SnapshotManifest> {
+    let previous = SnapshotManifest::read(dir)?;
+    // Checked before anything is written, the files of stale entries are removed below
+    if let Some(previous) = &previous {
+        for entry in &previous.objects {
+            entry.file(dir)?;
+        }
+    }
+
+    let mut objects: Vec<&Object> = objects.iter().filter(|o| o.ddl.is_some()).collect();
+    objects.sort_by(|a, b| {
+        (&a.owner, &a.object_type, &a.object_name).cmp(&(&b.owner, &b.object_type, &b.object_name))
+    });
+
+    let mut entries = Vec::new();
+    let mut used = HashSet::new();
+    for object in objects {
+        let Some(ddl) = &object.ddl else {
+            continue;
+        };
+        let content = normalize_snapshot_ddl(ddl);
+        let path = unique_path(
+            object_path(&object.owner, &object.object_type, &object.object_name),
+            &mut used,
+        );
+        let file = dir.join(&path);
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)
+                .context(format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&file, &content).context(format!("Failed to write {}", file.display()))?;
+
+        entries.push(SnapshotEntry {
+            owner: object.owner.clone(),
+            object_type: object.object_type.clone(),
+            object_name: object.object_name.clone(),
+            path,
+            last_ddl_time: object.last_ddl_time,
+            sha256: sha256_hex(content.as_bytes()),
+        });
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if let Some(previous) = previous {
+        let current: HashSet<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        for entry in previous.objects {
+            if !current.contains(entry.path.as_str()) {
+                let file = entry.file(dir)?;
+                if file.exists() {
+                    std::fs::remove_file(&file)
+                        .context(format!("Failed to remove {}", file.display()))?;
+                }
+            }
+        }
+    }
+
+    let manifest = SnapshotManifest {
+        version: MANIFEST_VERSION,
+        connection: connection.to_string(),
+        schemas: schemas.to_vec(),
+        objects: entries,
+    };
+    manifest.write(dir)?;
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn object(object_type: &str, name: &str, ddl: Option<&str>) -> Object {
+        Object {
+            owner: "HR".to_string(),
+            object_name: name.to_string(),
+            object_type: object_type.to_string(),
+            last_ddl_time: NaiveDate::from_ymd_opt(2025, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            ddl: ddl.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_object_path() {
+        assert_eq!(
+            object_path("HR", "PACKAGE BODY", "PKG"),
+            "HR/PACKAGE_BODY/PKG.sql"
+        );
+        assert_eq!(object_path("HR", "VIEW", "A/B"), "HR/VIEW/A_B.sql");
+        assert_eq!(object_path("..", "VIEW", "V"), "__/VIEW/V.sql");
+    }
+
+    #[test]
+    fn test_normalize_snapshot_ddl() {
+        assert_eq!(
+            normalize_snapshot_ddl("\r\n\n  CREATE VIEW V  \r\n  AS SELECT 1 FROM DUAL\t\n\n"),
+            "  CREATE VIEW V\n  AS SELECT 1 FROM DUAL\n"
+        );
+        assert_eq!(normalize_snapshot_ddl(""), "\n");
+    }
+
+    #[test]
+    fn test_write_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let schemas = vec!["HR".to_string()];
+        let objects = vec![
+            object(
+                "VIEW",
+                "V_EMP",
+                Some("CREATE VIEW V_EMP AS SELECT 1 FROM DUAL"),
+            ),
+            object("TABLE", "EMP", Some("CREATE TABLE EMP (ID NUMBER)")),
+            object("TABLE", "NO_DDL", None),
+        ];
+
+        let manifest = write_snapshot(dir.path(), "dev", &schemas, &objects).unwrap();
+
+        let paths: Vec<&str> = manifest.objects.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["HR/TABLE/EMP.sql", "HR/VIEW/V_EMP.sql"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("HR/TABLE/EMP.sql")).unwrap(),
+            "CREATE TABLE EMP (ID NUMBER)\n"
+        );
+        assert_eq!(SnapshotManifest::read(dir.path()).unwrap(), Some(manifest));
+
+        // Objects that disappeared are removed on the next export
+        write_snapshot(dir.path(), "dev", &schemas, &objects[1..]).unwrap();
+        assert!(!dir.path().join("HR/VIEW/V_EMP.sql").exists());
+        assert!(dir.path().join("HR/TABLE/EMP.sql").exists());
+    }
+
+    #[test]
+    fn test_write_snapshot_colliding_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let schemas = vec!["HR".to_string()];
+        let objects = vec![
+            object(
+                "VIEW",
+                "A_B",
+                Some("CREATE VIEW \"A_B\" AS SELECT 2 FROM DUAL"),
+            ),
+            object(
+                "VIEW",
+                "A/B",
+                Some("CREATE VIEW \"A/B\" AS SELECT 1 FROM DUAL"),
+            ),
+            object("TABLE", "EMP", Some("CREATE TABLE EMP (ID NUMBER)")),
+            object("TABLE", "Emp", Some("CREATE TABLE \"Emp\" (ID NUMBER)")),
+        ];
+
+        let manifest = write_snapshot(dir.path(), "dev", &schemas, &objects).unwrap();
+
+        let paths: Vec<(&str, &str)> = manifest
+            .objects
+            .iter()
+            .map(|e| (e.object_name.as_str(), e.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("EMP", "HR/TABLE/EMP.sql"),
+                ("Emp", "HR/TABLE/Emp~2.sql"),
+                ("A/B", "HR/VIEW/A_B.sql"),
+                ("A_B", "HR/VIEW/A_B~2.sql"),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("HR/VIEW/A_B~2.sql")).unwrap(),
+            "CREATE VIEW \"A_B\" AS SELECT 2 FROM DUAL\n"
+        );
+    }
+
+    #[test]
+    fn test_write_snapshot_rejects_paths_outside_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("snapshot");
+        std::fs::create_dir(&dir).unwrap();
+        let outside = root.path().join("outside.txt");
+        std::fs::write(&outside, "keep").unwrap();
+
+        let schemas = vec!["HR".to_string()];
+        let objects = vec![object("TABLE", "EMP", Some("CREATE TABLE EMP (ID NUMBER)"))];
+        let mut manifest = write_snapshot(&dir, "dev", &schemas, &objects).unwrap();
+
+        for path in ["../outside.txt", outside.to_str().unwrap()] {
+            manifest.objects[0].path = path.to_string();
+            manifest.write(&dir).unwrap();
+
+            assert!(write_snapshot(&dir, "dev", &schemas, &[]).is_err());
+            assert!(outside.exists());
+        }
+    }
+}
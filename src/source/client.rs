@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+
+use crate::{
+    entities::ConnectionModel, oracle::OracleClient, snapshot::DirectorySource,
+    types::ConnectionKind, types::Object, utils::SecretStore,
+};
+
+/// Where the source objects of a plan are read from, chosen by the kind of the connection.
+pub enum SourceClient {
+    Oracle(OracleClient),
+    Directory(DirectorySource),
+}
+
+impl SourceClient {
+    pub fn connect(connection: &ConnectionModel, secrets: &SecretStore) -> Result<Self> {
+        match connection.kind {
+            ConnectionKind::Oracle => OracleClient::connect(
+                &connection.username,
+                &secrets.reveal(&connection.password)?,
+                &connection.connection_string,
+            )
+            .context("Failed to connect to Oracle database")
+            .map(SourceClient::Oracle),
+            ConnectionKind::Directory => {
+                DirectorySource::open(connection.connection_string.as_ref())
+                    .map(SourceClient::Directory)
+            }
+        }
+    }
+
+    /// Schemas that can be read from the source.
+    pub async fn get_all_users(&self) -> Result<Vec<String>> {
+        match self {
+            SourceClient::Oracle(client) => client.get_all_users().await,
+            SourceClient::Directory(source) => source.schemas(),
+        }
+    }
+
+    pub async fn get_objects_with_ddls(
+        &self,
+        schemas: Vec<String>,
+        cutoff_date: Option<NaiveDateTime>,
+        object_types: Option<Vec<String>>,
+        exclude_object_types: Option<Vec<String>>,
+        exclude_object_names: Option<Vec<String>>,
+    ) -> Result<Vec<Object>> {
+        match self {
+            SourceClient::Oracle(client) => {
+                client
+                    .get_objects_with_ddls(
+                        schemas,
+                        cutoff_date,
+                        object_types,
+                        exclude_object_types,
+                        exclude_object_names,
+                    )
+                    .await
+            }
+            SourceClient::Directory(source) => source.get_objects_with_ddls(
+                &schemas,
+                cutoff_date,
+                object_types,
+                exclude_object_types,
+                exclude_object_names,
+            ),
+        }
+    }
+}
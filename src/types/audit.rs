@@ -0,0 +1,112 @@
+use colored::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::{config::Settings, utils::mask_arguments};
+
+/// Operation recorded in the audit trail.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    DeriveActiveEnum,
+    EnumIter,
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum AuditOperation {
+    #[sea_orm(string_value = "PREPARE")]
+    Prepare,
+
+    #[sea_orm(string_value = "APPLY")]
+    Apply,
+
+    /// Prepare and apply in one step, `plans run`
+    #[sea_orm(string_value = "RUN")]
+    Run,
+
+    #[sea_orm(string_value = "ROLLBACK")]
+    Rollback,
+
+    #[sea_orm(string_value = "RESET")]
+    Reset,
+}
+
+/// Outcome of an audited operation. Events stay `STARTED` if the process stopped
+/// before the operation finished.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    DeriveActiveEnum,
+    EnumIter,
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum AuditOutcome {
+    #[sea_orm(string_value = "STARTED")]
+    Started,
+
+    #[sea_orm(string_value = "SUCCESS")]
+    Success,
+
+    #[sea_orm(string_value = "ERROR")]
+    Error,
+}
+
+impl AuditOutcome {
+    pub fn to_colored_string(&self) -> String {
+        match self {
+            AuditOutcome::Started => "STARTED".yellow().bold().to_string(),
+            AuditOutcome::Success => "SUCCESS".green().bold().to_string(),
+            AuditOutcome::Error => "ERROR".red().bold().to_string(),
+        }
+    }
+}
+
+/// Operator identity and invocation recorded with every audit event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditOrigin {
+    pub os_user: String,
+    pub hostname: String,
+    pub leaf_version: String,
+    /// Command line arguments, secrets masked
+    pub arguments: Vec<String>,
+    /// Effective settings, secrets masked
+    pub settings: serde_json::Value,
+}
+
+/// `$USER` (`%USERNAME%` on Windows) of the running process, `unknown` if neither is set.
+pub fn current_os_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+impl AuditOrigin {
+    /// Origin of the running process: `$USER` (`%USERNAME%` on Windows), the hostname, the
+    /// leaf version, the command line and `settings`, all with secrets masked.
+    pub fn current(settings: &Settings) -> Self {
+        let os_user = current_os_user();
+        let arguments: Vec<String> = std::env::args().collect();
+        Self {
+            os_user,
+            hostname: gethostname::gethostname().to_string_lossy().into_owned(),
+            leaf_version: env!("CARGO_PKG_VERSION").to_string(),
+            arguments: mask_arguments(&arguments),
+            settings: serde_json::to_value(settings.masked()).unwrap_or_default(),
+        }
+    }
+}
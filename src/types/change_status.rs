@@ -26,6 +26,12 @@ pub enum ChangeStatus {
     #[sea_orm(string_value = "RUNNING")]
     Running,
 
+    #[sea_orm(string_value = "INTERRUPTED")]
+    Interrupted,
+
+    #[sea_orm(string_value = "CANCELLED")]
+    Cancelled,
+
     #[sea_orm(string_value = "SUCCESS")]
     Success,
 
@@ -47,6 +53,8 @@ impl ChangeStatus {
         match self {
             ChangeStatus::Idle => "IDLE".bright_black().to_string(),
             ChangeStatus::Running => "RUNNING".blue().bold().to_string(),
+            ChangeStatus::Interrupted => "INTERRUPTED".magenta().bold().to_string(),
+            ChangeStatus::Cancelled => "CANCELLED".yellow().bold().to_string(),
             ChangeStatus::Success => "SUCCESS".green().bold().to_string(),
             ChangeStatus::Error => "ERROR".red().bold().to_string(),
             ChangeStatus::RollingBack => "ROLLING BACK".blue().bold().to_string(),
@@ -27,6 +27,12 @@ pub enum ChangesetStatus {
     #[sea_orm(string_value = "RUNNING")]
     Running,
 
+    #[sea_orm(string_value = "INTERRUPTED")]
+    Interrupted,
+
+    #[sea_orm(string_value = "CANCELLED")]
+    Cancelled,
+
     #[sea_orm(string_value = "SUCCESS")]
     Success,
 
@@ -51,6 +57,8 @@ impl ChangesetStatus {
         match self {
             ChangesetStatus::Idle => "IDLE".bright_black().to_string(),
             ChangesetStatus::Running => "RUNNING".blue().bold().to_string(),
+            ChangesetStatus::Interrupted => "INTERRUPTED".magenta().bold().to_string(),
+            ChangesetStatus::Cancelled => "CANCELLED".yellow().bold().to_string(),
             ChangesetStatus::Success => "SUCCESS".green().bold().to_string(),
             ChangesetStatus::Error => "ERROR".red().bold().to_string(),
             ChangesetStatus::Warning => "WARNING".yellow().bold().to_string(),
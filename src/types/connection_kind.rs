@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+/// Where the objects of a connection come from.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    Default,
+    DeriveActiveEnum,
+    EnumIter,
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum ConnectionKind {
+    /// An Oracle database
+    #[sea_orm(string_value = "ORACLE")]
+    #[default]
+    Oracle,
+
+    /// A directory of DDL files laid out like a snapshot, usable as a plan source only
+    #[sea_orm(string_value = "DIRECTORY")]
+    Directory,
+}
@@ -1,5 +1,6 @@
 use chrono::NaiveDateTime;
 use serde::Serialize;
+use strum_macros::Display;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Default)]
 pub struct Delta {
@@ -13,3 +14,103 @@ pub struct Delta {
     pub scripts: Vec<String>,
     pub rollback_scripts: Vec<String>,
 }
+
+/// What a delta does to the target object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DeltaOperation {
+    /// The object does not exist on the target
+    Create,
+    /// The table is altered column by column
+    Alter,
+    /// The object is replaced with the source DDL
+    Replace,
+    /// The object does not exist on the source
+    Drop,
+}
+
+/// How likely a delta is to lose data or break dependants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DeltaRisk {
+    Low,
+    Medium,
+    High,
+}
+
+impl Delta {
+    pub fn operation(&self) -> DeltaOperation {
+        match (&self.source_ddl, &self.target_ddl) {
+            (_, None) => DeltaOperation::Create,
+            (None, Some(_)) => DeltaOperation::Drop,
+            _ if self.object_type == "TABLE" => DeltaOperation::Alter,
+            _ => DeltaOperation::Replace,
+        }
+    }
+
+    /// Dropping tables or columns loses data, other drops and table changes may break
+    /// dependants, creating or replacing code objects is low risk.
+    pub fn risk(&self) -> DeltaRisk {
+        let drops_data = self
+            .scripts
+            .iter()
+            .any(|s| s.to_uppercase().contains("DROP COLUMN"));
+        match self.operation() {
+            DeltaOperation::Drop if self.object_type == "TABLE" => DeltaRisk::High,
+            DeltaOperation::Alter if drops_data => DeltaRisk::High,
+            DeltaOperation::Drop | DeltaOperation::Alter => DeltaRisk::Medium,
+            DeltaOperation::Create | DeltaOperation::Replace => DeltaRisk::Low,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(object_type: &str, source: Option<&str>, target: Option<&str>) -> Delta {
+        Delta {
+            object_type: object_type.to_string(),
+            source_ddl: source.map(str::to_string),
+            target_ddl: target.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_operation() {
+        assert_eq!(
+            delta("VIEW", Some("a"), None).operation(),
+            DeltaOperation::Create
+        );
+        assert_eq!(
+            delta("VIEW", None, Some("a")).operation(),
+            DeltaOperation::Drop
+        );
+        assert_eq!(
+            delta("TABLE", Some("a"), Some("b")).operation(),
+            DeltaOperation::Alter
+        );
+        assert_eq!(
+            delta("PACKAGE", Some("a"), Some("b")).operation(),
+            DeltaOperation::Replace
+        );
+    }
+
+    #[test]
+    fn test_risk() {
+        assert_eq!(delta("TABLE", None, Some("a")).risk(), DeltaRisk::High);
+        assert_eq!(delta("VIEW", None, Some("a")).risk(), DeltaRisk::Medium);
+        assert_eq!(delta("VIEW", Some("a"), Some("b")).risk(), DeltaRisk::Low);
+
+        let mut table = delta("TABLE", Some("a"), Some("b"));
+        table.scripts = vec!["ALTER TABLE HR.T ADD C NUMBER".to_string()];
+        assert_eq!(table.risk(), DeltaRisk::Medium);
+        table
+            .scripts
+            .push("ALTER TABLE HR.T DROP COLUMN \"D\"".to_string());
+        assert_eq!(table.risk(), DeltaRisk::High);
+    }
+}
@@ -26,6 +26,12 @@ pub enum DeploymentStatus {
     #[sea_orm(string_value = "RUNNING")]
     Running,
 
+    #[sea_orm(string_value = "INTERRUPTED")]
+    Interrupted,
+
+    #[sea_orm(string_value = "CANCELLED")]
+    Cancelled,
+
     #[sea_orm(string_value = "ERROR")]
     Error,
 
@@ -47,6 +53,8 @@ impl DeploymentStatus {
         match self {
             DeploymentStatus::Idle => "IDLE".bright_black().to_string(),
             DeploymentStatus::Running => "RUNNING".blue().bold().to_string(),
+            DeploymentStatus::Interrupted => "INTERRUPTED".magenta().bold().to_string(),
+            DeploymentStatus::Cancelled => "CANCELLED".yellow().bold().to_string(),
             DeploymentStatus::Error => "ERROR".red().bold().to_string(),
             DeploymentStatus::Success => "SUCCESS".green().bold().to_string(),
             DeploymentStatus::RollingBack => "ROLLING_BACK".blue().bold().to_string(),
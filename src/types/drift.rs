@@ -0,0 +1,139 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use strum_macros::Display;
+
+use crate::delta::ddl_matches;
+
+/// What `apply` does when the target no longer matches the prepared changesets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DriftPolicy {
+    /// Abort the apply and list the drifted objects
+    #[default]
+    Abort,
+    /// Apply the prepared scripts anyway
+    Ignore,
+    /// Re-prepare the drifted changesets from the current target state
+    Refresh,
+}
+
+/// A changeset object whose current target DDL differs from the recorded `target_ddl`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DriftedObject {
+    pub changeset_id: i32,
+    pub object_type: String,
+    pub object_owner: String,
+    pub object_name: String,
+    /// Target DDL recorded when the deployment was prepared
+    pub expected_ddl: Option<String>,
+    /// Target DDL extracted right before the apply
+    pub actual_ddl: Option<String>,
+}
+
+impl DriftedObject {
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{} {}.{}",
+            self.object_type, self.object_owner, self.object_name
+        )
+    }
+}
+
+/// How an object deployed by leaf was changed outside of leaf.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DriftKind {
+    /// The object exists with a different DDL
+    Altered,
+    /// The object no longer exists
+    Dropped,
+    /// The object exists although leaf dropped it
+    Created,
+}
+
+impl DriftKind {
+    /// Compares the DDL leaf left an object with to its current DDL, `None` if they match.
+    pub fn classify(expected_ddl: Option<&str>, actual_ddl: Option<&str>) -> Option<Self> {
+        match (expected_ddl, actual_ddl) {
+            (Some(_), None) => Some(DriftKind::Dropped),
+            (None, Some(_)) => Some(DriftKind::Created),
+            (expected, actual) if !ddl_matches(expected, actual) => Some(DriftKind::Altered),
+            _ => None,
+        }
+    }
+}
+
+/// An object whose current target DDL differs from the state leaf last deployed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ObjectDrift {
+    pub object_type: String,
+    pub object_owner: String,
+    pub object_name: String,
+    pub kind: DriftKind,
+    /// Deployment and changeset that last changed the object
+    pub deployment_id: i32,
+    pub changeset_id: i32,
+    /// DDL leaf left the object with, `None` if leaf dropped it
+    pub expected_ddl: Option<String>,
+    /// Current target DDL, `None` if the object does not exist
+    pub actual_ddl: Option<String>,
+}
+
+impl ObjectDrift {
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{} {}.{}",
+            self.object_type, self.object_owner, self.object_name
+        )
+    }
+}
+
+/// Version of the [`DriftDocument`] layout, bumped on breaking changes.
+pub const DRIFT_DOCUMENT_VERSION: u32 = 1;
+
+/// Machine-readable result of `leaf drift check --format json`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DriftDocument {
+    pub version: u32,
+    pub plan: String,
+    pub checked_at: String,
+    pub has_drift: bool,
+    pub drift_count: usize,
+    pub objects: Vec<ObjectDrift>,
+}
+
+impl DriftDocument {
+    pub fn new(plan: &str, checked_at: NaiveDateTime, objects: Vec<ObjectDrift>) -> Self {
+        Self {
+            version: DRIFT_DOCUMENT_VERSION,
+            plan: plan.to_string(),
+            checked_at: checked_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            has_drift: !objects.is_empty(),
+            drift_count: objects.len(),
+            objects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_drift() {
+        let ddl = Some("CREATE VIEW V AS SELECT 1 FROM DUAL");
+
+        assert_eq!(DriftKind::classify(ddl, ddl), None);
+        assert_eq!(
+            DriftKind::classify(ddl, Some("CREATE VIEW V\n  AS SELECT 1 FROM DUAL;")),
+            None
+        );
+        assert_eq!(DriftKind::classify(None, None), None);
+        assert_eq!(
+            DriftKind::classify(ddl, Some("CREATE VIEW V AS SELECT 2 FROM DUAL")),
+            Some(DriftKind::Altered)
+        );
+        assert_eq!(DriftKind::classify(ddl, None), Some(DriftKind::Dropped));
+        assert_eq!(DriftKind::classify(None, ddl), Some(DriftKind::Created));
+    }
+}
@@ -0,0 +1,13 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A hook script executed during a deployment, recorded for reports.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookExecution {
+    /// Hook stage, e.g. `pre_apply_deployment`
+    pub stage: String,
+    pub script: String,
+    pub started_at: NaiveDateTime,
+    pub ended_at: NaiveDateTime,
+    pub error: Option<String>,
+}
@@ -6,11 +6,17 @@ use tera::{Context, Tera};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct Hooks {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pre_prepare_deployment: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub post_prepare_deployment: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pre_apply_deployment: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub post_apply_deployment: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pre_rollback: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub post_rollback: Option<Vec<String>>,
 }
 
@@ -70,6 +76,19 @@ impl Hooks {
     pub fn get_post_rollback(&self, ctx: &Context) -> Result<Option<Vec<String>>> {
         self.render_hooks(ctx, &self.post_rollback)
     }
+
+    /// Scripts of a hook stage by its name, e.g. `pre_apply_deployment`
+    pub fn stage_mut(&mut self, stage: &str) -> Option<&mut Option<Vec<String>>> {
+        match stage {
+            "pre_prepare_deployment" => Some(&mut self.pre_prepare_deployment),
+            "post_prepare_deployment" => Some(&mut self.post_prepare_deployment),
+            "pre_apply_deployment" => Some(&mut self.pre_apply_deployment),
+            "post_apply_deployment" => Some(&mut self.post_apply_deployment),
+            "pre_rollback" => Some(&mut self.pre_rollback),
+            "post_rollback" => Some(&mut self.post_rollback),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
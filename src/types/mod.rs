@@ -1,20 +1,38 @@
+mod audit;
 mod change_status;
 mod changeset_status;
+mod connection_kind;
 mod delta;
 mod deployment_status;
+mod drift;
+mod hook_execution;
 mod hooks;
 mod oracle;
+mod output_format;
 mod plan_status;
+mod report_format;
+mod retention;
 mod rollback_status;
+mod rollback_target;
 mod string_list;
+mod verification_status;
 
+pub use audit::{AuditOperation, AuditOrigin, AuditOutcome, current_os_user};
 pub use change_status::ChangeStatus;
 pub use changeset_status::ChangesetStatus;
-pub use delta::Delta;
+pub use connection_kind::ConnectionKind;
+pub use delta::{Delta, DeltaOperation, DeltaRisk};
 
 pub use deployment_status::DeploymentStatus;
+pub use drift::{DriftDocument, DriftKind, DriftPolicy, DriftedObject, ObjectDrift};
+pub use hook_execution::HookExecution;
 pub use hooks::Hooks;
 pub use oracle::Object;
+pub use output_format::{EventsFormat, OutputFormat};
 pub use plan_status::PlanStatus;
+pub use report_format::ReportFormat;
+pub use retention::{PurgeSummary, RetentionPolicy};
 pub use rollback_status::RollbackStatus;
+pub use rollback_target::RollbackTarget;
 pub use string_list::StringList;
+pub use verification_status::VerificationStatus;
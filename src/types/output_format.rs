@@ -0,0 +1,20 @@
+use strum_macros::{Display, EnumString};
+
+/// Output format of commands that describe the changes of a plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum OutputFormat {
+    /// Human readable progress and summary
+    #[default]
+    Text,
+    /// A single JSON document on stdout, see `DeltaDocument`
+    Json,
+}
+
+/// Format of the progress event stream printed by `--events`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum EventsFormat {
+    /// One JSON object per line on stdout
+    Jsonl,
+}
@@ -26,6 +26,12 @@ pub enum PlanStatus {
     #[sea_orm(string_value = "RUNNING")]
     Running,
 
+    #[sea_orm(string_value = "INTERRUPTED")]
+    Interrupted,
+
+    #[sea_orm(string_value = "CANCELLED")]
+    Cancelled,
+
     #[sea_orm(string_value = "ERROR")]
     Error,
 
@@ -47,6 +53,8 @@ impl PlanStatus {
         match self {
             PlanStatus::Idle => "IDLE".bright_black().to_string(),
             PlanStatus::Running => "RUNNING".blue().bold().to_string(),
+            PlanStatus::Interrupted => "INTERRUPTED".magenta().bold().to_string(),
+            PlanStatus::Cancelled => "CANCELLED".yellow().bold().to_string(),
             PlanStatus::Error => "ERROR".red().bold().to_string(),
             PlanStatus::Success => "SUCCESS".green().bold().to_string(),
             PlanStatus::RollingBack => "ROLLING_BACK".blue().bold().to_string(),
@@ -0,0 +1,47 @@
+use strum_macros::{Display, EnumString};
+
+/// Output format of a deployment report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ReportFormat {
+    /// Colored tables for the terminal
+    #[default]
+    Terminal,
+    #[strum(serialize = "markdown", serialize = "md")]
+    Markdown,
+    /// Standalone HTML page
+    Html,
+    Json,
+}
+
+impl ReportFormat {
+    /// File extension used when the report is written to a directory
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Terminal => "txt",
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+            ReportFormat::Json => "json",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_report_format() {
+        assert_eq!(ReportFormat::from_str("html").unwrap(), ReportFormat::Html);
+        assert_eq!(
+            ReportFormat::from_str("MD").unwrap(),
+            ReportFormat::Markdown
+        );
+        assert_eq!(
+            ReportFormat::from_str("markdown").unwrap(),
+            ReportFormat::Markdown
+        );
+        assert!(ReportFormat::from_str("pdf").is_err());
+    }
+}
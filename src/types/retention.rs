@@ -0,0 +1,192 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use anyhow::{Result, ensure};
+use chrono::{Duration, NaiveDateTime};
+
+use crate::{entities::DeploymentModel, types::DeploymentStatus};
+
+/// Which deployments are deleted from the repository by a purge.
+///
+/// With both limits set a deployment is purged when it is older than `older_than` and not
+/// one of the `keep_last` newest of its plan. The latest successful deployment of every plan
+/// is always kept, and so are deployments that are running or can still be applied: idle
+/// deployments waiting to be applied or approved, and interrupted or cancelled ones that
+/// can be resumed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub older_than: Option<Duration>,
+    pub keep_last: Option<usize>,
+}
+
+impl RetentionPolicy {
+    pub fn new(older_than: Option<Duration>, keep_last: Option<usize>) -> Result<Self> {
+        ensure!(
+            older_than.is_some() || keep_last.is_some(),
+            "A retention policy needs an age limit, a number of deployments to keep, or both"
+        );
+        Ok(Self {
+            older_than,
+            keep_last,
+        })
+    }
+
+    /// The deployments to purge, oldest first.
+    pub fn select(
+        &self,
+        deployments: &[DeploymentModel],
+        now: NaiveDateTime,
+    ) -> Vec<DeploymentModel> {
+        let mut by_plan: HashMap<i32, Vec<&DeploymentModel>> = HashMap::new();
+        for deployment in deployments {
+            by_plan
+                .entry(deployment.plan_id)
+                .or_default()
+                .push(deployment);
+        }
+
+        let cutoff = self.older_than.map(|d| now - d);
+        let mut selected = Vec::new();
+        for mut plan_deployments in by_plan.into_values() {
+            // Newest first
+            plan_deployments.sort_by_key(|d| Reverse((d.created_at, d.id)));
+            let latest_success = plan_deployments
+                .iter()
+                .find(|d| d.status == DeploymentStatus::Success)
+                .map(|d| d.id);
+
+            for (i, deployment) in plan_deployments.into_iter().enumerate() {
+                let kept = Some(deployment.id) == latest_success
+                    || matches!(
+                        deployment.status,
+                        DeploymentStatus::Idle
+                            | DeploymentStatus::Running
+                            | DeploymentStatus::Interrupted
+                            | DeploymentStatus::Cancelled
+                            | DeploymentStatus::RollingBack
+                    )
+                    || self.keep_last.is_some_and(|n| i < n)
+                    || cutoff.is_some_and(|cutoff| deployment.created_at >= cutoff);
+                if !kept {
+                    selected.push(deployment.clone());
+                }
+            }
+        }
+        selected.sort_by_key(|d| (d.created_at, d.id));
+        selected
+    }
+}
+
+/// Number of rows a purge deleted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PurgeSummary {
+    pub deployments: u64,
+    pub changesets: u64,
+    pub changes: u64,
+    pub rollbacks: u64,
+    pub approvals: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn deployment(
+        id: i32,
+        plan_id: i32,
+        days_ago: i64,
+        status: DeploymentStatus,
+    ) -> DeploymentModel {
+        DeploymentModel {
+            id,
+            plan_id,
+            status,
+            created_at: now() - Duration::days(days_ago),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 12, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn ids(deployments: Vec<DeploymentModel>) -> Vec<i32> {
+        deployments.into_iter().map(|d| d.id).collect()
+    }
+
+    #[test]
+    fn test_select_older_than() {
+        let deployments = vec![
+            deployment(1, 1, 200, DeploymentStatus::Success),
+            deployment(2, 1, 150, DeploymentStatus::Error),
+            deployment(3, 1, 120, DeploymentStatus::Success),
+            deployment(4, 1, 10, DeploymentStatus::Error),
+            // The latest successful deployment of plan 2 is old but kept
+            deployment(5, 2, 300, DeploymentStatus::Success),
+            deployment(6, 2, 200, DeploymentStatus::Error),
+            deployment(7, 2, 100, DeploymentStatus::Running),
+        ];
+        let policy = RetentionPolicy::new(Some(Duration::days(90)), None).unwrap();
+
+        assert_eq!(ids(policy.select(&deployments, now())), vec![1, 6, 2]);
+    }
+
+    #[test]
+    fn test_select_keep_last() {
+        let deployments = vec![
+            deployment(1, 1, 200, DeploymentStatus::Success),
+            deployment(2, 1, 150, DeploymentStatus::Success),
+            deployment(3, 1, 120, DeploymentStatus::Error),
+            deployment(4, 1, 10, DeploymentStatus::Error),
+        ];
+
+        let policy = RetentionPolicy::new(None, Some(2)).unwrap();
+        assert_eq!(ids(policy.select(&deployments, now())), vec![1]);
+
+        // Both limits: old and not among the newest
+        let policy = RetentionPolicy::new(Some(Duration::days(180)), Some(1)).unwrap();
+        assert_eq!(ids(policy.select(&deployments, now())), vec![1]);
+
+        assert!(RetentionPolicy::new(None, None).is_err());
+    }
+
+    #[test]
+    fn test_select_keeps_pending_deployments() {
+        let policy = RetentionPolicy::new(Some(Duration::days(90)), Some(1)).unwrap();
+        for status in [
+            DeploymentStatus::Idle,
+            DeploymentStatus::Running,
+            DeploymentStatus::Interrupted,
+            DeploymentStatus::Cancelled,
+            DeploymentStatus::RollingBack,
+        ] {
+            let deployments = vec![
+                deployment(1, 1, 300, status.clone()),
+                deployment(2, 1, 200, DeploymentStatus::Error),
+                deployment(3, 1, 100, DeploymentStatus::Success),
+            ];
+            assert_eq!(
+                ids(policy.select(&deployments, now())),
+                vec![2],
+                "{:?} deployment is purged",
+                status
+            );
+        }
+
+        // Finished deployments are purged
+        for status in [
+            DeploymentStatus::Error,
+            DeploymentStatus::RolledBack,
+            DeploymentStatus::RollbackError,
+        ] {
+            let deployments = vec![
+                deployment(1, 1, 300, status.clone()),
+                deployment(2, 1, 100, DeploymentStatus::Success),
+            ];
+            assert_eq!(ids(policy.select(&deployments, now())), vec![1]);
+        }
+    }
+}
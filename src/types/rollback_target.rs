@@ -0,0 +1,19 @@
+use std::fmt;
+
+/// Point a plan is rolled back to. Every successful deployment after it is reverted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RollbackTarget {
+    /// Keep the deployment with this ID and everything before it
+    Deployment(i32),
+    /// Keep the deployment tagged with this release tag and everything before it
+    Tag(String),
+}
+
+impl fmt::Display for RollbackTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RollbackTarget::Deployment(id) => write!(f, "deployment {}", id),
+            RollbackTarget::Tag(tag) => write!(f, "tag '{}'", tag),
+        }
+    }
+}
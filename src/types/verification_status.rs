@@ -0,0 +1,36 @@
+use colored::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+/// Result of comparing a deployed object with its source DDL after apply.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    DeriveActiveEnum,
+    EnumIter,
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum VerificationStatus {
+    #[sea_orm(string_value = "VERIFIED")]
+    Verified,
+
+    #[sea_orm(string_value = "MISMATCH")]
+    Mismatch,
+}
+
+impl VerificationStatus {
+    pub fn to_colored_string(&self) -> String {
+        match self {
+            VerificationStatus::Verified => "VERIFIED".green().bold().to_string(),
+            VerificationStatus::Mismatch => "MISMATCH".red().bold().to_string(),
+        }
+    }
+}
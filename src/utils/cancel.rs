@@ -0,0 +1,119 @@
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// A cooperative cancellation flag shared between a signal handler and a running operation.
+///
+/// Cloned tokens share the same flag. Operations check it between statements, so a
+/// statement that already started always completes.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Handles Ctrl-C and SIGTERM for the operation it was created for, until it is dropped.
+///
+/// Tokio can not unregister signal handlers, so once the guard is dropped a signal exits the
+/// process with code 130 right away, as it would without a handler.
+#[derive(Debug)]
+pub struct SignalGuard {
+    active: Arc<AtomicBool>,
+}
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        self.active.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Cancels the token on the first Ctrl-C or SIGTERM. On the second one `on_abort` runs,
+/// releasing the lock and marking the operation interrupted while the statement in flight
+/// is still running, and the process exits with code 130.
+pub fn cancel_on_signal<F>(token: CancelToken, on_abort: F) -> SignalGuard
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let active = Arc::new(AtomicBool::new(true));
+    let is_active = active.clone();
+    tokio::spawn(async move {
+        if wait_for_signal().await.is_err() {
+            return;
+        }
+        if !is_active.load(Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        token.cancel();
+        eprintln!(
+            "\n🛑 Cancelling after the current statement, press Ctrl-C again to abort immediately"
+        );
+
+        if wait_for_signal().await.is_err() {
+            return;
+        }
+        if is_active.load(Ordering::SeqCst) {
+            eprintln!("\n🛑 Aborting, the deployment is marked INTERRUPTED");
+            on_abort.await;
+        }
+        std::process::exit(130);
+    });
+    SignalGuard { active }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> std::io::Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        res = tokio::signal::ctrl_c() => res,
+        _ = terminate.recv() => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> std::io::Result<()> {
+    tokio::signal::ctrl_c().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_the_flag() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+
+        token.cancel();
+
+        assert!(clone.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_guard_deactivates_handler_on_drop() {
+        let guard = cancel_on_signal(CancelToken::new(), async {});
+        let active = guard.active.clone();
+        assert!(active.load(Ordering::SeqCst));
+
+        drop(guard);
+
+        assert!(!active.load(Ordering::SeqCst));
+    }
+}
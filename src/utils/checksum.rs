@@ -0,0 +1,48 @@
+use sha2::{Digest, Sha256};
+
+/// Computes a hex encoded SHA-256 checksum over the given scripts, in the given order.
+///
+/// Each script is terminated with a NUL byte, so moving text from one script to the
+/// next changes the checksum.
+pub fn scripts_checksum<'a>(scripts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = Sha256::new();
+    for script in scripts {
+        hasher.update(script.as_bytes());
+        hasher.update([0u8]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Computes a hex encoded SHA-256 checksum of the content, same as `sha256sum`.
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_is_stable() {
+        let a = scripts_checksum(["ALTER TABLE EMP ADD (X NUMBER)", "DROP VIEW V"]);
+        let b = scripts_checksum(["ALTER TABLE EMP ADD (X NUMBER)", "DROP VIEW V"]);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+    }
+
+    #[test]
+    fn test_checksum_changes_with_content_and_order() {
+        let a = scripts_checksum(["A", "B"]);
+        assert_ne!(a, scripts_checksum(["B", "A"]));
+        assert_ne!(a, scripts_checksum(["AB"]));
+        assert_ne!(a, scripts_checksum(["A", "B "]));
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
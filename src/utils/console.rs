@@ -0,0 +1,27 @@
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// Reserves stdout for a machine-readable stream, such as `--events jsonl`. Output printed
+/// with [`print_output`] goes to stderr from then on, so every line of stdout stays part
+/// of the stream.
+pub fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if stdout is reserved for a machine-readable stream.
+pub fn is_stdout_reserved() -> bool {
+    STDOUT_RESERVED.load(Ordering::Relaxed)
+}
+
+/// Prints a line of human-readable command output, on stdout unless it is reserved.
+pub fn print_output(line: impl Display) {
+    if is_stdout_reserved() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
@@ -1,5 +1,8 @@
+use crate::types::Delta;
 use crate::utils::script_writer::ScriptWriterMode;
-use crate::utils::{ProgressReporter, ScriptWriter, ScriptWriterOptions};
+use crate::utils::{
+    CancelToken, ProgressEvent, ProgressReporter, ScriptWriter, ScriptWriterOptions, print_output,
+};
 use std::io::{Error, ErrorKind, Result};
 use std::path::PathBuf;
 use tokio::sync::mpsc;
@@ -18,7 +21,7 @@ pub struct DeploymentContextOptions {
     pub script_sep: Option<String>,
     /// Optional progress reporter sender.
     /// If None, progress reporting is disabled.
-    pub progress_tx: Option<mpsc::UnboundedSender<String>>,
+    pub progress_tx: Option<mpsc::UnboundedSender<ProgressEvent>>,
 }
 
 impl DeploymentContextOptions {
@@ -27,7 +30,7 @@ impl DeploymentContextOptions {
         collect_scripts: bool,
         output_path: Option<PathBuf>,
         script_sep: Option<String>,
-        progress_tx: Option<mpsc::UnboundedSender<String>>,
+        progress_tx: Option<mpsc::UnboundedSender<ProgressEvent>>,
     ) -> Self {
         Self {
             dry,
@@ -91,6 +94,8 @@ pub struct DeploymentContext {
     collect_scripts: bool,
     script_writer: ScriptWriter,
     progress_reporter: ProgressReporter,
+    cancel_token: CancelToken,
+    deltas: Vec<Delta>,
 }
 
 impl DeploymentContext {
@@ -129,9 +134,27 @@ impl DeploymentContext {
             collect_scripts: opts.collect_scripts,
             script_writer,
             progress_reporter,
+            cancel_token: CancelToken::new(),
+            deltas: Vec::new(),
         })
     }
 
+    /// Shares the given token with the context, so the caller can cancel the operation.
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel_token = token;
+        self
+    }
+
+    /// Returns true if the operation was asked to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token.is_cancelled()
+    }
+
+    /// The token that cancels the operation, shared with whatever may need to stop it.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel_token.clone()
+    }
+
     /// Returns true if running in dry-run mode.
     pub fn is_dry_run(&self) -> bool {
         self.dry_run
@@ -143,8 +166,8 @@ impl DeploymentContext {
     }
 
     /// Reports progress (no-op if progress reporting is disabled).
-    pub fn progress(&mut self, message: impl Into<String>) {
-        self.progress_reporter.report(message);
+    pub fn progress(&mut self, event: impl Into<ProgressEvent>) {
+        self.progress_reporter.report(event);
     }
 
     /// Writes a migration script (no-op if script writing is disabled).
@@ -157,6 +180,21 @@ impl DeploymentContext {
         self.script_writer.write_rollback_script(content)
     }
 
+    /// Writes a line of the summary (no-op if script writing is disabled).
+    pub fn write_summary(&mut self, line: &str) -> Result<()> {
+        self.script_writer.write_summary(&format!("{}\n", line))
+    }
+
+    /// Records a delta that produced a changeset, in deployment order.
+    pub fn record_delta(&mut self, delta: &Delta) {
+        self.deltas.push(delta.clone());
+    }
+
+    /// Gets the deltas recorded during preparation.
+    pub fn deltas(&self) -> &[Delta] {
+        &self.deltas
+    }
+
     /// Gets the script content if available (Memory mode only).
     pub fn script_content(&self) -> Option<&str> {
         self.script_writer.script_content()
@@ -167,6 +205,11 @@ impl DeploymentContext {
         self.script_writer.rollback_content()
     }
 
+    /// Gets the summary content if available (Memory mode only).
+    pub fn summary_content(&self) -> Option<&str> {
+        self.script_writer.summary_content()
+    }
+
     /// Gets a reference to the progress reporter for advanced usage.
     pub fn progress_reporter(&self) -> &ProgressReporter {
         &self.progress_reporter
@@ -189,31 +232,50 @@ impl DeploymentContext {
 
         match self.script_writer.mode() {
             ScriptWriterMode::Memory => {
-                // Memory mode - print both contents
-                if let Some(script_content) = self.script_content() {
-                    println!("\n📝 Migration Scripts:\n{}", script_content);
+                // Memory mode - print all non-empty contents
+                if let Some(script_content) = self.script_content().filter(|c| !c.is_empty()) {
+                    print_output(format!("\n📝 Migration Scripts:\n{}", script_content));
                 }
-                if let Some(rollback_content) = self.rollback_content() {
-                    println!("\n📝 Rollback Scripts:\n{}", rollback_content);
+                if let Some(rollback_content) = self.rollback_content().filter(|c| !c.is_empty()) {
+                    print_output(format!("\n📝 Rollback Scripts:\n{}", rollback_content));
+                }
+                if let Some(summary_content) = self.summary_content().filter(|c| !c.is_empty()) {
+                    print_output(format!("\n📋 Summary:\n{}", summary_content));
                 }
             }
             ScriptWriterMode::File => {
-                // File mode - show paths
-                if let Some(script_path) = self.script_writer.script_file_path() {
-                    println!(
+                // File mode - show paths of files that have content
+                let has_content =
+                    |path: &std::path::Path| std::fs::metadata(path).is_ok_and(|m| m.len() > 0);
+                if let Some(script_path) = self
+                    .script_writer
+                    .script_file_path()
+                    .filter(|p| has_content(p))
+                {
+                    print_output(format!(
                         "📄 Migration scripts written to: '{}'",
                         script_path.display()
-                    );
+                    ));
                 }
-                if let Some(rollback_path) = self.script_writer.rollback_file_path() {
-                    println!(
+                if let Some(rollback_path) = self
+                    .script_writer
+                    .rollback_file_path()
+                    .filter(|p| has_content(p))
+                {
+                    print_output(format!(
                         "📄 Rollback scripts written to: '{}'",
                         rollback_path.display()
-                    );
+                    ));
+                }
+                if let Some(summary_path) = self.script_writer.summary_file_path() {
+                    print_output(format!(
+                        "📄 Summary written to: '{}'",
+                        summary_path.display()
+                    ));
                 }
             }
             ScriptWriterMode::Disabled => {
-                println!("✅ Script writing is disabled");
+                print_output("✅ Script writing is disabled");
             }
         }
     }
@@ -307,8 +369,8 @@ mod tests {
         sink.progress("Test message");
 
         // Check that message was sent
-        let msg = rx.try_recv().unwrap();
-        assert_eq!(msg, "Test message");
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event, ProgressEvent::from("Test message"));
     }
 
     #[test]
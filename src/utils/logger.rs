@@ -28,15 +28,17 @@ pub fn init_logging(log_config: &crate::config::LogConfig) {
         None
     };
 
-    // Console layer with configurable formatting
+    // Console layer with configurable formatting, on stderr to keep stdout for command output
     let console_layer = match log_config.console_format.as_str() {
         "json" => fmt::layer()
             .json()
+            .with_writer(std::io::stderr)
             .with_target(true)
             .with_thread_ids(false)
             .boxed(),
         "pretty" | _ => fmt::layer()
             .pretty()
+            .with_writer(std::io::stderr)
             .with_target(true)
             .with_thread_ids(false)
             .with_file(true)
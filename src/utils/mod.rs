@@ -1,20 +1,33 @@
+pub mod cancel;
+pub mod checksum;
+pub mod console;
 pub mod deployment_context;
 pub mod fs;
 pub mod init;
 pub mod logger;
+pub mod mask;
 pub mod parsers;
 pub mod progress;
 pub mod queries;
 pub mod script_writer;
+pub mod secret;
 pub mod serde;
 pub mod time;
 pub mod utils;
 
+pub use cancel::{CancelToken, SignalGuard, cancel_on_signal};
+pub use checksum::{scripts_checksum, sha256_hex};
+pub use console::{print_output, reserve_stdout};
 pub use deployment_context::{DeploymentContext, DeploymentContextOptions};
 pub use fs::validate_dir;
-pub use progress::ProgressReporter;
+pub use mask::{MASK, mask_arguments, mask_url};
+pub use progress::{ConnectionSide, Phase, ProgressEvent, ProgressReporter};
 pub use queries::{get_query, normalize_sql};
 pub use script_writer::{ScriptWriter, ScriptWriterOptions};
+pub use secret::{
+    SecretKey, SecretStore, is_encrypted, is_secret_reference, password_env_name, pending_key_file,
+    resolve_secret,
+};
 pub use serde::deserialize_opt_vec_from_string;
 pub use time::format_duration;
 pub use utils::{format_sql_list, indent_lines, objects_as_map};
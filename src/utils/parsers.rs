@@ -1,4 +1,4 @@
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
 
 pub fn parse_cutoff_date(input: &str) -> Result<NaiveDateTime, String> {
     if let Ok(dt) = NaiveDateTime::parse_from_str(input, "%Y.%m.%d:%H.%M.%S") {
@@ -16,6 +16,30 @@ pub fn parse_cutoff_date(input: &str) -> Result<NaiveDateTime, String> {
     ))
 }
 
+/// Parses a duration like `90d`: a number followed by `s`, `m`, `h`, `d` or `w`.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let invalid = || {
+        format!(
+            "Invalid duration: '{}'. Expected a number followed by s, m, h, d or w, e.g. '90d'",
+            input
+        )
+    };
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (value, unit) = input.split_at(split);
+    let value: i64 = value.parse().map_err(|_| invalid())?;
+    match unit {
+        "s" => Ok(Duration::seconds(value)),
+        "m" => Ok(Duration::minutes(value)),
+        "h" => Ok(Duration::hours(value)),
+        "d" => Ok(Duration::days(value)),
+        "w" => Ok(Duration::weeks(value)),
+        _ => Err(invalid()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,4 +78,14 @@ mod tests {
                 .contains("Invalid date format: '2025-11-07'")
         );
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90d").unwrap(), Duration::days(90));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_duration("90").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("90 days").is_err());
+    }
 }
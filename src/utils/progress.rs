@@ -1,16 +1,202 @@
+use std::fmt;
+
+use serde::Serialize;
+use strum_macros::Display;
 use tokio::sync::mpsc;
 
+/// A phase of a deployment, reported by [`ProgressEvent::PhaseStarted`] and
+/// [`ProgressEvent::PhaseFinished`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Phase {
+    Prepare,
+    Apply,
+    Rollback,
+    Verify,
+}
+
+/// Connection the objects were fetched from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ConnectionSide {
+    Source,
+    Target,
+}
+
+/// Progress of a deployment operation. Indexes are 1-based, `total` is the number of
+/// items of the same kind in the operation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// Free-form status message
+    Message {
+        message: String,
+    },
+    PhaseStarted {
+        phase: Phase,
+    },
+    PhaseFinished {
+        phase: Phase,
+        success: bool,
+    },
+    ObjectsFetched {
+        side: ConnectionSide,
+        count: usize,
+    },
+    ChangesetCreated {
+        index: usize,
+        total: usize,
+        object: String,
+    },
+    ChangeExecuting {
+        change_id: i32,
+        index: usize,
+        total: usize,
+        object: String,
+    },
+    ChangeSucceeded {
+        change_id: i32,
+        index: usize,
+        total: usize,
+        object: String,
+    },
+    ChangeFailed {
+        change_id: i32,
+        index: usize,
+        total: usize,
+        object: String,
+        error: String,
+    },
+    HookStarted {
+        stage: String,
+        index: usize,
+        total: usize,
+    },
+    HookFinished {
+        stage: String,
+        index: usize,
+        total: usize,
+        error: Option<String>,
+    },
+}
+
+impl ProgressEvent {
+    /// Position and length of counted events, used to drive progress bars.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            ProgressEvent::ChangesetCreated { index, total, .. }
+            | ProgressEvent::ChangeExecuting { index, total, .. }
+            | ProgressEvent::ChangeSucceeded { index, total, .. }
+            | ProgressEvent::ChangeFailed { index, total, .. } => Some((*index, *total)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProgressEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgressEvent::Message { message } => write!(f, "{}", message),
+            ProgressEvent::PhaseStarted { phase } => write!(f, "Starting {}...", phase),
+            ProgressEvent::PhaseFinished { phase, success } => {
+                if *success {
+                    write!(f, "✅ {} completed", phase)
+                } else {
+                    write!(f, "❌ {} failed", phase)
+                }
+            }
+            ProgressEvent::ObjectsFetched { side, count } => {
+                write!(f, "Fetched {} {} objects", count, side)
+            }
+            ProgressEvent::ChangesetCreated {
+                index,
+                total,
+                object,
+            } => write!(
+                f,
+                "Creating changeset {} of {} for '{}'",
+                index, total, object
+            ),
+            ProgressEvent::ChangeExecuting { object, .. } => {
+                write!(f, "Executing change for '{}'", object)
+            }
+            ProgressEvent::ChangeSucceeded { object, .. } => {
+                write!(f, "Applied change for '{}'", object)
+            }
+            ProgressEvent::ChangeFailed { object, error, .. } => {
+                write!(f, "Change for '{}' failed: {}", object, error)
+            }
+            ProgressEvent::HookStarted {
+                stage,
+                index,
+                total,
+            } => write!(f, "Executing {} hook {} of {}", stage, index, total),
+            ProgressEvent::HookFinished {
+                stage,
+                index,
+                error,
+                ..
+            } => match error {
+                Some(error) => write!(f, "❌ {} hook {} failed: {}", stage, index, error),
+                None => write!(f, "✅ {} hook {} completed", stage, index),
+            },
+        }
+    }
+}
+
+impl From<String> for ProgressEvent {
+    fn from(message: String) -> Self {
+        ProgressEvent::Message { message }
+    }
+}
+
+impl From<&str> for ProgressEvent {
+    fn from(message: &str) -> Self {
+        ProgressEvent::Message {
+            message: message.to_string(),
+        }
+    }
+}
+
 #[derive(Debug)]
-pub struct ProgressReporter(Option<mpsc::UnboundedSender<String>>);
+pub struct ProgressReporter(Option<mpsc::UnboundedSender<ProgressEvent>>);
 
 impl ProgressReporter {
-    pub fn new(tx: Option<mpsc::UnboundedSender<String>>) -> Self {
+    pub fn new(tx: Option<mpsc::UnboundedSender<ProgressEvent>>) -> Self {
         Self(tx)
     }
 
-    pub fn report(&self, message: impl Into<String>) {
+    pub fn report(&self, event: impl Into<ProgressEvent>) {
         if let Some(tx) = &self.0 {
-            let _ = tx.send(message.into());
+            let _ = tx.send(event.into());
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_event_json() {
+        let event = ProgressEvent::ChangeFailed {
+            change_id: 4,
+            index: 2,
+            total: 5,
+            object: "VIEW HR.V_EMP".to_string(),
+            error: "ORA-00942".to_string(),
+        };
+
+        let value = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(value["event"], "change_failed");
+        assert_eq!(value["change_id"], 4);
+        assert_eq!(event.position(), Some((2, 5)));
+        assert_eq!(
+            serde_json::to_value(ProgressEvent::from("hello")).unwrap(),
+            serde_json::json!({"event": "message", "message": "hello"})
+        );
+    }
+}
@@ -43,6 +43,8 @@ pub struct ScriptWriter {
     // These targets are now wrapped in Option. If None, writing is disabled.
     script_target: Option<ScriptTarget>,
     rollback_target: Option<ScriptTarget>,
+    // Free-form summary, the file is only created on the first write.
+    summary_target: Option<ScriptTarget>,
     // Separator is also optional, only present if writing is enabled.
     script_sep: Option<String>,
 
@@ -64,6 +66,7 @@ impl ScriptWriter {
                     _dir: None,
                     script_target: None,
                     rollback_target: None,
+                    summary_target: None,
                     script_sep: None,
                     mode: ScriptWriterMode::Disabled,
                 });
@@ -80,6 +83,7 @@ impl ScriptWriter {
 
                 let script_file = dir.join(format!("scripts-{}.sql", timestamp));
                 let rollback_file = dir.join(format!("rollback_scripts-{}.sql", timestamp));
+                let summary_file = dir.join(format!("summary-{}.txt", timestamp));
 
                 std::fs::File::create(&script_file)?;
                 std::fs::File::create(&rollback_file)?;
@@ -88,6 +92,7 @@ impl ScriptWriter {
                     _dir: Some(dir),
                     script_target: Some(ScriptTarget::File(script_file)),
                     rollback_target: Some(ScriptTarget::File(rollback_file)),
+                    summary_target: Some(ScriptTarget::File(summary_file)),
                     script_sep: Some(script_sep),
                     mode: ScriptWriterMode::File, // Add this
                 })
@@ -97,6 +102,7 @@ impl ScriptWriter {
                 _dir: None,
                 script_target: Some(ScriptTarget::Memory(String::new())),
                 rollback_target: Some(ScriptTarget::Memory(String::new())),
+                summary_target: Some(ScriptTarget::Memory(String::new())),
                 script_sep: Some(script_sep),
                 mode: ScriptWriterMode::Memory, // Add this
             }),
@@ -138,6 +144,15 @@ impl ScriptWriter {
         Self::append(target, sep)
     }
 
+    /// Appends content as is to the summary target.
+    /// If the writer is disabled, this is a no-op.
+    pub fn write_summary(&mut self, content: &str) -> Result<()> {
+        match self.summary_target.as_mut() {
+            Some(target) => Self::append(target, content),
+            None => Ok(()),
+        }
+    }
+
     /// Internal static function to append content to a target.
     /// It avoids adding an implicit newline, ensuring cleaner separation control.
     fn append(target: &mut ScriptTarget, content: &str) -> Result<()> {
@@ -171,6 +186,14 @@ impl ScriptWriter {
         }
     }
 
+    /// Retrieves summary content if the writer is in Memory mode and is enabled.
+    pub fn summary_content(&self) -> Option<&str> {
+        match self.summary_target.as_ref() {
+            Some(ScriptTarget::Memory(s)) => Some(s),
+            _ => None,
+        }
+    }
+
     pub fn get_script_target(&self) -> Option<&ScriptTarget> {
         self.script_target.as_ref()
     }
@@ -190,6 +213,14 @@ impl ScriptWriter {
             _ => None,
         }
     }
+
+    /// Returns the path to the summary file if a summary was written to a file.
+    pub fn summary_file_path(&self) -> Option<&Path> {
+        match &self.summary_target {
+            Some(ScriptTarget::File(path)) if path.exists() => Some(path.as_path()),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -307,6 +338,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_summary_is_written_as_is() -> Result<()> {
+        let tmp = tempdir()?;
+        let options = Some(ScriptWriterOptions {
+            dir: Some(tmp.path().to_path_buf()),
+            script_sep: None,
+        });
+        let mut writer = ScriptWriter::new(options)?;
+
+        // The summary file is only created when something is written
+        assert!(writer.summary_file_path().is_none());
+
+        writer.write_summary("TABLE SCHEMA1.EMP\n")?;
+        writer.write_summary("VIEW SCHEMA1.EMP_V\n")?;
+
+        let path = writer.summary_file_path().expect("summary file is created");
+        let content = read_and_normalize(path)?;
+        assert_eq!(content, "TABLE SCHEMA1.EMP\nVIEW SCHEMA1.EMP_V\n");
+
+        Ok(())
+    }
+
     #[test]
     fn test_default_separator_in_files() -> Result<()> {
         let tmp = tempdir()?;
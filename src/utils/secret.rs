@@ -0,0 +1,370 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+use crate::{config::SecretsConfig, utils::MASK};
+
+/// Prefix of passwords encrypted with a [`SecretKey`]
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+const REFERENCE_PREFIXES: [&str; 3] = ["env:", "file:", "cmd:"];
+
+const NONCE_LEN: usize = 12;
+
+/// Resolves a secret reference: `env:NAME` reads an environment variable,
+/// `file:PATH` reads a file and `cmd:COMMAND` runs a shell command, with the
+/// trailing newline of the file or the output removed.
+pub fn resolve_secret(reference: &str) -> Result<String> {
+    if let Some(name) = reference.strip_prefix("env:") {
+        return std::env::var(name).context(format!("Environment variable '{}' is not set", name));
+    }
+    if let Some(path) = reference.strip_prefix("file:") {
+        let secret = std::fs::read_to_string(path)
+            .context(format!("Failed to read secret file '{}'", path))?;
+        return Ok(secret.trim_end_matches(['\r', '\n']).to_string());
+    }
+    if let Some(command) = reference.strip_prefix("cmd:") {
+        return run_secret_command(command);
+    }
+    bail!(
+        "Expected a secret reference like 'env:NAME', 'file:PATH' or 'cmd:COMMAND', plain text is not allowed"
+    )
+}
+
+/// Whether a password is a reference resolved by [`resolve_secret`] when connecting.
+pub fn is_secret_reference(password: &str) -> bool {
+    REFERENCE_PREFIXES.iter().any(|p| password.starts_with(p))
+}
+
+fn run_secret_command(command: &str) -> Result<String> {
+    #[cfg(windows)]
+    let output = Command::new("cmd").args(["/C", command]).output();
+    #[cfg(not(windows))]
+    let output = Command::new("sh").args(["-c", command]).output();
+
+    let output = output.context(format!("Failed to run secret command '{}'", command))?;
+    ensure!(
+        output.status.success(),
+        "Secret command '{}' failed with {}: {}",
+        command,
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    let secret = String::from_utf8(output.stdout).context(format!(
+        "Secret command '{}' printed invalid UTF-8",
+        command
+    ))?;
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Name of the environment variable suggested for the password of a connection,
+/// `LEAF_<NAME>_PASSWORD` with every other character than letters and digits as `_`.
+pub fn password_env_name(connection: &str) -> String {
+    let name: String = connection
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("LEAF_{}_PASSWORD", name)
+}
+
+/// AES-256-GCM key encrypting connection passwords, stored base64 encoded.
+#[derive(Clone)]
+pub struct SecretKey(Key<Aes256Gcm>);
+
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(MASK)
+    }
+}
+
+impl SecretKey {
+    pub fn generate() -> Self {
+        Self(Aes256Gcm::generate_key(OsRng))
+    }
+
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .context("Encryption key is not valid base64")?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|b: Vec<u8>| anyhow!("Encryption key must be 32 bytes, got {}", b.len()))?;
+        Ok(Self(Key::<Aes256Gcm>::from(bytes)))
+    }
+
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.0)
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let encoded = std::fs::read_to_string(path)
+            .context(format!("Failed to read key file '{}'", path.display()))?;
+        Self::from_base64(&encoded).context(format!("Invalid key file '{}'", path.display()))
+    }
+
+    /// Writes the key to a file only the current user can read.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options
+            .open(path)
+            .context(format!("Failed to create key file '{}'", path.display()))?;
+        std::io::Write::write_all(&mut file, format!("{}\n", self.to_base64()).as_bytes())
+            .context(format!("Failed to write key file '{}'", path.display()))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&self.0)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("Failed to encrypt password"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload)))
+    }
+
+    pub fn decrypt(&self, encrypted: &str) -> Result<String> {
+        let payload = encrypted
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .and_then(|p| STANDARD.decode(p).ok())
+            .filter(|p| p.len() > NONCE_LEN)
+            .context("Password is not a valid encrypted value")?;
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into()?;
+        let plaintext = Aes256Gcm::new(&self.0)
+            .decrypt(&Nonce::from(nonce), ciphertext)
+            .map_err(|_| {
+                anyhow!("Failed to decrypt password, the encryption key does not match")
+            })?;
+        String::from_utf8(plaintext).context("Decrypted password is not valid UTF-8")
+    }
+}
+
+/// Encrypts connection passwords before they are stored and reveals them when connecting.
+///
+/// Stored passwords are either encrypted with the configured key, `env:`, `file:` or `cmd:`
+/// references resolved at connect time, or empty for directory connections.
+pub struct SecretStore {
+    config: SecretsConfig,
+}
+
+impl SecretStore {
+    pub fn new(config: &SecretsConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// Key file the key is read from, `None` if the key is set by `LEAF__SECRETS__KEY`.
+    pub fn key_file(&self) -> Option<&Path> {
+        match self.config.key {
+            Some(_) => None,
+            None => Some(&self.config.key_file),
+        }
+    }
+
+    /// The configured key, `None` if there is no key yet.
+    pub fn key(&self) -> Result<Option<SecretKey>> {
+        if let Some(key) = &self.config.key {
+            return SecretKey::from_base64(key)
+                .context("LEAF__SECRETS__KEY: invalid key")
+                .map(Some);
+        }
+        if !self.config.key_file.exists() {
+            return Ok(None);
+        }
+        SecretKey::read(&self.config.key_file).map(Some)
+    }
+
+    fn key_or_create(&self) -> Result<SecretKey> {
+        if let Some(key) = self.key()? {
+            return Ok(key);
+        }
+        let key = SecretKey::generate();
+        key.write(&self.config.key_file)?;
+        tracing::info!(
+            "Created encryption key file '{}'",
+            self.config.key_file.display()
+        );
+        Ok(key)
+    }
+
+    /// The form a password is stored in: encrypted, or as is for references, empty
+    /// passwords and passwords that are already encrypted.
+    pub fn seal(&self, password: &str) -> Result<String> {
+        if is_stored_form(password) {
+            return Ok(password.to_string());
+        }
+        self.key_or_create()?.encrypt(password)
+    }
+
+    /// The stored form of passwords saved in plain text by earlier versions, `None` for
+    /// passwords that are stored as is. Fails instead of creating a key when some of the
+    /// passwords are encrypted already, their key is missing and a new one can not read them.
+    pub fn seal_passwords(&self, passwords: &[String]) -> Result<Vec<Option<String>>> {
+        if !passwords.iter().any(|p| !is_stored_form(p)) {
+            return Ok(vec![None; passwords.len()]);
+        }
+        ensure!(
+            self.key()?.is_some() || !passwords.iter().any(|p| is_encrypted(p)),
+            "Passwords are encrypted but the key is missing, set LEAF__SECRETS__KEY or restore '{}'",
+            self.config.key_file.display()
+        );
+        passwords
+            .iter()
+            .map(|p| {
+                if is_stored_form(p) {
+                    Ok(None)
+                } else {
+                    self.seal(p).map(Some)
+                }
+            })
+            .collect()
+    }
+
+    /// Encrypts a password with `key` instead of the configured key.
+    pub fn seal_with(&self, key: &SecretKey, password: &str) -> Result<String> {
+        if is_stored_form(password) {
+            return Ok(password.to_string());
+        }
+        key.encrypt(password)
+    }
+
+    /// The plaintext of a stored password, decrypted or resolved from its reference.
+    /// Passwords stored in plain text before encryption at rest are rejected, the migrations
+    /// encrypt them.
+    pub fn reveal(&self, stored: &str) -> Result<String> {
+        if stored.starts_with(ENCRYPTED_PREFIX) {
+            let key = self.key()?.context(format!(
+                "Password is encrypted but there is no key, set LEAF__SECRETS__KEY or create '{}'",
+                self.config.key_file.display()
+            ))?;
+            return key.decrypt(stored);
+        }
+        if is_secret_reference(stored) {
+            return resolve_secret(stored);
+        }
+        ensure!(
+            stored.is_empty(),
+            "Password is stored in plain text, run `leaf db migrate up` to encrypt it"
+        );
+        Ok(String::new())
+    }
+}
+
+impl migration::PasswordSealer for SecretStore {
+    fn seal_passwords(
+        &self,
+        passwords: &[String],
+    ) -> std::result::Result<Vec<Option<String>>, String> {
+        SecretStore::seal_passwords(self, passwords).map_err(|e| format!("{:#}", e))
+    }
+}
+
+/// Whether a password is encrypted with a [`SecretKey`].
+pub fn is_encrypted(password: &str) -> bool {
+    password.starts_with(ENCRYPTED_PREFIX)
+}
+
+fn is_stored_form(password: &str) -> bool {
+    password.is_empty() || is_encrypted(password) || is_secret_reference(password)
+}
+
+/// Sibling of a key file the rotated key is written to before it replaces the file.
+pub fn pending_key_file(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".new");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_resolve_secret() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "s3cret").unwrap();
+        let reference = format!("file:{}", file.path().display());
+        assert_eq!(resolve_secret(&reference).unwrap(), "s3cret");
+
+        assert!(resolve_secret("env:LEAF_TEST_SECRET_THAT_IS_NOT_SET").is_err());
+        assert!(resolve_secret("s3cret").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_secret_command() {
+        assert_eq!(resolve_secret("cmd:echo s3cret").unwrap(), "s3cret");
+        assert!(resolve_secret("cmd:exit 3").is_err());
+    }
+
+    #[test]
+    fn test_password_env_name() {
+        assert_eq!(password_env_name("prod-db.1"), "LEAF_PROD_DB_1_PASSWORD");
+    }
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let key = SecretKey::generate();
+        let encrypted = key.encrypt("s3cret").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("s3cret"));
+        assert_ne!(encrypted, key.encrypt("s3cret").unwrap());
+        assert_eq!(key.decrypt(&encrypted).unwrap(), "s3cret");
+
+        let key = SecretKey::from_base64(&key.to_base64()).unwrap();
+        assert_eq!(key.decrypt(&encrypted).unwrap(), "s3cret");
+        assert!(SecretKey::generate().decrypt(&encrypted).is_err());
+        assert!(SecretKey::from_base64("c2hvcnQ=").is_err());
+        assert_eq!(format!("{:?}", key), MASK);
+    }
+
+    #[test]
+    fn test_secret_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SecretsConfig {
+            key: None,
+            key_file: dir.path().join("leaf.key"),
+        };
+        let store = SecretStore::new(&config);
+        assert!(store.key().unwrap().is_none());
+
+        let sealed = store.seal("s3cret").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(config.key_file.exists());
+        assert_eq!(store.seal(&sealed).unwrap(), sealed);
+        assert_eq!(store.reveal(&sealed).unwrap(), "s3cret");
+
+        assert_eq!(store.seal("env:DB_PASSWORD").unwrap(), "env:DB_PASSWORD");
+        assert_eq!(store.seal("").unwrap(), "");
+        assert!(store.reveal("legacy").is_err());
+        assert_eq!(store.reveal("").unwrap(), "");
+
+        let other = SecretStore::new(&SecretsConfig {
+            key: Some(SecretKey::generate().to_base64()),
+            key_file: config.key_file.clone(),
+        });
+        assert!(other.key_file().is_none());
+        assert!(other.reveal(&sealed).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::process::Command;
+
+use anyhow::Result;
+use leaf::{
+    config::Settings,
+    services::{AppServices, PlanSpec},
+};
+use serial_test::serial;
+
+use crate::{
+    common::{TestDatabase, load_test_env},
+    services::init_plan_test,
+};
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_run_events_jsonl_keeps_stdout_json() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            fail_fast: true,
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+
+    // Tagging, verification, collected scripts and the report all print human output
+    let output = Command::new(env!("CARGO_BIN_EXE_leaf"))
+        .args([
+            "plans",
+            "run",
+            "test",
+            "--cutoff-date",
+            "2000.01.01",
+            "--tag",
+            "v1.0",
+            "--verify",
+            "--collect-scripts",
+            "--show-report",
+            "--events",
+            "jsonl",
+        ])
+        .env("LEAF__DATABASE__URL", &database.url)
+        .env("LEAF__LOGS__FILE_ENABLED", "false")
+        .output()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "leaf plans run failed: {}", stderr);
+    assert!(stderr.contains("tagged 'v1.0'"));
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(!stdout.trim().is_empty());
+    for line in stdout.lines() {
+        let event: serde_json::Value = serde_json::from_str(line)
+            .map_err(|e| anyhow::anyhow!("stdout line is not JSON ({}): {}", e, line))?;
+        assert!(event.get("ts").is_some(), "event without ts: {}", line);
+    }
+
+    Ok(())
+}
@@ -5,5 +5,5 @@ pub mod seeds;
 
 pub use env::load_test_env;
 pub use oracle::{create_source_test_client, create_target_test_client};
-pub use repo::init_repo;
+pub use repo::{TestDatabase, init_repo};
 pub use seeds::{cleanup, create_source_objects, create_target_objects, init_source, init_target};
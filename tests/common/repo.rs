@@ -1,9 +1,91 @@
 use anyhow::{Context, Result};
 use leaf::db::migrations;
-use sea_orm::DatabaseConnection;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection};
+use tempfile::NamedTempFile;
 
 pub async fn init_repo(db: &DatabaseConnection) -> Result<()> {
     migrations::up(&db)
         .await
         .context("Failed to init repository objects.")
 }
+
+/// Empty repository database of one test. A temporary SQLite file by default, or a new
+/// database on the PostgreSQL or MySQL server of `LEAF_TEST_DATABASE_URL`, dropped with
+/// the value, so tests can run against a shared server.
+pub struct TestDatabase {
+    pub url: String,
+    server: Option<(String, String)>,
+    _file: Option<NamedTempFile>,
+}
+
+impl TestDatabase {
+    pub async fn new() -> Result<Self> {
+        let Ok(server_url) = std::env::var("LEAF_TEST_DATABASE_URL") else {
+            let file = NamedTempFile::new()?;
+            return Ok(Self {
+                url: "sqlite://".to_string() + file.path().to_str().unwrap(),
+                server: None,
+                _file: Some(file),
+            });
+        };
+
+        let name = format!("leaf_test_{}", uuid::Uuid::new_v4().simple());
+        let db = Database::connect(&server_url)
+            .await
+            .context("Failed to connect to LEAF_TEST_DATABASE_URL")?;
+        db.execute_unprepared(&format!("create database {}", name))
+            .await
+            .context(format!("Failed to create test database {}", name))?;
+
+        Ok(Self {
+            url: with_database(&server_url, &name),
+            server: Some((server_url, name)),
+            _file: None,
+        })
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        let Some((server_url, name)) = self.server.take() else {
+            return;
+        };
+        // Dropped from another thread, the test runtime may be shutting down
+        let _ = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            runtime.block_on(async {
+                let db = Database::connect(&server_url).await?;
+                let force = match db.get_database_backend() {
+                    sea_orm::DbBackend::Postgres => " with (force)",
+                    _ => "",
+                };
+                db.execute_unprepared(&format!("drop database if exists {}{}", name, force))
+                    .await?;
+                anyhow::Ok(())
+            })
+        })
+        .join();
+    }
+}
+
+/// `url` pointing to database `name` on the same server.
+fn with_database(url: &str, name: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url, None),
+    };
+    let authority_start = base.find("://").map(|i| i + 3).unwrap_or(0);
+    let path_start = base[authority_start..]
+        .find('/')
+        .map(|i| authority_start + i)
+        .unwrap_or(base.len());
+
+    let mut url = format!("{}/{}", &base[..path_start], name);
+    if let Some(query) = query {
+        url.push('?');
+        url.push_str(query);
+    }
+    url
+}
@@ -1,5 +1,8 @@
+pub mod test_config_service;
 pub mod test_connection_service;
 pub mod test_deployment_service;
+pub mod test_lock_service;
 pub mod test_plan_service;
+pub mod test_snapshot_service;
 
 pub use test_plan_service::init_plan_test;
@@ -0,0 +1,159 @@
+use std::io::Write;
+
+use anyhow::Result;
+use leaf::{
+    config::Settings,
+    declarative::{ConfigChange, ConfigFormat, LeafConfig},
+    services::AppServices,
+};
+use tempfile::NamedTempFile;
+
+use crate::common::{TestDatabase, init_repo, load_test_env};
+
+fn config(secret: &NamedTempFile, schemas: &str, approvals: i32) -> Result<LeafConfig> {
+    let yaml = format!(
+        r#"
+connections:
+  - name: dev
+    username: hr
+    password: file:{secret}
+    connection_string: dev:1521/ORCL
+  - name: prod
+    username: hr
+    password: file:{secret}
+    connection_string: prod:1521/ORCL
+rules:
+  exclude_object_types: [JOB]
+plans:
+  - name: release
+    source: dev
+    target: prod
+    schemas: {schemas}
+    required_approvals: {approvals}
+"#,
+        secret = secret.path().display(),
+    );
+    LeafConfig::parse(&yaml, ConfigFormat::Yaml)
+}
+
+#[tokio::test]
+async fn test_apply_config() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    let services = AppServices::new(&settings).await?;
+    init_repo(&services.connection_service.get_repo().get_db().await).await?;
+
+    let mut secret = NamedTempFile::new()?;
+    writeln!(secret, "s3cret")?;
+    let base_dir = std::env::current_dir()?;
+    let config_service = &services.config_service;
+
+    let diff = config_service
+        .diff(&config(&secret, "[HR]", 0)?, &base_dir, false)
+        .await?;
+    assert_eq!(diff.changes.len(), 3);
+    config_service.apply(&diff).await?;
+
+    let prod = services
+        .connection_service
+        .find_by_name("prod")
+        .await?
+        .unwrap();
+    // The reference is stored, not the password it resolves to
+    let reference = format!("file:{}", secret.path().display());
+    assert_eq!(prod.password, reference);
+    let secrets = services.connection_service.get_repo().secrets();
+    assert_eq!(secrets.reveal(&prod.password)?, "s3cret");
+    let plan = services
+        .plan_service
+        .find_by_name("release")
+        .await?
+        .unwrap();
+    assert_eq!(
+        plan.get_exclude_object_types(),
+        Some(vec!["JOB".to_string()])
+    );
+    assert!(plan.disable_all_drops);
+
+    // Applying the same file again changes nothing
+    let diff = config_service
+        .diff(&config(&secret, "[HR]", 0)?, &base_dir, false)
+        .await?;
+    assert!(diff.is_empty());
+    assert_eq!(diff.unchanged, 3);
+
+    let diff = config_service
+        .diff(&config(&secret, "[HR, SALES]", 2)?, &base_dir, false)
+        .await?;
+    assert_eq!(diff.changes.len(), 1);
+    assert!(
+        matches!(&diff.changes[0], ConfigChange::UpdatePlan { fields, .. } if fields.len() == 2)
+    );
+    config_service.apply(&diff).await?;
+
+    let updated = services
+        .plan_service
+        .find_by_name("release")
+        .await?
+        .unwrap();
+    assert_eq!(updated.id, plan.id);
+    assert_eq!(updated.get_schemas(), vec!["HR", "SALES"]);
+    assert_eq!(updated.required_approvals, 2);
+
+    // The export describes the same repository, with the references of the file
+    let exported = config_service.export().await?;
+    assert_eq!(
+        exported.connections[1].password.as_deref(),
+        Some(reference.as_str())
+    );
+    assert_eq!(exported.plans[0].schemas, vec!["HR", "SALES"]);
+
+    // Without --prune nothing is deleted
+    let empty = LeafConfig::default();
+    let diff = config_service.diff(&empty, &base_dir, false).await?;
+    assert!(diff.is_empty());
+    assert_eq!(diff.unmanaged.len(), 3);
+
+    let diff = config_service.diff(&empty, &base_dir, true).await?;
+    assert_eq!(diff.changes.len(), 3);
+    config_service.apply(&diff).await?;
+    assert!(services.plan_service.get_all().await?.is_empty());
+    assert!(services.connection_service.get_all().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_apply_config_validates_every_change_first() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    let services = AppServices::new(&settings).await?;
+    init_repo(&services.connection_service.get_repo().get_db().await).await?;
+
+    let mut secret = NamedTempFile::new()?;
+    writeln!(secret, "s3cret")?;
+    let base_dir = std::env::current_dir()?;
+    let config_service = &services.config_service;
+
+    let mut diff = config_service
+        .diff(&config(&secret, "[HR]", 0)?, &base_dir, false)
+        .await?;
+    let Some(ConfigChange::CreatePlan(plan)) = diff.changes.last_mut() else {
+        panic!("expected the plan to be created last");
+    };
+    plan.target = "missing".to_string();
+
+    let err = config_service.apply(&diff).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("Target connection 'missing' not found"));
+    // The connections created before the plan are not written either
+    assert!(services.connection_service.get_all().await?.is_empty());
+    assert!(services.plan_service.get_all().await?.is_empty());
+
+    Ok(())
+}
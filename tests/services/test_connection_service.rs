@@ -1,30 +1,47 @@
-use anyhow::Result;
-use leaf::{config::Settings, entities::ConnectionModel, services::AppServices};
+use std::io::Write;
 
-use crate::common::{init_repo, load_test_env};
+use anyhow::Result;
+use leaf::{
+    config::Settings,
+    db::migrations,
+    delta::CompareStatus,
+    entities::ConnectionModel,
+    services::{AppServices, CompareOptions, ConnectionEdit, PlanSpec},
+    types::{ConnectionKind, PlanStatus},
+    utils::{ProgressReporter, is_encrypted},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::ConnectionTrait;
+use tempfile::NamedTempFile;
+
+use crate::common::{TestDatabase, init_repo, load_test_env};
 
 #[tokio::test]
 async fn test_create_connection() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
     fn assert_connection(connection: &ConnectionModel) {
         assert_eq!(connection.name, "test");
         assert_eq!(connection.username, "test");
-        assert_eq!(connection.password, "test");
+        assert!(is_encrypted(&connection.password));
         assert_eq!(connection.connection_string, "test");
     }
 
     let connection = services
         .connection_service
-        .create("test", "test", "test", "test")
+        .create("test", "test", "test", "test", 0)
         .await?;
 
     assert_connection(&connection);
     let connection = services.connection_service.get_by_id(connection.id).await?;
     assert_connection(&connection);
+    let secrets = services.connection_service.get_repo().secrets();
+    assert_eq!(secrets.reveal(&connection.password)?, "test");
 
     Ok(())
 }
@@ -32,19 +49,21 @@ async fn test_create_connection() -> Result<()> {
 #[tokio::test]
 async fn test_create_connection_with_duplicate_name() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
     let res = services
         .connection_service
-        .create("test", "test", "test", "test")
+        .create("test", "test", "test", "test", 0)
         .await;
     assert!(res.is_ok());
 
     let res = services
         .connection_service
-        .create("TeST", "test", "test", "test")
+        .create("TeST", "test", "test", "test", 0)
         .await;
     assert!(res.is_err());
 
@@ -54,20 +73,22 @@ async fn test_create_connection_with_duplicate_name() -> Result<()> {
 #[tokio::test]
 async fn test_find_connection_by_name() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
     fn assert_connection(connection: &ConnectionModel) {
         assert_eq!(connection.name, "test");
         assert_eq!(connection.username, "test");
-        assert_eq!(connection.password, "test");
+        assert!(is_encrypted(&connection.password));
         assert_eq!(connection.connection_string, "test");
     }
 
     let connection = services
         .connection_service
-        .create("test", "test", "test", "test")
+        .create("test", "test", "test", "test", 0)
         .await?;
 
     assert_connection(&connection);
@@ -85,13 +106,15 @@ async fn test_find_connection_by_name() -> Result<()> {
 #[tokio::test]
 async fn test_delete_connection_by_name() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
     let connection = services
         .connection_service
-        .create("test", "test", "test", "test")
+        .create("test", "test", "test", "test", 0)
         .await?;
 
     let connection_name = connection.name.clone();
@@ -112,23 +135,25 @@ async fn test_delete_connection_by_name() -> Result<()> {
 #[tokio::test]
 async fn test_prune_connections() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
     services
         .connection_service
-        .create("test", "test", "test", "test")
+        .create("test", "test", "test", "test", 0)
         .await?;
 
     services
         .connection_service
-        .create("test2", "test", "test", "test")
+        .create("test2", "test", "test", "test", 0)
         .await?;
 
     services
         .connection_service
-        .create("test3", "test", "test", "test")
+        .create("test3", "test", "test", "test", 0)
         .await?;
 
     assert!(services.connection_service.prune().await.is_ok());
@@ -147,23 +172,25 @@ async fn test_prune_connections() -> Result<()> {
 #[tokio::test]
 async fn test_get_all_connections() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
     services
         .connection_service
-        .create("test", "test", "test", "test")
+        .create("test", "test", "test", "test", 0)
         .await?;
 
     services
         .connection_service
-        .create("test2", "test", "test", "test")
+        .create("test2", "test", "test", "test", 0)
         .await?;
 
     services
         .connection_service
-        .create("test3", "test", "test", "test")
+        .create("test3", "test", "test", "test", 0)
         .await?;
     let connections = services.connection_service.get_all().await?;
     assert_eq!(connections.len(), 3);
@@ -173,7 +200,9 @@ async fn test_get_all_connections() -> Result<()> {
 #[tokio::test]
 async fn test_ping_connection() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
@@ -188,6 +217,7 @@ async fn test_ping_connection() -> Result<()> {
             username.as_str(),
             password.as_str(),
             connection_string.as_str(),
+            0,
         )
         .await?;
 
@@ -203,6 +233,7 @@ async fn test_ping_connection() -> Result<()> {
             username.as_str(),
             "invalid_password",
             connection_string.as_str(),
+            0,
         )
         .await?;
 
@@ -220,7 +251,9 @@ async fn test_ping_connection() -> Result<()> {
 #[tokio::test]
 async fn test_test_connection() -> Result<()> {
     load_test_env();
-    let settings = Settings::new()?;
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
     let services = AppServices::new(&settings).await?;
     init_repo(&services.connection_service.get_repo().get_db().await).await?;
 
@@ -253,3 +286,296 @@ async fn test_test_connection() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_edit_connection_keeps_plans() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+    let services = AppServices::new(&settings).await?;
+    init_repo(&services.connection_service.get_repo().get_db().await).await?;
+
+    let source = services
+        .connection_service
+        .create("source", "test", "test", "test", 0)
+        .await?;
+    services
+        .connection_service
+        .create("target", "test", "test", "test", 0)
+        .await?;
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_all_drops: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let edit = ConnectionEdit {
+        rename: Some("source_eu".to_string()),
+        password: Some("rotated".to_string()),
+        ..Default::default()
+    };
+    let connection = services
+        .connection_service
+        .edit("source", &edit, false)
+        .await?;
+    assert_eq!(connection.id, source.id);
+    assert_eq!(connection.name, "source_eu");
+    assert_eq!(connection.username, "test");
+    let secrets = services.connection_service.get_repo().secrets();
+    assert_eq!(secrets.reveal(&connection.password)?, "rotated");
+
+    let plan = services
+        .plan_service
+        .find_by_name(&plan.name)
+        .await?
+        .expect("plan is kept");
+    assert_eq!(plan.source_connection_id, source.id);
+
+    let edit = ConnectionEdit {
+        rename: Some("TARGET".to_string()),
+        ..Default::default()
+    };
+    let res = services
+        .connection_service
+        .edit("source_eu", &edit, false)
+        .await;
+    assert!(res.is_err());
+
+    // New credentials failing the connection test are not saved
+    let edit = ConnectionEdit {
+        connection_string: Some("localhost:1/MISSING".to_string()),
+        ..Default::default()
+    };
+    let res = services
+        .connection_service
+        .edit("source_eu", &edit, true)
+        .await;
+    assert!(res.is_err());
+    let connection = services.connection_service.get_by_id(source.id).await?;
+    assert_eq!(connection.connection_string, "test");
+
+    let res = services
+        .connection_service
+        .edit("source_eu", &ConnectionEdit::default(), false)
+        .await;
+    assert!(res.is_err());
+
+    // Connections of a running plan are kept as they are
+    services
+        .plan_service
+        .set_status_by_name(&plan.name, PlanStatus::Running)
+        .await?;
+    let edit = ConnectionEdit {
+        rename: Some("source_us".to_string()),
+        ..Default::default()
+    };
+    let err = services
+        .connection_service
+        .edit("source_eu", &edit, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("running plan"));
+    let connection = services.connection_service.get_by_id(source.id).await?;
+    assert_eq!(connection.name, "source_eu");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_connection_passwords_at_rest() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let dir = tempfile::tempdir()?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+    settings.secrets.key = None;
+    settings.secrets.key_file = dir.path().join("leaf.key");
+
+    let services = AppServices::new(&settings).await?;
+    let db = services.connection_service.get_repo().get_db().await;
+    up_to_password_encryption(&db).await?;
+
+    // A password stored in plain text before encryption at rest
+    db.execute_unprepared(
+        "insert into connections (name, username, password, connection_string) \
+         values ('legacy', 'test', 'plain', 'test')",
+    )
+    .await?;
+    // Only the application has the key to encrypt it with
+    assert!(migrations::up(&db).await.is_err());
+    migrations::up_with_secrets(&db, &settings).await?;
+    assert!(settings.secrets.key_file.exists());
+    let legacy = services
+        .connection_service
+        .find_by_name("legacy")
+        .await?
+        .unwrap();
+    assert!(is_encrypted(&legacy.password));
+    assert!(!format!("{:?}", legacy).contains(&legacy.password));
+
+    let mut secret = NamedTempFile::new()?;
+    writeln!(secret, "s3cret")?;
+    let reference = format!("file:{}", secret.path().display());
+    let referenced = services
+        .connection_service
+        .create("referenced", "test", &reference, "test", 0)
+        .await?;
+    assert_eq!(referenced.password, reference);
+
+    let key = std::fs::read_to_string(&settings.secrets.key_file)?;
+    let (count, path) = services.connection_service.rotate_key(None).await?;
+    assert_eq!(count, 1);
+    assert_eq!(path, settings.secrets.key_file);
+    assert_ne!(std::fs::read_to_string(&path)?, key);
+
+    let secrets = services.connection_service.get_repo().secrets();
+    let rotated = services.connection_service.get_by_id(legacy.id).await?;
+    assert_ne!(rotated.password, legacy.password);
+    assert_eq!(secrets.reveal(&rotated.password)?, "plain");
+    let referenced = services.connection_service.get_by_id(referenced.id).await?;
+    assert_eq!(referenced.password, reference);
+    assert_eq!(secrets.reveal(&referenced.password)?, "s3cret");
+
+    Ok(())
+}
+
+/// Runs the migrations of the versions that stored passwords in plain text
+async fn up_to_password_encryption(db: &sea_orm::DatabaseConnection) -> Result<()> {
+    let steps = Migrator::migrations()
+        .iter()
+        .position(|m| m.name().ends_with("_encrypt_connection_passwords"))
+        .expect("Password encryption migration not found");
+    Migrator::up(db, Some(steps as u32)).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_password_migration_never_creates_a_second_key() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let dir = tempfile::tempdir()?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+    settings.secrets.key = None;
+    settings.secrets.key_file = dir.path().join("leaf.key");
+
+    let services = AppServices::new(&settings).await?;
+    let db = services.connection_service.get_repo().get_db().await;
+    up_to_password_encryption(&db).await?;
+
+    // Encrypted with a key that is not available, next to a plain text password
+    let encrypted = leaf::utils::SecretKey::generate().encrypt("s3cret")?;
+    db.execute_unprepared(&format!(
+        "insert into connections (name, username, password, connection_string) \
+         values ('encrypted', 'test', '{}', 'test'), ('legacy', 'test', 'plain', 'test')",
+        encrypted
+    ))
+    .await?;
+
+    let err = migrations::up_with_secrets(&db, &settings)
+        .await
+        .expect_err("Migration should fail without the key");
+    assert!(err.to_string().contains("key is missing"));
+    assert!(!settings.secrets.key_file.exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_directory_connection() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+    let services = AppServices::new(&settings).await?;
+    init_repo(&services.connection_service.get_repo().get_db().await).await?;
+
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir_all(dir.path().join("HR/VIEW"))?;
+
+    let connection = services
+        .connection_service
+        .create_directory("repo", dir.path(), 0)
+        .await?;
+    assert_eq!(connection.kind, ConnectionKind::Directory);
+    assert_eq!(
+        connection.connection_string,
+        dir.path().canonicalize()?.to_string_lossy()
+    );
+    services.connection_service.ping("repo").await?;
+
+    let res = services
+        .connection_service
+        .create_directory("missing", &dir.path().join("missing"), 0)
+        .await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_compare_directory_connections() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+    let services = AppServices::new(&settings).await?;
+    init_repo(&services.connection_service.get_repo().get_db().await).await?;
+
+    let source = tempfile::tempdir()?;
+    let target = tempfile::tempdir()?;
+    for (dir, ddl) in [
+        (&source, "CREATE VIEW V AS SELECT 2 A FROM DUAL"),
+        (&target, "CREATE VIEW V AS SELECT 1 A FROM DUAL"),
+    ] {
+        std::fs::create_dir_all(dir.path().join("HR/VIEW"))?;
+        std::fs::write(dir.path().join("HR/VIEW/V.sql"), ddl)?;
+    }
+    std::fs::create_dir_all(source.path().join("HR/TABLE"))?;
+    std::fs::write(
+        source.path().join("HR/TABLE/T.sql"),
+        "CREATE TABLE T (ID NUMBER)",
+    )?;
+    services
+        .connection_service
+        .create_directory("source", source.path(), 0)
+        .await?;
+    services
+        .connection_service
+        .create_directory("target", target.path(), 0)
+        .await?;
+
+    let comparisons = services
+        .connection_service
+        .compare(
+            "source",
+            "target",
+            CompareOptions {
+                schemas: vec!["HR".to_string()],
+                ..Default::default()
+            },
+            &ProgressReporter::new(None),
+        )
+        .await?;
+
+    let statuses: Vec<(&str, CompareStatus)> = comparisons
+        .iter()
+        .map(|c| (c.object_name.as_str(), c.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("T", CompareStatus::OnlyInSource),
+            ("V", CompareStatus::Different)
+        ]
+    );
+
+    Ok(())
+}
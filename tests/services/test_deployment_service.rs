@@ -1,15 +1,30 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use leaf::{
     config::Settings,
     entities::DeploymentModel,
+    errors::{
+        ApprovalRequiredError, DeploymentCancelledError, DriftDetectedError, RollbackChainError,
+        TagInUseError,
+    },
     oracle::OracleClient,
-    services::AppServices,
-    utils::{DeploymentContext, ProgressReporter},
+    repo::{
+        ChangeRepository, ChangesetRepository, DeploymentRepository, PlanRepository,
+        RollbackRepository,
+    },
+    services::{AppServices, PlanSpec},
+    types::{
+        ChangeStatus, ChangesetStatus, DeploymentStatus, DriftKind, DriftPolicy, PlanStatus,
+        RetentionPolicy, RollbackTarget, VerificationStatus,
+    },
+    utils::{CancelToken, DeploymentContext, DeploymentContextOptions, ProgressReporter},
 };
+use sea_orm::{ActiveValue::Set, IntoActiveModel};
 use serial_test::serial;
-use tempfile::NamedTempFile;
 
-use crate::services::init_plan_test;
+use crate::{
+    common::{TestDatabase, create_source_test_client, create_target_test_client, load_test_env},
+    services::{init_plan_test, test_plan_service::create_connections},
+};
 
 async fn get_target_client(
     deployment: &DeploymentModel,
@@ -22,9 +37,11 @@ async fn get_target_client(
         .get_by_id(plan.target_connection_id)
         .await?;
 
+    // Passwords are stored encrypted
+    let secrets = services.connection_service.get_repo().secrets();
     OracleClient::connect(
         &target_connection.username,
-        &target_connection.password,
+        &secrets.reveal(&target_connection.password)?,
         &target_connection.connection_string,
     )
 }
@@ -127,9 +144,10 @@ async fn check_schema2_bonus_not_exists(
 #[tokio::test]
 #[serial(oracle)]
 async fn test_run_deployment_single_schema() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -137,19 +155,14 @@ async fn test_run_deployment_single_schema() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false, // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
@@ -180,9 +193,10 @@ async fn test_run_deployment_single_schema() -> Result<()> {
 #[tokio::test]
 #[serial(oracle)]
 async fn test_run_deployment() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -190,19 +204,14 @@ async fn test_run_deployment() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string(), "SCHEMA2".to_string()],
-            None,
-            None,
-            None,
-            false, // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
@@ -234,9 +243,10 @@ async fn test_run_deployment() -> Result<()> {
 #[tokio::test]
 #[serial(oracle)]
 async fn test_run_deployment_exclude_object_types() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -244,19 +254,15 @@ async fn test_run_deployment_exclude_object_types() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string(), "SCHEMA2".to_string()],
-            Some(vec!["TABLE".to_string()]),
-            None,
-            None,
-            false, // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            exclude_object_types: Some(vec!["TABLE".to_string()]),
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
@@ -287,9 +293,10 @@ async fn test_run_deployment_exclude_object_types() -> Result<()> {
 #[tokio::test]
 #[serial(oracle)]
 async fn test_run_deployment_exclude_object_names() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -297,19 +304,15 @@ async fn test_run_deployment_exclude_object_names() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string(), "SCHEMA2".to_string()],
-            None,
-            Some(vec!["BONUS".to_string()]),
-            None,
-            false, // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            exclude_object_names: Some(vec!["BONUS".to_string()]),
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
@@ -340,9 +343,10 @@ async fn test_run_deployment_exclude_object_names() -> Result<()> {
 #[tokio::test]
 #[serial(oracle)]
 async fn test_run_deployment_disabled_drop_types() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -350,19 +354,15 @@ async fn test_run_deployment_disabled_drop_types() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string(), "SCHEMA2".to_string()],
-            None,
-            None,
-            Some(vec!["TABLE".to_string(), "COLUMN".to_string()]),
-            false, // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            disabled_drop_types: Some(vec!["TABLE".to_string(), "COLUMN".to_string()]),
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
@@ -395,9 +395,10 @@ async fn test_run_deployment_disabled_drop_types() -> Result<()> {
 #[tokio::test]
 #[serial(oracle)]
 async fn test_run_deployment_with_disable_all_drops() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -405,19 +406,15 @@ async fn test_run_deployment_with_disable_all_drops() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string(), "SCHEMA2".to_string()],
-            None,
-            None,
-            None,
-            true,  // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            disable_all_drops: true,
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
@@ -450,9 +447,10 @@ async fn test_run_deployment_with_disable_all_drops() -> Result<()> {
 #[tokio::test]
 #[serial(oracle)]
 async fn test_run_deployment_disable_all_drops_vs_disabled_drop_types() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -462,19 +460,15 @@ async fn test_run_deployment_disable_all_drops_vs_disabled_drop_types() -> Resul
     // but also skip target object processing (no DROP TABLE operations)
     let plan1 = services
         .plan_service
-        .create(
-            "test_disable_all",
-            "source",
-            "target",
-            &["SCHEMA1".to_string(), "SCHEMA2".to_string()],
-            None,
-            None,
-            None,
-            true,  // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test_disable_all".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            disable_all_drops: true,
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
@@ -505,9 +499,10 @@ async fn test_run_deployment_disable_all_drops_vs_disabled_drop_types() -> Resul
 #[tokio::test]
 #[serial(oracle)]
 async fn test_rollback_deployment() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     init_plan_test(&settings).await?;
 
@@ -515,19 +510,14 @@ async fn test_rollback_deployment() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string(), "SCHEMA2".to_string()],
-            None,
-            None,
-            None,
-            false, // disable_all_drops
-            true,  // fail_fast
-            false, // disable_hooks
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
         .await?;
 
     let plan_id = plan.id.clone();
@@ -552,9 +542,29 @@ async fn test_rollback_deployment() -> Result<()> {
         _ => panic!("Deployment id is not returned"),
     }
 
+    // A dry run only collects the rollback scripts
+    let mut dry_ctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
+        true, true, None, None, None,
+    )))?;
+    services
+        .deployment_service
+        .rollback(plan_id, Some(true), &mut dry_ctx)
+        .await?;
+    assert!(!dry_ctx.rollback_content().unwrap_or_default().is_empty());
+    assert!(
+        dry_ctx
+            .summary_content()
+            .unwrap_or_default()
+            .contains("SCHEMA1.EMP")
+    );
+    assert_eq!(
+        services.deployment_service.get_by_id(1).await?.status,
+        DeploymentStatus::Success
+    );
+
     services
         .deployment_service
-        .rollback(plan_id, Some(true), ProgressReporter::new(None))
+        .rollback(plan_id, Some(true), &mut DeploymentContext::default())
         .await?;
 
     check_schema1_emp_rolled_back(&services.deployment_service.get_by_id(1).await?, &services)
@@ -562,3 +572,1312 @@ async fn test_rollback_deployment() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_rollback_to_tag() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+
+    let deployment_id = services
+        .deployment_service
+        .run(
+            plan.id,
+            false,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+
+    services
+        .deployment_service
+        .tag_deployment(deployment_id, "v1.0")
+        .await?;
+
+    let tagged = services
+        .deployment_service
+        .find_by_tag(plan.id, "v1.0")
+        .await?
+        .expect("Tagged deployment is not found");
+    assert_eq!(tagged.id, deployment_id);
+
+    // Nothing was deployed after the tagged deployment
+    let reverted = services
+        .deployment_service
+        .rollback_to(
+            plan.id,
+            RollbackTarget::Tag("v1.0".to_string()),
+            Some(true),
+            &mut DeploymentContext::default(),
+        )
+        .await?;
+    assert!(reverted.is_empty());
+
+    // Unknown rollback points are rejected
+    let reverted = services
+        .deployment_service
+        .rollback_to(
+            plan.id,
+            RollbackTarget::Deployment(0),
+            Some(true),
+            &mut DeploymentContext::default(),
+        )
+        .await;
+    assert!(reverted.is_err());
+
+    let missing = services
+        .deployment_service
+        .rollback_to(
+            plan.id,
+            RollbackTarget::Tag("v0.9".to_string()),
+            Some(true),
+            &mut DeploymentContext::default(),
+        )
+        .await;
+    assert!(missing.is_err());
+
+    // Three deployments after the tag are reverted newest first
+    let mut chain = Vec::new();
+    for column in ["D2", "D3", "D4"] {
+        chain.push(deploy_schema1_emp_column(plan.id, column, cutoff_date, &services).await?);
+    }
+    assert_eq!(schema1_emp_columns()?, vec!["D2", "D3", "D4", "ID"]);
+
+    let reverted = services
+        .deployment_service
+        .rollback_to(
+            plan.id,
+            RollbackTarget::Tag("v1.0".to_string()),
+            Some(true),
+            &mut DeploymentContext::default(),
+        )
+        .await?;
+    chain.reverse();
+    assert_eq!(reverted, chain);
+    assert_eq!(schema1_emp_columns()?, vec!["ID"]);
+    for id in &chain {
+        assert_eq!(
+            services.deployment_service.get_by_id(*id).await?.status,
+            DeploymentStatus::RolledBack
+        );
+    }
+    assert_eq!(
+        services
+            .deployment_service
+            .get_by_id(deployment_id)
+            .await?
+            .status,
+        DeploymentStatus::Success
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_rollback_to_tag_stops_at_first_failure() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+    let tagged = services
+        .deployment_service
+        .run(
+            plan.id,
+            false,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+    services
+        .deployment_service
+        .tag_deployment(tagged, "v1.0")
+        .await?;
+
+    let mut chain = Vec::new();
+    for column in ["D2", "D3", "D4"] {
+        chain.push(deploy_schema1_emp_column(plan.id, column, cutoff_date, &services).await?);
+    }
+
+    // The rollback of the middle deployment fails, the column it drops is gone
+    create_target_test_client()?
+        .execute("ALTER TABLE SCHEMA1.EMP DROP COLUMN D3")
+        .await?;
+
+    let err = services
+        .deployment_service
+        .rollback_to(
+            plan.id,
+            RollbackTarget::Tag("v1.0".to_string()),
+            Some(true),
+            &mut DeploymentContext::default(),
+        )
+        .await
+        .expect_err("Rollback should fail");
+    let chain_err = err
+        .downcast_ref::<RollbackChainError>()
+        .expect("Rollback chain error is not returned");
+    assert_eq!(chain_err.reverted, vec![chain[2]]);
+    assert_eq!(chain_err.failed, chain[1]);
+    assert_eq!(chain_err.skipped, vec![chain[0]]);
+
+    // The deployment before the failure is left deployed
+    assert_eq!(schema1_emp_columns()?, vec!["D2", "ID"]);
+    for (id, status) in [
+        (chain[2], DeploymentStatus::RolledBack),
+        (chain[1], DeploymentStatus::RollbackError),
+        (chain[0], DeploymentStatus::Success),
+    ] {
+        assert_eq!(
+            services.deployment_service.get_by_id(id).await?.status,
+            status
+        );
+    }
+
+    Ok(())
+}
+
+/// Adds a column to SCHEMA1.EMP in the source and deploys it, returns the deployment id.
+async fn deploy_schema1_emp_column(
+    plan_id: i32,
+    column: &str,
+    cutoff_date: chrono::NaiveDateTime,
+    services: &AppServices,
+) -> Result<i32> {
+    create_source_test_client()?
+        .execute(&format!("ALTER TABLE SCHEMA1.EMP ADD ({} NUMBER)", column))
+        .await?;
+    services
+        .deployment_service
+        .run(
+            plan_id,
+            false,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .context(format!("Deployment of column {} is not returned", column))
+}
+
+fn schema1_emp_columns() -> Result<Vec<String>> {
+    let client = create_target_test_client()?;
+    let query = r#"SELECT column_name FROM all_tab_columns WHERE table_name = 'EMP' AND owner = 'SCHEMA1' ORDER BY column_name"#;
+    let rows = client.conn.query(query, &[])?;
+    Ok(rows
+        .map(|row| row?.get(0))
+        .collect::<Result<Vec<String>, _>>()?)
+}
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_apply_with_drifted_target() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+
+    let deployment_id = services
+        .deployment_service
+        .prepare(
+            plan.id,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+
+    let deployment = services.deployment_service.get_by_id(deployment_id).await?;
+    let client = get_target_client(&deployment, &services).await?;
+    client
+        .execute("ALTER TABLE SCHEMA1.EMP ADD (DRIFTED NUMBER)")
+        .await?;
+
+    let res = services
+        .deployment_service
+        .apply(
+            deployment_id,
+            true,
+            Some(true),
+            DriftPolicy::Abort,
+            &mut DeploymentContext::default(),
+        )
+        .await;
+    let err = res.expect_err("Apply should be aborted on drift");
+    let drift = err
+        .downcast_ref::<DriftDetectedError>()
+        .expect("Drift error is not returned");
+    assert!(drift.objects.iter().any(|o| o.contains("SCHEMA1.EMP")));
+
+    let deployment = services.deployment_service.get_by_id(deployment_id).await?;
+    assert_eq!(deployment.status, DeploymentStatus::Idle);
+
+    services
+        .deployment_service
+        .apply(
+            deployment_id,
+            true,
+            Some(true),
+            DriftPolicy::Refresh,
+            &mut DeploymentContext::default(),
+        )
+        .await?;
+
+    // The refreshed changes also drop the drifted column
+    check_schema1_emp_deployed(&deployment, &services).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_verify_deployment() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+
+    let deployment_id = services
+        .deployment_service
+        .run(
+            plan.id,
+            false,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+
+    let mismatched = services
+        .deployment_service
+        .verify(deployment_id, &mut DeploymentContext::default())
+        .await?;
+    assert!(mismatched.is_empty());
+
+    let changesets = services
+        .deployment_service
+        .find_changesets_by_deployment_id(deployment_id)
+        .await?;
+    assert!(
+        changesets
+            .iter()
+            .filter(|c| c.verification_status.is_some())
+            .all(|c| c.verification_status == Some(VerificationStatus::Verified))
+    );
+
+    // Change a deployed object behind leaf's back
+    let deployment = services.deployment_service.get_by_id(deployment_id).await?;
+    let client = get_target_client(&deployment, &services).await?;
+    client
+        .execute("ALTER TABLE SCHEMA1.EMP ADD (UNEXPECTED NUMBER)")
+        .await?;
+
+    let mismatched = services
+        .deployment_service
+        .verify(deployment_id, &mut DeploymentContext::default())
+        .await?;
+    assert_eq!(mismatched.len(), 1);
+    assert_eq!(mismatched[0].object_name, "EMP");
+    assert_eq!(
+        mismatched[0].verification_status,
+        Some(VerificationStatus::Mismatch)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_apply_requires_approvals() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+    services
+        .plan_service
+        .set_required_approvals("test", 2)
+        .await?;
+
+    let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+
+    // Plans requiring approvals can not be run in one step
+    let res = services
+        .deployment_service
+        .run(
+            plan.id,
+            false,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await;
+    assert!(res.is_err());
+
+    let deployment_id = services
+        .deployment_service
+        .prepare(
+            plan.id,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+
+    services
+        .deployment_service
+        .approve(deployment_id, "alice", Some("looks good"))
+        .await?;
+    // The same approver can not approve twice
+    assert!(
+        services
+            .deployment_service
+            .approve(deployment_id, "ALICE", None)
+            .await
+            .is_err()
+    );
+
+    let err = services
+        .deployment_service
+        .apply(
+            deployment_id,
+            true,
+            Some(true),
+            DriftPolicy::Abort,
+            &mut DeploymentContext::default(),
+        )
+        .await
+        .expect_err("Apply should require approvals");
+    let approval = err
+        .downcast_ref::<ApprovalRequiredError>()
+        .expect("Approval error is not returned");
+    assert_eq!(approval.required, 2);
+    assert_eq!(approval.approved, 1);
+
+    services
+        .deployment_service
+        .approve(deployment_id, "bob", None)
+        .await?;
+
+    services
+        .deployment_service
+        .apply(
+            deployment_id,
+            true,
+            Some(true),
+            DriftPolicy::Abort,
+            &mut DeploymentContext::default(),
+        )
+        .await?;
+
+    let deployment = services.deployment_service.get_by_id(deployment_id).await?;
+    assert_eq!(deployment.status, DeploymentStatus::Success);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recover_interrupted_deployment() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+    settings.locks.stale_after_secs = 0;
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+
+    // Simulate a process killed while applying the second change
+    let db = services.connection_service.get_repo().get_db().await;
+    let deployment_repo = DeploymentRepository::new(db.clone());
+    let changeset_repo = ChangesetRepository::new(db.clone());
+    let change_repo = ChangeRepository::new(db.clone());
+
+    let deployment = deployment_repo
+        .create(
+            plan.id,
+            None,
+            chrono::Utc::now().naive_utc(),
+            String::new(),
+            true,
+            None,
+        )
+        .await?;
+    deployment_repo
+        .set_status(deployment.id, DeploymentStatus::Running)
+        .await?;
+    deployment_repo
+        .set_owner(deployment.id, 4242, "build-agent", "ci")
+        .await?;
+    PlanRepository::new(db.clone())
+        .set_status(plan.id, PlanStatus::Running)
+        .await?;
+
+    let changeset = changeset_repo
+        .create(deployment.id, "TABLE", "EMP", "SCHEMA1", None, None)
+        .await?;
+    changeset_repo
+        .set_status(changeset.id, ChangesetStatus::Running)
+        .await?;
+    let applied = change_repo
+        .create(changeset.id, "script1", "rollback1")
+        .await?;
+    change_repo
+        .set_status(applied.id, ChangeStatus::Success)
+        .await?;
+    let in_flight = change_repo
+        .create(changeset.id, "script2", "rollback2")
+        .await?;
+    change_repo
+        .set_status(in_flight.id, ChangeStatus::Running)
+        .await?;
+    let pending = change_repo
+        .create(changeset.id, "script3", "rollback3")
+        .await?;
+
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    let found = services.deployment_service.find_interrupted().await?;
+    assert_eq!(found.deployments.len(), 1);
+    assert_eq!(
+        found.deployments[0].owner_host.as_deref(),
+        Some("build-agent")
+    );
+    assert_eq!(found.plans.len(), 1);
+
+    services.deployment_service.recover_interrupted().await?;
+
+    let deployment = services.deployment_service.get_by_id(deployment.id).await?;
+    assert_eq!(deployment.status, DeploymentStatus::Interrupted);
+    let plan = services.plan_service.get_by_id(plan.id).await?;
+    assert_eq!(plan.status, PlanStatus::Interrupted);
+    assert_eq!(
+        changeset_repo.get_by_id(changeset.id).await?.status,
+        ChangesetStatus::Interrupted
+    );
+    assert_eq!(
+        change_repo.get_by_id(applied.id).await?.status,
+        ChangeStatus::Success
+    );
+    assert_eq!(
+        change_repo.get_by_id(in_flight.id).await?.status,
+        ChangeStatus::Interrupted
+    );
+    assert_eq!(
+        change_repo.get_by_id(pending.id).await?.status,
+        ChangeStatus::Idle
+    );
+
+    assert!(
+        services
+            .deployment_service
+            .find_interrupted()
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_abort_interrupts_own_deployment_and_releases_lock() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let db = services.connection_service.get_repo().get_db().await;
+    let deployment_repo = DeploymentRepository::new(db.clone());
+    let changeset_repo = ChangesetRepository::new(db.clone());
+
+    let mut deployments = Vec::new();
+    for pid in [std::process::id() as i32, 4242] {
+        let deployment = deployment_repo
+            .create(
+                plan.id,
+                None,
+                chrono::Utc::now().naive_utc(),
+                String::new(),
+                true,
+                None,
+            )
+            .await?;
+        deployment_repo
+            .set_status(deployment.id, DeploymentStatus::Running)
+            .await?;
+        deployment_repo
+            .set_owner(deployment.id, pid, "build-agent", "ci")
+            .await?;
+        deployments.push(deployment);
+    }
+    let changeset = changeset_repo
+        .create(deployments[0].id, "TABLE", "EMP", "SCHEMA1", None, None)
+        .await?;
+    changeset_repo
+        .set_status(changeset.id, ChangesetStatus::Running)
+        .await?;
+    PlanRepository::new(db.clone())
+        .set_status(plan.id, PlanStatus::Running)
+        .await?;
+    let lock = services
+        .lock_service
+        .acquire(&plan, "apply", CancelToken::new())
+        .await?;
+
+    services.deployment_service.abort(plan.id).await?;
+
+    let own = services
+        .deployment_service
+        .get_by_id(deployments[0].id)
+        .await?;
+    assert_eq!(own.status, DeploymentStatus::Interrupted);
+    assert_eq!(
+        changeset_repo.get_by_id(changeset.id).await?.status,
+        ChangesetStatus::Interrupted
+    );
+    // Deployments of other processes are left to them
+    let other = services
+        .deployment_service
+        .get_by_id(deployments[1].id)
+        .await?;
+    assert_eq!(other.status, DeploymentStatus::Running);
+    let plan = services.plan_service.get_by_id(plan.id).await?;
+    assert_eq!(plan.status, PlanStatus::Interrupted);
+    assert!(services.lock_service.get_all().await?.is_empty());
+    drop(lock);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_apply_cancelled_deployment() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+
+    let deployment_id = services
+        .deployment_service
+        .prepare(
+            plan.id,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+
+    // Cancelled before the first statement, nothing is applied
+    let token = CancelToken::new();
+    token.cancel();
+    let res = services
+        .deployment_service
+        .apply(
+            deployment_id,
+            true,
+            Some(true),
+            DriftPolicy::Abort,
+            &mut DeploymentContext::default().with_cancel_token(token),
+        )
+        .await;
+    let err = res.expect_err("Apply should be cancelled");
+    let cancelled = err
+        .downcast_ref::<DeploymentCancelledError>()
+        .expect("Cancelled error is not returned");
+    let change_count = services
+        .deployment_service
+        .get_change_count_by_deployment_id(deployment_id)
+        .await?;
+    assert_eq!(cancelled.not_applied as u64, change_count);
+
+    let deployment = services.deployment_service.get_by_id(deployment_id).await?;
+    assert_eq!(deployment.status, DeploymentStatus::Cancelled);
+    let plan = services.plan_service.get_by_id(plan.id).await?;
+    assert_eq!(plan.status, PlanStatus::Cancelled);
+    let changes = services
+        .deployment_service
+        .find_changes_by_deployment_id(deployment_id)
+        .await?;
+    assert!(changes.iter().all(|c| c.status == ChangeStatus::Cancelled));
+
+    // A cancelled deployment can be resumed
+    services
+        .deployment_service
+        .apply(
+            deployment_id,
+            true,
+            Some(true),
+            DriftPolicy::Abort,
+            &mut DeploymentContext::default(),
+        )
+        .await?;
+    check_schema1_emp_deployed(&deployment, &services).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_find_plan_drift() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+
+    let deployment_id = services
+        .deployment_service
+        .run(
+            plan.id,
+            false,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+
+    let drifted = services
+        .deployment_service
+        .find_plan_drift(plan.id, &ProgressReporter::new(None))
+        .await?;
+    assert!(drifted.is_empty());
+
+    // Change a deployed object behind leaf's back
+    let deployment = services.deployment_service.get_by_id(deployment_id).await?;
+    let client = get_target_client(&deployment, &services).await?;
+    client
+        .execute("ALTER TABLE SCHEMA1.EMP ADD (UNEXPECTED NUMBER)")
+        .await?;
+
+    let drifted = services
+        .deployment_service
+        .find_plan_drift(plan.id, &ProgressReporter::new(None))
+        .await?;
+    assert_eq!(drifted.len(), 1);
+    assert_eq!(drifted[0].object_name, "EMP");
+    assert_eq!(drifted[0].kind, DriftKind::Altered);
+    assert_eq!(drifted[0].deployment_id, deployment_id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replace_changes_of_changeset() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+    let db = services.connection_service.get_repo().get_db().await;
+    let deployment_repo = DeploymentRepository::new(db.clone());
+    let changeset_repo = ChangesetRepository::new(db.clone());
+    let change_repo = ChangeRepository::new(db.clone());
+
+    let deployment = deployment_repo
+        .create(
+            plan.id,
+            None,
+            chrono::Utc::now().naive_utc(),
+            String::new(),
+            true,
+            None,
+        )
+        .await?;
+    let changeset = changeset_repo
+        .create(
+            deployment.id,
+            "TABLE",
+            "EMP",
+            "SCHEMA1",
+            Some("CREATE TABLE EMP (ID NUMBER, NAME VARCHAR2(100))"),
+            None,
+        )
+        .await?;
+    for i in 1..=2 {
+        change_repo
+            .create(
+                changeset.id,
+                &format!("script{}", i),
+                &format!("rollback{}", i),
+            )
+            .await?;
+    }
+
+    // The target got the table after the deployment was prepared
+    let mut active = changeset.clone().into_active_model();
+    active.target_ddl = Set(Some("CREATE TABLE EMP (ID NUMBER)".to_string()));
+    let saved = changeset_repo
+        .replace_changes(&active, &[("alter".to_string(), "revert".to_string())])
+        .await?;
+
+    assert_eq!(saved.id, changeset.id);
+    assert_eq!(
+        changeset_repo
+            .get_by_id(changeset.id)
+            .await?
+            .target_ddl
+            .as_deref(),
+        Some("CREATE TABLE EMP (ID NUMBER)")
+    );
+    let changes = change_repo.get_by_changeset_id(changeset.id).await?;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].script, "alter");
+    assert_eq!(changes[0].rollback_script, "revert");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_find_object_history() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let db = services.connection_service.get_repo().get_db().await;
+    let deployment_repo = DeploymentRepository::new(db.clone());
+    let changeset_repo = ChangesetRepository::new(db.clone());
+    let change_repo = ChangeRepository::new(db.clone());
+
+    // Two plans that both deployed SCHEMA1.EMP
+    for (name, source_ddl, target_ddl) in [
+        ("first", "CREATE TABLE EMP (ID NUMBER)", None),
+        (
+            "second",
+            "CREATE TABLE EMP (ID NUMBER, NAME VARCHAR2(100))",
+            Some("CREATE TABLE EMP (ID NUMBER)"),
+        ),
+    ] {
+        let plan = services
+            .plan_service
+            .create(&PlanSpec {
+                name: name.to_string(),
+                source: "source".to_string(),
+                target: "target".to_string(),
+                schemas: vec!["SCHEMA1".to_string()],
+                disable_hooks: true,
+                ..Default::default()
+            })
+            .await?;
+        let deployment = deployment_repo
+            .create(
+                plan.id,
+                None,
+                chrono::Utc::now().naive_utc(),
+                String::new(),
+                true,
+                None,
+            )
+            .await?;
+        deployment_repo
+            .set_owner(deployment.id, 4242, "build-agent", "ci")
+            .await?;
+        let changeset = changeset_repo
+            .create(
+                deployment.id,
+                "TABLE",
+                "EMP",
+                "SCHEMA1",
+                Some(source_ddl),
+                target_ddl,
+            )
+            .await?;
+        changeset_repo
+            .set_status(changeset.id, ChangesetStatus::Success)
+            .await?;
+        change_repo
+            .create(changeset.id, source_ddl, "DROP TABLE EMP")
+            .await?;
+        changeset_repo
+            .create(deployment.id, "VIEW", "V_EMP", "SCHEMA1", None, None)
+            .await?;
+    }
+
+    let history = services
+        .deployment_service
+        .find_object_history("SCHEMA1", "EMP", Some("TABLE"))
+        .await?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].version, 1);
+    assert_eq!(history[0].plan_name, "first");
+    assert_eq!(history[1].plan_name, "second");
+    assert_eq!(
+        history[1].changeset.target_ddl.as_deref(),
+        Some("CREATE TABLE EMP (ID NUMBER)")
+    );
+    assert_eq!(history[1].changes.len(), 1);
+    assert_eq!(
+        history[1].deployment.owner_host.as_deref(),
+        Some("build-agent")
+    );
+    assert_eq!(history[1].deployment.owner_user.as_deref(), Some("ci"));
+
+    let history = services
+        .deployment_service
+        .find_object_history("SCHEMA1", "EMP", Some("VIEW"))
+        .await?;
+    assert!(history.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_purge_deployments() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let db = services.connection_service.get_repo().get_db().await;
+    let deployment_repo = DeploymentRepository::new(db.clone());
+    let changeset_repo = ChangesetRepository::new(db.clone());
+    let change_repo = ChangeRepository::new(db.clone());
+    let rollback_repo = RollbackRepository::new(db.clone());
+
+    let mut ids = Vec::new();
+    for status in [
+        DeploymentStatus::Success,
+        DeploymentStatus::Success,
+        DeploymentStatus::Error,
+    ] {
+        let deployment = deployment_repo
+            .create(
+                plan.id,
+                None,
+                chrono::Utc::now().naive_utc(),
+                String::new(),
+                true,
+                None,
+            )
+            .await?;
+        deployment_repo.set_status(deployment.id, status).await?;
+        let changeset = changeset_repo
+            .create(deployment.id, "TABLE", "EMP", "SCHEMA1", None, None)
+            .await?;
+        let change = change_repo
+            .create(changeset.id, "script", "rollback")
+            .await?;
+        rollback_repo
+            .create(change.id, "rollback".to_string(), None)
+            .await?;
+        ids.push(deployment.id);
+    }
+
+    // The newest deployment and the latest successful one are kept
+    let policy = RetentionPolicy::new(None, Some(1))?;
+    let purgeable = services
+        .deployment_service
+        .find_purgeable(&policy, Some(plan.id))
+        .await?;
+    assert_eq!(
+        purgeable.iter().map(|d| d.id).collect::<Vec<_>>(),
+        vec![ids[0]]
+    );
+
+    let summary = services.deployment_service.purge(&[ids[0]]).await?;
+    assert_eq!(summary.deployments, 1);
+    assert_eq!(summary.changesets, 1);
+    assert_eq!(summary.changes, 1);
+    assert_eq!(summary.rollbacks, 1);
+
+    assert!(deployment_repo.find_by_id(ids[0]).await?.is_none());
+    assert!(
+        changeset_repo
+            .find_by_deployment_id(ids[0])
+            .await?
+            .is_empty()
+    );
+    assert!(deployment_repo.find_by_id(ids[1]).await?.is_some());
+    assert!(
+        services
+            .deployment_service
+            .find_purgeable(&policy, Some(plan.id))
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tag_unique_per_plan() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let db = services.connection_service.get_repo().get_db().await;
+    let deployment_repo = DeploymentRepository::new(db.clone());
+
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let deployment = deployment_repo
+            .create(
+                plan.id,
+                None,
+                chrono::Utc::now().naive_utc(),
+                String::new(),
+                true,
+                None,
+            )
+            .await?;
+        ids.push(deployment.id);
+    }
+
+    services
+        .deployment_service
+        .tag_deployment(ids[0], "v1.0")
+        .await?;
+
+    // A concurrent tagger that passed the service check is stopped by the index
+    let err = deployment_repo
+        .set_tag(ids[1], Some("v1.0".to_string()))
+        .await
+        .expect_err("Duplicate tag is accepted");
+    let err = err
+        .downcast_ref::<TagInUseError>()
+        .expect("Duplicate tag is not reported as in use");
+    assert_eq!(err.deployment_id, ids[0]);
+
+    let err = services
+        .deployment_service
+        .tag_deployment(ids[1], "v1.0")
+        .await
+        .expect_err("Duplicate tag is accepted");
+    assert!(err.downcast_ref::<TagInUseError>().is_some());
+
+    // The rejected deployment is left untagged
+    assert!(deployment_repo.get_by_id(ids[1]).await?.tag.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_diff_deployments_of_different_plans() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let mut plan_ids = Vec::new();
+    for name in ["first", "second"] {
+        let plan = services
+            .plan_service
+            .create(&PlanSpec {
+                name: name.to_string(),
+                source: "source".to_string(),
+                target: "target".to_string(),
+                schemas: vec!["SCHEMA1".to_string()],
+                disable_hooks: true,
+                ..Default::default()
+            })
+            .await?;
+        plan_ids.push(plan.id);
+    }
+
+    let db = services.connection_service.get_repo().get_db().await;
+    let deployment_repo = DeploymentRepository::new(db.clone());
+    let mut ids = Vec::new();
+    for plan_id in [plan_ids[0], plan_ids[0], plan_ids[1]] {
+        let deployment = deployment_repo
+            .create(
+                plan_id,
+                None,
+                chrono::Utc::now().naive_utc(),
+                String::new(),
+                true,
+                None,
+            )
+            .await?;
+        ids.push(deployment.id);
+    }
+
+    let diff = services.deployment_service.diff(ids[0], ids[1]).await?;
+    assert!(diff.is_empty());
+
+    let err = services
+        .deployment_service
+        .diff(ids[0], ids[2])
+        .await
+        .expect_err("Deployments of different plans are compared");
+    assert!(err.to_string().contains("different plans"));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial(oracle)]
+async fn test_prepare_from_directory_of_target() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    init_plan_test(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let schemas = vec!["SCHEMA1".to_string()];
+
+    // A snapshot of the target holds the same DDLs, reformatted
+    let dir = tempfile::tempdir()?;
+    services
+        .snapshot_service
+        .export(
+            "target",
+            &schemas,
+            None,
+            None,
+            dir.path(),
+            &ProgressReporter::new(None),
+        )
+        .await?;
+    services
+        .connection_service
+        .create_directory("snapshot", dir.path(), 0)
+        .await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "snapshot".to_string(),
+            target: "target".to_string(),
+            schemas: schemas.clone(),
+            fail_fast: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let cutoff_date = chrono::NaiveDate::from_ymd_opt(2000, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let deployment_id = services
+        .deployment_service
+        .prepare(
+            plan.id,
+            cutoff_date,
+            None,
+            &mut DeploymentContext::default(),
+        )
+        .await?
+        .expect("Deployment id is not returned");
+
+    let db = services.connection_service.get_repo().get_db().await;
+    let changesets = ChangesetRepository::new(db)
+        .get_by_deployment_id(deployment_id)
+        .await?;
+    assert!(changesets.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use leaf::{
+    config::Settings,
+    entities::PlanModel,
+    errors::{LockHeldError, LockLostError},
+    services::AppServices,
+    utils::CancelToken,
+};
+
+use crate::common::{TestDatabase, init_repo, load_test_env};
+
+async fn init_services(stale_after_secs: u64) -> Result<(TestDatabase, AppServices)> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+    settings.locks.stale_after_secs = stale_after_secs;
+    settings.locks.heartbeat_interval_secs = 1;
+
+    let services = AppServices::new(&settings).await?;
+    init_repo(&services.connection_service.get_repo().get_db().await).await?;
+    Ok((database, services))
+}
+
+fn plan(id: i32, target_connection_id: i32) -> PlanModel {
+    PlanModel {
+        id,
+        target_connection_id,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_lock_is_exclusive_per_plan_and_target() -> Result<()> {
+    let (_database, services) = init_services(120).await?;
+    let locks = &services.lock_service;
+
+    let lock = locks
+        .acquire(&plan(1, 10), "apply", CancelToken::new())
+        .await?;
+
+    // Same plan
+    let err = locks
+        .acquire(&plan(1, 10), "prepare", CancelToken::new())
+        .await
+        .err()
+        .expect("Lock should be held");
+    let held = err
+        .downcast_ref::<LockHeldError>()
+        .expect("Lock error is not returned");
+    assert_eq!(held.plan_id, 1);
+    assert_eq!(held.operation, "apply");
+    assert_eq!(held.owner_pid, std::process::id() as i32);
+
+    // Another plan deploying to the same target
+    let res = locks
+        .acquire(&plan(2, 10), "apply", CancelToken::new())
+        .await;
+    assert!(res.is_err());
+
+    // Another plan and target
+    let other = locks
+        .acquire(&plan(3, 11), "apply", CancelToken::new())
+        .await?;
+    assert_eq!(locks.get_all().await?.len(), 2);
+
+    // Live locks are only released with force
+    assert!(locks.release(lock.id(), false).await.is_err());
+
+    lock.release().await?;
+    other.release().await?;
+    assert!(locks.get_all().await?.is_empty());
+
+    let lock = locks
+        .acquire(&plan(2, 10), "apply", CancelToken::new())
+        .await?;
+    lock.release().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stale_lock_is_taken_over() -> Result<()> {
+    let (_database, services) = init_services(0).await?;
+    let locks = &services.lock_service;
+
+    let stale = locks
+        .acquire(&plan(1, 10), "apply", CancelToken::new())
+        .await?;
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    let all = locks.get_all().await?;
+    assert_eq!(all.len(), 1);
+    assert!(locks.is_stale(&all[0]));
+
+    let lock = locks
+        .acquire(&plan(1, 10), "apply", CancelToken::new())
+        .await?;
+    assert_ne!(lock.id(), stale.id());
+    assert_eq!(locks.get_all().await?.len(), 1);
+
+    lock.release().await?;
+    drop(stale);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lost_lock_cancels_the_operation() -> Result<()> {
+    let (_database, services) = init_services(120).await?;
+    let locks = &services.lock_service;
+
+    let token = CancelToken::new();
+    let lock = locks.acquire(&plan(1, 10), "apply", token.clone()).await?;
+
+    // Another process force-releases the lock, the next heartbeat notices it
+    locks.release(lock.id(), true).await?;
+    tokio::time::sleep(locks.heartbeat_interval() + std::time::Duration::from_millis(500)).await;
+
+    assert!(token.is_cancelled());
+    assert!(lock.is_lost());
+    let err = lock.release().await.expect_err("Release should fail");
+    assert!(err.is::<LockLostError>());
+
+    Ok(())
+}
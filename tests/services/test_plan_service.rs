@@ -1,13 +1,12 @@
 use anyhow::Result;
 use leaf::{
     config::Settings,
-    services::AppServices,
-    types::{PlanStatus, StringList},
+    services::{AppServices, ListEdit, PlanEdit, PlanSpec},
+    types::{AuditOperation, AuditOutcome, PlanStatus, StringList},
 };
-use tempfile::NamedTempFile;
 
 use crate::common::{
-    cleanup, create_source_objects, create_source_test_client, create_target_objects,
+    TestDatabase, cleanup, create_source_objects, create_source_test_client, create_target_objects,
     create_target_test_client, init_repo, load_test_env,
 };
 
@@ -29,6 +28,7 @@ pub async fn create_connections(settings: &Settings) -> Result<()> {
             username.as_str(),
             password.as_str(),
             connection_string.as_str(),
+            0,
         )
         .await?;
 
@@ -44,6 +44,7 @@ pub async fn create_connections(settings: &Settings) -> Result<()> {
             username.as_str(),
             password.as_str(),
             connection_string.as_str(),
+            0,
         )
         .await?;
 
@@ -68,9 +69,10 @@ pub async fn init_plan_test(settings: &Settings) -> Result<()> {
 
 #[tokio::test]
 async fn test_create_plan() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     create_connections(&settings).await?;
 
@@ -78,19 +80,14 @@ async fn test_create_plan() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await?;
 
     assert_eq!(plan.name, "test");
@@ -104,37 +101,27 @@ async fn test_create_plan() -> Result<()> {
 
     let res = services
         .plan_service
-        .create(
-            "TEST",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "TEST".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await;
     assert!(res.is_err());
 
     let res = services
         .plan_service
-        .create(
-            "valid",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "valid".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await;
     assert!(res.is_ok());
     assert_eq!(res.unwrap().name, "valid");
@@ -144,9 +131,10 @@ async fn test_create_plan() -> Result<()> {
 
 #[tokio::test]
 async fn test_find_by_name() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     create_connections(&settings).await?;
 
@@ -154,19 +142,14 @@ async fn test_find_by_name() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await?;
 
     assert_eq!(plan.name, "test");
@@ -185,9 +168,10 @@ async fn test_find_by_name() -> Result<()> {
 
 #[tokio::test]
 async fn test_get_by_id() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     create_connections(&settings).await?;
 
@@ -195,19 +179,14 @@ async fn test_get_by_id() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await?;
 
     assert_eq!(plan.name, "test");
@@ -226,9 +205,10 @@ async fn test_get_by_id() -> Result<()> {
 
 #[tokio::test]
 async fn test_get_all() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     create_connections(&settings).await?;
 
@@ -236,19 +216,14 @@ async fn test_get_all() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await?;
 
     assert_eq!(plan.name, "test");
@@ -268,9 +243,10 @@ async fn test_get_all() -> Result<()> {
 
 #[tokio::test]
 async fn test_get_by_status() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     create_connections(&settings).await?;
 
@@ -278,19 +254,14 @@ async fn test_get_by_status() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await?;
 
     assert_eq!(plan.name, "test");
@@ -331,9 +302,10 @@ async fn test_get_by_status() -> Result<()> {
 
 #[tokio::test]
 async fn test_prune() -> Result<()> {
-    let file = NamedTempFile::new()?;
+    load_test_env();
+    let database = TestDatabase::new().await?;
     let mut settings = Settings::new()?;
-    settings.database.url = "sqlite://".to_string() + file.path().to_str().unwrap();
+    settings.database.url = database.url.clone();
 
     create_connections(&settings).await?;
 
@@ -341,19 +313,14 @@ async fn test_prune() -> Result<()> {
 
     let plan = services
         .plan_service
-        .create(
-            "test",
-            "source",
-            "target",
-            &["SCHEMA1".to_string()],
-            None,
-            None,
-            None,
-            false,
-            false,
-            true,
-            None,
-        )
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
         .await?;
     assert_eq!(plan.name, "test");
     assert_eq!(plan.source_connection_id > 0, true);
@@ -371,3 +338,266 @@ async fn test_prune() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_required_approvals() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(plan.required_approvals, 0);
+    assert_eq!(
+        services
+            .deployment_service
+            .required_approvals(&plan)
+            .await?,
+        0
+    );
+
+    let plan = services
+        .plan_service
+        .set_required_approvals("test", 1)
+        .await?;
+    assert_eq!(plan.required_approvals, 1);
+
+    // The stricter of the plan and target connection requirements wins
+    services
+        .connection_service
+        .set_required_approvals("target", 2)
+        .await?;
+    assert_eq!(
+        services
+            .deployment_service
+            .required_approvals(&plan)
+            .await?,
+        2
+    );
+
+    let res = services
+        .plan_service
+        .set_required_approvals("test", -1)
+        .await;
+    assert!(res.is_err());
+
+    // Required approvals are stored with the new plan, a rejected value creates nothing
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "guarded".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            required_approvals: 3,
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(plan.required_approvals, 3);
+
+    let res = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "negative".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            required_approvals: -1,
+            ..Default::default()
+        })
+        .await;
+    assert!(res.is_err());
+    assert!(
+        services
+            .plan_service
+            .find_by_name("negative")
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reset_is_audited() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string()],
+            disable_hooks: true,
+            ..Default::default()
+        })
+        .await?;
+    services
+        .plan_service
+        .set_status_by_name("test", PlanStatus::Running)
+        .await?;
+    services.plan_service.reset_status_by_id(plan.id).await?;
+
+    let events = services
+        .audit_service
+        .find(None, Some(plan.id), None)
+        .await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].operation, AuditOperation::Reset);
+    assert_eq!(events[0].outcome, AuditOutcome::Success);
+    assert!(events[0].ended_at.is_some());
+    assert!(!events[0].hostname.is_empty());
+    assert_eq!(events[0].leaf_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(events[0].settings["database"]["url"], settings.database.url);
+
+    let since = chrono::Utc::now().naive_utc() + chrono::Duration::days(1);
+    assert!(
+        services
+            .audit_service
+            .find(Some(since), None, None)
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_edit_plan_records_revisions() -> Result<()> {
+    load_test_env();
+    let database = TestDatabase::new().await?;
+    let mut settings = Settings::new()?;
+    settings.database.url = database.url.clone();
+
+    create_connections(&settings).await?;
+
+    let services = AppServices::new(&settings).await?;
+    let plan = services
+        .plan_service
+        .create(&PlanSpec {
+            name: "test".to_string(),
+            source: "source".to_string(),
+            target: "target".to_string(),
+            schemas: vec!["SCHEMA1".to_string(), "SCHEMA2".to_string()],
+            exclude_object_names: Some(vec!["TMP_LOAD".to_string()]),
+            disable_all_drops: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let edit = PlanEdit {
+        rename: Some("renamed".to_string()),
+        schemas: ListEdit {
+            add: vec!["SCHEMA3".to_string()],
+            remove: vec!["SCHEMA1".to_string()],
+        },
+        exclude_object_names: ListEdit {
+            add: vec![],
+            remove: vec!["TMP_LOAD".to_string()],
+        },
+        fail_fast: Some(true),
+        add_hooks: vec![(
+            "pre_apply_deployment".to_string(),
+            "begin null; end;".to_string(),
+        )],
+        ..Default::default()
+    };
+    let (updated, changed) = services.plan_service.edit("TEST", &edit).await?;
+    assert!(changed);
+    assert_eq!(updated.id, plan.id);
+    assert_eq!(updated.name, "renamed");
+    assert_eq!(updated.get_schemas(), vec!["SCHEMA2", "SCHEMA3"]);
+    assert!(
+        updated
+            .get_exclude_object_names()
+            .unwrap_or_default()
+            .is_empty()
+    );
+    assert!(updated.fail_fast);
+    assert_eq!(
+        updated.get_hooks()?.unwrap().pre_apply_deployment,
+        Some(vec!["begin null; end;".to_string()])
+    );
+
+    // Editing to the same values records no revision
+    let (_, changed) = services
+        .plan_service
+        .edit(
+            "renamed",
+            &PlanEdit {
+                fail_fast: Some(true),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert!(!changed);
+
+    services
+        .plan_service
+        .set_required_approvals("renamed", 1)
+        .await?;
+
+    let revisions = services.plan_service.find_revisions("renamed").await?;
+    assert_eq!(
+        revisions.iter().map(|r| r.revision).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(revisions[0].name, "test");
+    assert_eq!(revisions[0].changed_fields, Some(StringList::new()));
+    assert!(!revisions[0].is_reconstructed());
+    assert_eq!(
+        revisions[1].changed_fields,
+        Some(StringList(
+            [
+                "name",
+                "schemas",
+                "exclude_object_names",
+                "fail_fast",
+                "hooks"
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+        ))
+    );
+    assert_eq!(revisions[2].required_approvals, 1);
+
+    // Connections are validated like on creation
+    let swap = PlanEdit {
+        target: Some("source".to_string()),
+        ..Default::default()
+    };
+    assert!(services.plan_service.edit("renamed", &swap).await.is_err());
+
+    services
+        .plan_service
+        .set_status_by_name("renamed", PlanStatus::Running)
+        .await?;
+    assert!(services.plan_service.edit("renamed", &edit).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use leaf::{
+    config::Settings,
+    services::{AppServices, DiffOptions},
+    types::DeltaOperation,
+    utils::{DeploymentContext, deployment_context::DeploymentContextOptions},
+};
+
+use crate::common::load_test_env;
+
+fn write(dir: &std::path::Path, path: &str, ddl: &str) -> Result<()> {
+    let file = dir.join(path);
+    std::fs::create_dir_all(file.parent().unwrap())?;
+    std::fs::write(file, ddl)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_diff_snapshot_directories() -> Result<()> {
+    load_test_env();
+    let settings = Settings::new()?;
+    let services = AppServices::new(&settings).await?;
+
+    let from = tempfile::tempdir()?;
+    let to = tempfile::tempdir()?;
+    write(
+        from.path(),
+        "HR/VIEW/V_EMP.sql",
+        "CREATE OR REPLACE VIEW V_EMP AS SELECT 1 A FROM DUAL",
+    )?;
+    write(
+        to.path(),
+        "HR/VIEW/V_EMP.sql",
+        "CREATE OR REPLACE VIEW V_EMP AS SELECT 2 A FROM DUAL",
+    )?;
+    write(
+        from.path(),
+        "HR/VIEW/V_OLD.sql",
+        "CREATE VIEW V_OLD AS SELECT 1 A FROM DUAL",
+    )?;
+    write(
+        to.path(),
+        "HR/PROCEDURE/P_NEW.sql",
+        "CREATE OR REPLACE PROCEDURE P_NEW AS BEGIN NULL; END;",
+    )?;
+    write(
+        from.path(),
+        "HR/TABLE/EMP.sql",
+        "CREATE TABLE EMP (ID NUMBER)",
+    )?;
+    write(
+        to.path(),
+        "HR/TABLE/EMP.sql",
+        "CREATE TABLE EMP (ID  NUMBER)\n",
+    )?;
+
+    let mut ctx = DeploymentContext::new(Some(DeploymentContextOptions::new(
+        true, true, None, None, None,
+    )))?;
+    let count = services.snapshot_service.diff(
+        from.path(),
+        to.path(),
+        DiffOptions {
+            disable_all_drops: false,
+            ..Default::default()
+        },
+        &mut ctx,
+    )?;
+
+    let operations: Vec<(DeltaOperation, &str)> = ctx
+        .deltas()
+        .iter()
+        .map(|d| (d.operation(), d.object_name.as_str()))
+        .collect();
+    assert_eq!(count, 3);
+    assert_eq!(
+        operations,
+        vec![
+            (DeltaOperation::Create, "P_NEW"),
+            (DeltaOperation::Replace, "V_EMP"),
+            (DeltaOperation::Drop, "V_OLD"),
+        ]
+    );
+    let scripts = ctx.script_content().unwrap();
+    assert!(scripts.contains("SELECT 2 A FROM DUAL"));
+    assert!(scripts.contains("DROP VIEW HR.V_OLD"));
+    assert!(
+        ctx.summary_content()
+            .unwrap()
+            .contains("DROP VIEW HR.V_OLD")
+    );
+
+    Ok(())
+}